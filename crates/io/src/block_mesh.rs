@@ -0,0 +1,168 @@
+//! JSON block-mesh descriptions.
+//!
+//! A block-mesh file mirrors OpenFOAM's `blockMeshDict` with straight edges
+//! and `simpleGrading`:
+//!
+//! ```json
+//! {
+//!   "vertices": [[0, 0, 0], [1, 0, 0], [1, 1, 0], [0, 1, 0],
+//!                [0, 0, 0.1], [1, 0, 0.1], [1, 1, 0.1], [0, 1, 0.1]],
+//!   "blocks": [
+//!     { "vertices": [0, 1, 2, 3, 4, 5, 6, 7], "cells": [20, 20, 1], "grading": [1, 1, 1] }
+//!   ],
+//!   "patches": [
+//!     { "name": "movingWall", "type": "wall", "faces": [[3, 7, 6, 2]] }
+//!   ]
+//! }
+//! ```
+//!
+//! `grading` defaults to `[1, 1, 1]` and `patches` to an empty list.
+
+use std::path::Path;
+
+use dugong_mesh::{BlockMesh, BlockPatch, HexBlock};
+use dugong_types::tensor::Vector;
+use serde::Deserialize;
+
+use crate::error::IoError;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockMeshFile {
+    vertices: Vec<[f64; 3]>,
+    blocks: Vec<BlockEntry>,
+    #[serde(default)]
+    patches: Vec<PatchEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockEntry {
+    vertices: [usize; 8],
+    cells: [usize; 3],
+    #[serde(default = "unit_grading")]
+    grading: [f64; 3],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatchEntry {
+    name: String,
+    #[serde(rename = "type")]
+    patch_type: String,
+    faces: Vec<[usize; 4]>,
+}
+
+fn unit_grading() -> [f64; 3] {
+    [1.0; 3]
+}
+
+impl From<BlockMeshFile> for BlockMesh {
+    fn from(file: BlockMeshFile) -> Self {
+        let vertices = file
+            .vertices
+            .into_iter()
+            .map(|[x, y, z]| Vector::new(x, y, z))
+            .collect();
+        let blocks = file
+            .blocks
+            .into_iter()
+            .map(|b| HexBlock::new(b.vertices, b.cells).with_grading(b.grading))
+            .collect();
+        let patches = file
+            .patches
+            .into_iter()
+            .map(|p| BlockPatch::new(p.name, p.patch_type, p.faces))
+            .collect();
+        BlockMesh::new(vertices, blocks, patches)
+    }
+}
+
+/// Parses a JSON block-mesh description from a string.
+///
+/// # Errors
+///
+/// Returns `Err` if the text is not valid JSON or does not match the
+/// block-mesh schema.
+pub fn parse_block_mesh(text: &str) -> Result<BlockMesh, serde_json::Error> {
+    let file: BlockMeshFile = serde_json::from_str(text)?;
+    Ok(file.into())
+}
+
+/// Reads a JSON block-mesh description from `path`.
+///
+/// The returned [`BlockMesh`] is not yet meshed; call [`BlockMesh::build`]
+/// to generate the mesh and its patches.
+///
+/// # Errors
+///
+/// Returns `Err` if the file cannot be read or fails to parse.
+pub fn read_block_mesh(path: impl AsRef<Path>) -> Result<BlockMesh, IoError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|source| IoError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_block_mesh(&text).map_err(|source| IoError::Json {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// L-shaped cavity: three blocks around a re-entrant corner.
+    const L_SHAPE: &str = r#"{
+        "vertices": [
+            [0, 0, 0], [1, 0, 0], [2, 0, 0],
+            [0, 1, 0], [1, 1, 0], [2, 1, 0],
+            [0, 2, 0], [1, 2, 0],
+            [0, 0, 1], [1, 0, 1], [2, 0, 1],
+            [0, 1, 1], [1, 1, 1], [2, 1, 1],
+            [0, 2, 1], [1, 2, 1]
+        ],
+        "blocks": [
+            { "vertices": [0, 1, 4, 3, 8, 9, 12, 11], "cells": [2, 2, 1] },
+            { "vertices": [1, 2, 5, 4, 9, 10, 13, 12], "cells": [2, 2, 1], "grading": [0.5, 1, 1] },
+            { "vertices": [3, 4, 7, 6, 11, 12, 15, 14], "cells": [2, 2, 1] }
+        ],
+        "patches": [
+            { "name": "lid", "type": "wall", "faces": [[6, 7, 15, 14]] },
+            { "name": "frontAndBack", "type": "empty", "faces": [
+                [0, 1, 4, 3], [1, 2, 5, 4], [3, 4, 7, 6],
+                [8, 9, 12, 11], [9, 10, 13, 12], [11, 12, 15, 14]
+            ] }
+        ]
+    }"#;
+
+    #[test]
+    fn parse_l_shape_builds_mesh() {
+        let bm = parse_block_mesh(L_SHAPE).unwrap();
+        assert_eq!(bm.blocks.len(), 3);
+        assert_eq!(bm.blocks[1].grading, [0.5, 1.0, 1.0]);
+        assert_eq!(bm.blocks[0].grading, [1.0, 1.0, 1.0]);
+        let (mesh, patches) = bm.build().unwrap();
+        assert_eq!(mesh.n_cells(), 12);
+        let total: f64 = mesh.cell_volumes().iter().sum();
+        assert!((total - 3.0).abs() < 1e-12, "total volume {total}");
+        let names: Vec<&str> = patches.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["lid", "frontAndBack", "defaultFaces"]);
+        assert_eq!(patches[0].size, 2);
+        assert_eq!(patches[1].size, 24);
+        assert_eq!(patches[2].size, 14);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_field() {
+        let text = r#"{ "vertices": [], "blocks": [], "edges": [] }"#;
+        assert!(parse_block_mesh(text).is_err());
+    }
+
+    #[test]
+    fn read_missing_file_returns_io_error() {
+        let result = read_block_mesh("/nonexistent/blockMesh.json");
+        assert!(matches!(result, Err(IoError::Io { .. })));
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use dugong_mesh::MeshError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IoError {
+    #[error("{path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{path}: {source}")]
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("{path}:{line}: {message}")]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    #[error("{path}: {source}")]
+    Mesh { path: PathBuf, source: MeshError },
+    #[error("{path}: array {name} has {got} values, expected {expected}")]
+    FieldLength {
+        path: PathBuf,
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    #[error("{path}: label {label} does not fit in {bits} bits")]
+    LabelOverflow {
+        path: PathBuf,
+        label: usize,
+        bits: usize,
+    },
+    #[error("{path}: patch index {patch} does not fit the 16-bit STL attribute")]
+    StlPatchIndex { path: PathBuf, patch: usize },
+}
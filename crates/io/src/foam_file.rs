@@ -0,0 +1,723 @@
+//! Low-level reading of OpenFOAM `FoamFile` data files.
+//!
+//! A file starts with an optional `FoamFile { ... }` header dictionary whose
+//! `format` (`ascii` or `binary`), `class` and `arch` entries determine how
+//! the body is encoded. Lists are written as `N ( ... )`; in binary files,
+//! lists of fixed-size items (labels, scalars, vectors) carry `N` items of
+//! raw little-endian data between the parentheses.
+//!
+//! [`FoamReader`] decodes such files and [`FoamWriter`] produces them.
+
+use std::path::Path;
+
+use dugong_mesh::CompactList;
+use dugong_types::tensor::Vector;
+
+use crate::error::IoError;
+
+/// Largest uniform list `N { item }` expanded in memory. A uniform list takes
+/// a few bytes in the file whatever `N` is, so its size is bounded here
+/// rather than by the data.
+const MAX_UNIFORM_LIST_SIZE: usize = 1 << 26;
+
+/// Body encoding of a `FoamFile`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FoamFormat {
+    /// Human-readable text.
+    #[default]
+    Ascii,
+    /// Raw little-endian data inside list parentheses.
+    Binary,
+}
+
+/// The `FoamFile` header entries relevant for decoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FoamHeader {
+    pub format: FoamFormat,
+    /// `class` entry, e.g. `vectorField` or `faceCompactList`; empty if absent.
+    pub class: String,
+    /// Size of a binary label in bytes (from `arch`, default 4).
+    pub label_bytes: usize,
+    /// Size of a binary scalar in bytes (from `arch`, default 8).
+    pub scalar_bytes: usize,
+}
+
+impl Default for FoamHeader {
+    fn default() -> Self {
+        Self {
+            format: FoamFormat::default(),
+            class: String::new(),
+            label_bytes: 4,
+            scalar_bytes: 8,
+        }
+    }
+}
+
+/// A lexical token of the ASCII parts of a file.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Token {
+    /// A bare word or number.
+    Word(String),
+    /// A double-quoted string, without the quotes.
+    Str(String),
+    /// One of `( ) { } ; [ ]`.
+    Punct(u8),
+}
+
+/// Cursor over the bytes of one file, tracking the current line for error
+/// messages.
+pub(crate) struct FoamReader<'a> {
+    path: &'a Path,
+    data: &'a [u8],
+    pos: usize,
+    line: usize,
+    header: FoamHeader,
+}
+
+impl<'a> FoamReader<'a> {
+    /// Creates a reader and parses the `FoamFile` header if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the header is malformed.
+    pub fn new(path: &'a Path, data: &'a [u8]) -> Result<Self, IoError> {
+        let mut reader = Self {
+            path,
+            data,
+            pos: 0,
+            line: 1,
+            header: FoamHeader::default(),
+        };
+        reader.skip_space();
+        if reader.data[reader.pos..].starts_with(b"FoamFile") {
+            reader.read_header()?;
+        }
+        Ok(reader)
+    }
+
+    /// Returns a parse error at the current line.
+    pub fn error(&self, message: impl Into<String>) -> IoError {
+        IoError::Parse {
+            path: self.path.to_path_buf(),
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    fn read_header(&mut self) -> Result<(), IoError> {
+        self.expect_word("FoamFile")?;
+        self.expect_punct(b'{')?;
+        loop {
+            let key = match self.next_token()? {
+                Token::Punct(b'}') => break,
+                Token::Word(w) => w,
+                other => return Err(self.error(format!("unexpected {other:?} in header"))),
+            };
+            let value = self.read_entry_value()?;
+            match key.as_str() {
+                "format" => {
+                    self.header.format = match value.as_str() {
+                        "ascii" => FoamFormat::Ascii,
+                        "binary" => FoamFormat::Binary,
+                        other => return Err(self.error(format!("unknown format `{other}`"))),
+                    }
+                }
+                "class" => self.header.class = value,
+                "arch" => self.parse_arch(&value)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads tokens up to the terminating `;` and joins them with spaces.
+    pub fn read_entry_value(&mut self) -> Result<String, IoError> {
+        let mut parts = Vec::new();
+        loop {
+            match self.next_token()? {
+                Token::Punct(b';') => break,
+                Token::Word(w) | Token::Str(w) => parts.push(w),
+                Token::Punct(c) => parts.push((c as char).to_string()),
+            }
+        }
+        Ok(parts.join(" "))
+    }
+
+    /// Parses an `arch` string such as `LSB;label=32;scalar=64`.
+    fn parse_arch(&mut self, arch: &str) -> Result<(), IoError> {
+        for item in arch.split(';') {
+            let Some((key, bits)) = item.split_once('=') else {
+                if item == "MSB" {
+                    return Err(self.error("big-endian binary files are not supported"));
+                }
+                continue;
+            };
+            let bytes = match bits.trim() {
+                "32" => 4,
+                "64" => 8,
+                other => return Err(self.error(format!("unsupported {key} size `{other}`"))),
+            };
+            match key.trim() {
+                "label" => self.header.label_bytes = bytes,
+                "scalar" => self.header.scalar_bytes = bytes,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips whitespace and `//` / `/* */` comments.
+    fn skip_space(&mut self) {
+        while self.pos < self.data.len() {
+            let rest = &self.data[self.pos..];
+            if rest[0] == b'\n' {
+                self.line += 1;
+                self.pos += 1;
+            } else if rest[0].is_ascii_whitespace() {
+                self.pos += 1;
+            } else if rest.starts_with(b"//") {
+                while self.pos < self.data.len() && self.data[self.pos] != b'\n' {
+                    self.pos += 1;
+                }
+            } else if rest.starts_with(b"/*") {
+                self.pos += 2;
+                while self.pos < self.data.len() && !self.data[self.pos..].starts_with(b"*/") {
+                    if self.data[self.pos] == b'\n' {
+                        self.line += 1;
+                    }
+                    self.pos += 1;
+                }
+                self.pos = (self.pos + 2).min(self.data.len());
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns `true` if only whitespace and comments remain.
+    pub fn at_end(&mut self) -> bool {
+        self.skip_space();
+        self.pos >= self.data.len()
+    }
+
+    /// Returns the next token without consuming it.
+    pub fn peek_token(&mut self) -> Result<Token, IoError> {
+        let (pos, line) = (self.pos, self.line);
+        let token = self.next_token();
+        self.pos = pos;
+        self.line = line;
+        token
+    }
+
+    /// Consumes and returns the next token.
+    pub fn next_token(&mut self) -> Result<Token, IoError> {
+        self.skip_space();
+        let Some(&c) = self.data.get(self.pos) else {
+            return Err(self.error("unexpected end of file"));
+        };
+        match c {
+            b'(' | b')' | b'{' | b'}' | b';' | b'[' | b']' => {
+                self.pos += 1;
+                Ok(Token::Punct(c))
+            }
+            b'"' => {
+                self.pos += 1;
+                let start = self.pos;
+                while self.pos < self.data.len() && self.data[self.pos] != b'"' {
+                    if self.data[self.pos] == b'\n' {
+                        self.line += 1;
+                    }
+                    self.pos += 1;
+                }
+                if self.pos >= self.data.len() {
+                    return Err(self.error("unterminated string"));
+                }
+                let s = String::from_utf8_lossy(&self.data[start..self.pos]).into_owned();
+                self.pos += 1;
+                Ok(Token::Str(s))
+            }
+            _ => {
+                let start = self.pos;
+                while self.pos < self.data.len() && !is_delimiter(self.data[self.pos]) {
+                    self.pos += 1;
+                }
+                Ok(Token::Word(
+                    String::from_utf8_lossy(&self.data[start..self.pos]).into_owned(),
+                ))
+            }
+        }
+    }
+
+    /// Consumes the punctuation character `c`.
+    pub fn expect_punct(&mut self, c: u8) -> Result<(), IoError> {
+        match self.next_token()? {
+            Token::Punct(p) if p == c => Ok(()),
+            other => Err(self.error(format!("expected `{}`, found {other:?}", c as char))),
+        }
+    }
+
+    /// Consumes the word `word`.
+    pub fn expect_word(&mut self, word: &str) -> Result<(), IoError> {
+        match self.next_token()? {
+            Token::Word(w) if w == word => Ok(()),
+            other => Err(self.error(format!("expected `{word}`, found {other:?}"))),
+        }
+    }
+
+    /// Consumes a bare word.
+    pub fn read_word(&mut self) -> Result<String, IoError> {
+        match self.next_token()? {
+            Token::Word(w) => Ok(w),
+            other => Err(self.error(format!("expected a word, found {other:?}"))),
+        }
+    }
+
+    /// Consumes an ASCII integer.
+    pub fn read_label(&mut self) -> Result<i64, IoError> {
+        let word = self.read_word()?;
+        word.parse()
+            .map_err(|_| self.error(format!("expected an integer, found `{word}`")))
+    }
+
+    /// Consumes an ASCII non-negative integer.
+    pub fn read_index(&mut self) -> Result<usize, IoError> {
+        let word = self.read_word()?;
+        word.parse()
+            .map_err(|_| self.error(format!("expected a non-negative integer, found `{word}`")))
+    }
+
+    /// Consumes an ASCII floating-point number.
+    pub fn read_scalar(&mut self) -> Result<f64, IoError> {
+        let word = self.read_word()?;
+        word.parse()
+            .map_err(|_| self.error(format!("expected a number, found `{word}`")))
+    }
+
+    /// Reads a list of labels, in ASCII or binary according to the header.
+    pub fn read_label_list(&mut self) -> Result<Vec<i64>, IoError> {
+        let bytes = self.header.label_bytes;
+        self.read_list(Self::read_label, bytes, |chunk| match chunk.len() {
+            4 => i32::from_le_bytes(chunk.try_into().unwrap()) as i64,
+            _ => i64::from_le_bytes(chunk.try_into().unwrap()),
+        })
+    }
+
+    /// Reads a list of non-negative labels, in ASCII or binary according to
+    /// the header.
+    pub fn read_index_list(&mut self) -> Result<Vec<usize>, IoError> {
+        let labels = self.read_label_list()?;
+        labels
+            .into_iter()
+            .map(|l| usize::try_from(l).map_err(|_| self.error(format!("negative index {l}"))))
+            .collect()
+    }
+
+    /// Reads a list of `(x y z)` vectors, in ASCII or binary according to the
+    /// header.
+    pub fn read_vector_list(&mut self) -> Result<Vec<Vector>, IoError> {
+        let scalar = self.header.scalar_bytes;
+        self.read_list(Self::read_vector, 3 * scalar, |chunk| {
+            let c: Vec<f64> = chunk.chunks_exact(scalar).map(scalar_from_le).collect();
+            Vector::new(c[0], c[1], c[2])
+        })
+    }
+
+    /// Reads an ASCII `(x y z)` vector.
+    pub fn read_vector(&mut self) -> Result<Vector, IoError> {
+        self.expect_punct(b'(')?;
+        let x = self.read_scalar()?;
+        let y = self.read_scalar()?;
+        let z = self.read_scalar()?;
+        self.expect_punct(b')')?;
+        Ok(Vector::new(x, y, z))
+    }
+
+    /// Reads a list of lists of indices (e.g. faces).
+    ///
+    /// For `faceCompactList` files the body is an offsets list followed by
+    /// a flat index list; otherwise it is a list of index lists.
+    pub fn read_compact_or_nested_list(&mut self) -> Result<Vec<Vec<usize>>, IoError> {
+        if self.header.class == "faceCompactList" {
+            let offsets = self.read_index_list()?;
+            let line = self.line;
+            let flat = self.read_index_list()?;
+            let mut lists = Vec::with_capacity(offsets.len().saturating_sub(1));
+            for w in offsets.windows(2) {
+                if w[0] > w[1] || w[1] > flat.len() {
+                    return Err(IoError::Parse {
+                        path: self.path.to_path_buf(),
+                        line,
+                        message: format!("invalid compact offsets {}..{}", w[0], w[1]),
+                    });
+                }
+                lists.push(flat[w[0]..w[1]].to_vec());
+            }
+            Ok(lists)
+        } else {
+            let n = self.read_list_size()?;
+            self.expect_punct(b'(')?;
+            // `n` is not trusted for allocation; the items must be read
+            let mut lists = Vec::new();
+            for _ in 0..n {
+                lists.push(self.read_index_list()?);
+            }
+            self.expect_punct(b')')?;
+            Ok(lists)
+        }
+    }
+
+    /// Reads the item count preceding a list.
+    pub fn read_list_size(&mut self) -> Result<usize, IoError> {
+        let word = self.read_word()?;
+        word.parse()
+            .map_err(|_| self.error(format!("expected a list size, found `{word}`")))
+    }
+
+    /// Consumes `len` raw bytes, failing with "`what` is truncated" if the
+    /// data ends first.
+    fn read_raw(&mut self, len: usize, what: &str) -> Result<&'a [u8], IoError> {
+        if len > self.data.len() - self.pos {
+            return Err(self.error(format!("{what} is truncated")));
+        }
+        let raw = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        self.line += raw.iter().filter(|&&b| b == b'\n').count();
+        Ok(raw)
+    }
+
+    /// Reads a list of fixed-size items.
+    ///
+    /// ASCII lists are `N ( item ... )` or the uniform form `N { item }`;
+    /// binary lists are `N (` followed by `N * item_bytes` raw bytes and `)`,
+    /// or `N {` followed by the raw bytes of one item and `}`. `N` is checked
+    /// against the data before anything is allocated, and uniform lists are
+    /// limited to [`MAX_UNIFORM_LIST_SIZE`] items.
+    fn read_list<T: Clone>(
+        &mut self,
+        ascii: fn(&mut Self) -> Result<T, IoError>,
+        item_bytes: usize,
+        from_bytes: impl Fn(&[u8]) -> T,
+    ) -> Result<Vec<T>, IoError> {
+        let n = self.read_list_size()?;
+        match self.next_token()? {
+            Token::Punct(b'{') => {
+                let item = if self.header.format == FoamFormat::Binary {
+                    from_bytes(self.read_raw(item_bytes, "uniform binary list item")?)
+                } else {
+                    ascii(self)?
+                };
+                self.expect_punct(b'}')?;
+                if n > MAX_UNIFORM_LIST_SIZE {
+                    return Err(self.error(format!("uniform list of {n} items is too large")));
+                }
+                Ok(vec![item; n])
+            }
+            Token::Punct(b'(') => {
+                let items = if self.header.format == FoamFormat::Binary {
+                    let what = format!("binary list of {n} items");
+                    let len = n
+                        .checked_mul(item_bytes)
+                        .ok_or_else(|| self.error(format!("{what} is truncated")))?;
+                    let raw = self.read_raw(len, &what)?;
+                    raw.chunks_exact(item_bytes).map(from_bytes).collect()
+                } else {
+                    let mut items = Vec::new();
+                    for _ in 0..n {
+                        items.push(ascii(self)?);
+                    }
+                    items
+                };
+                self.expect_punct(b')')?;
+                Ok(items)
+            }
+            other => Err(self.error(format!("expected `(` or `{{`, found {other:?}"))),
+        }
+    }
+}
+
+/// Builds the bytes of one `FoamFile`.
+///
+/// Labels are written with 32 bits unless `label_bytes` is set to 8; scalars
+/// always use 64 bits. ASCII scalars are written in Rust's shortest
+/// round-trip representation, so reading them back is exact. A label that
+/// does not fit the label size makes [`into_bytes`](Self::into_bytes) fail.
+pub(crate) struct FoamWriter {
+    format: FoamFormat,
+    label_bytes: usize,
+    data: Vec<u8>,
+    /// First label too large for `label_bytes`.
+    overflow: Option<usize>,
+}
+
+impl FoamWriter {
+    /// Starts a file with a `FoamFile` header.
+    ///
+    /// `note` is written as the header's `note` entry if given.
+    pub fn new(
+        format: FoamFormat,
+        label_bytes: usize,
+        class: &str,
+        location: &str,
+        object: &str,
+        note: Option<&str>,
+    ) -> Self {
+        let format_name = match format {
+            FoamFormat::Ascii => "ascii",
+            FoamFormat::Binary => "binary",
+        };
+        let mut header = format!(
+            "FoamFile\n{{\n    version     2.0;\n    format      {format_name};\n    class       {class};\n    arch        \"LSB;label={};scalar=64\";\n",
+            8 * label_bytes
+        );
+        if let Some(note) = note {
+            header.push_str(&format!("    note        \"{note}\";\n"));
+        }
+        header.push_str(&format!(
+            "    location    \"{location}\";\n    object      {object};\n}}\n\n"
+        ));
+        Self {
+            format,
+            label_bytes,
+            data: header.into_bytes(),
+            overflow: None,
+        }
+    }
+
+    /// Appends raw text.
+    pub fn text(&mut self, text: &str) {
+        self.data.extend_from_slice(text.as_bytes());
+    }
+
+    /// Appends a list of labels.
+    pub fn label_list(&mut self, labels: &[usize]) {
+        let label_bytes = self.label_bytes;
+        let max = match label_bytes {
+            4 => i32::MAX as usize,
+            _ => i64::MAX as usize,
+        };
+        if let Some(&l) = labels.iter().find(|&&l| l > max) {
+            self.overflow.get_or_insert(l);
+        }
+        self.list(
+            labels,
+            |l| l.to_string(),
+            |l, out| match label_bytes {
+                4 => out.extend_from_slice(&(*l as i32).to_le_bytes()),
+                _ => out.extend_from_slice(&(*l as i64).to_le_bytes()),
+            },
+        );
+    }
+
+    /// Appends a list of `(x y z)` vectors.
+    pub fn vector_list(&mut self, vectors: &[Vector]) {
+        self.list(
+            vectors,
+            |v| format!("({} {} {})", v.x(), v.y(), v.z()),
+            |v, out| {
+                for c in v.as_array() {
+                    out.extend_from_slice(&c.to_le_bytes());
+                }
+            },
+        );
+    }
+
+    /// Appends a list of label lists: nested `N(...)` items in ASCII, or a
+    /// compact offsets list followed by a flat label list in binary (the
+    /// `faceCompactList` layout).
+    pub fn nested_label_list(&mut self, lists: &CompactList) {
+        match self.format {
+            FoamFormat::Ascii => {
+                self.text(&format!("{}\n(\n", lists.len()));
+                for list in lists {
+                    let items: Vec<String> = list.iter().map(|l| l.to_string()).collect();
+                    self.text(&format!("{}({})\n", list.len(), items.join(" ")));
+                }
+                self.text(")\n");
+            }
+            FoamFormat::Binary => {
+                self.label_list(lists.offsets());
+                self.text("\n");
+                self.label_list(lists.values());
+            }
+        }
+    }
+
+    fn list<T>(
+        &mut self,
+        items: &[T],
+        ascii: impl Fn(&T) -> String,
+        binary: impl Fn(&T, &mut Vec<u8>),
+    ) {
+        match self.format {
+            FoamFormat::Ascii => {
+                self.text(&format!("{}\n(\n", items.len()));
+                for item in items {
+                    self.text(&ascii(item));
+                    self.text("\n");
+                }
+                self.text(")\n");
+            }
+            FoamFormat::Binary => {
+                self.text(&format!("{}\n(", items.len()));
+                for item in items {
+                    binary(item, &mut self.data);
+                }
+                self.text(")\n");
+            }
+        }
+    }
+
+    /// Returns the file contents, to be written to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a label did not fit the label size.
+    pub fn into_bytes(self, path: &Path) -> Result<Vec<u8>, IoError> {
+        match self.overflow {
+            Some(label) => Err(IoError::LabelOverflow {
+                path: path.to_path_buf(),
+                label,
+                bits: 8 * self.label_bytes,
+            }),
+            None => Ok(self.data),
+        }
+    }
+}
+
+fn is_delimiter(c: u8) -> bool {
+    c.is_ascii_whitespace() || matches!(c, b'(' | b')' | b'{' | b'}' | b';' | b'[' | b']' | b'"')
+}
+
+fn scalar_from_le(chunk: &[u8]) -> f64 {
+    match chunk.len() {
+        4 => f32::from_le_bytes(chunk.try_into().unwrap()) as f64,
+        _ => f64::from_le_bytes(chunk.try_into().unwrap()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(text: &[u8]) -> FoamReader<'_> {
+        FoamReader::new(Path::new("test"), text).unwrap()
+    }
+
+    #[test]
+    fn header_and_ascii_lists() {
+        let text = b"/* banner */\nFoamFile\n{\n    version 2.0;\n    format ascii;\n    class labelList;\n    note \"nCells:2\";\n}\n// comment\n3 (4 -1 7)\n2{5}\n";
+        let mut r = reader(text);
+        assert_eq!(r.header.format, FoamFormat::Ascii);
+        assert_eq!(r.header.class, "labelList");
+        assert_eq!(r.read_label_list().unwrap(), vec![4, -1, 7]);
+        assert_eq!(r.read_label_list().unwrap(), vec![5, 5]);
+        assert!(r.at_end());
+    }
+
+    #[test]
+    fn binary_lists_follow_arch() {
+        let mut text = b"FoamFile { format binary; class vectorField; arch \"LSB;label=32;scalar=64\"; }\n2\n(".to_vec();
+        for v in [1.5_f64, -2.0, 0.25, 3.0, 4.0, 5.0] {
+            text.extend_from_slice(&v.to_le_bytes());
+        }
+        text.extend_from_slice(b")\n3(");
+        for l in [0_i32, 10, 2] {
+            text.extend_from_slice(&l.to_le_bytes());
+        }
+        text.extend_from_slice(b")\n");
+        let mut r = reader(&text);
+        let v = r.read_vector_list().unwrap();
+        assert_eq!(
+            v,
+            vec![Vector::new(1.5, -2.0, 0.25), Vector::new(3.0, 4.0, 5.0)]
+        );
+        assert_eq!(r.read_index_list().unwrap(), vec![0, 10, 2]);
+    }
+
+    #[test]
+    fn binary_uniform_lists_read_raw_bytes() {
+        let mut text =
+            b"FoamFile { format binary; class labelList; arch \"LSB;label=32;scalar=64\"; }\n4{"
+                .to_vec();
+        // 125 is `}` and 32 a space, both as the first byte
+        text.extend_from_slice(&125_i32.to_le_bytes());
+        text.extend_from_slice(b"}\n2{");
+        text.extend_from_slice(&32_i32.to_le_bytes());
+        text.extend_from_slice(b"}\n3{");
+        for v in [0.5_f64, -1.0, 2.0] {
+            text.extend_from_slice(&v.to_le_bytes());
+        }
+        text.extend_from_slice(b"}\n");
+        let mut r = reader(&text);
+        assert_eq!(r.read_label_list().unwrap(), vec![125; 4]);
+        assert_eq!(r.read_index_list().unwrap(), vec![32; 2]);
+        assert_eq!(
+            r.read_vector_list().unwrap(),
+            vec![Vector::new(0.5, -1.0, 2.0); 3]
+        );
+        assert!(r.at_end());
+
+        let truncated = b"FoamFile { format binary; }\n4{\x01\x00";
+        assert!(reader(truncated).read_label_list().is_err());
+    }
+
+    #[test]
+    fn oversized_list_counts_are_errors() {
+        let cases: [&[u8]; 5] = [
+            b"FoamFile { format ascii; }\n1000000000000000 {(0 0 0)}\n",
+            b"FoamFile { format ascii; }\n1000000000000000 ((0 0 0))\n",
+            b"FoamFile { format binary; }\n18446744073709551615 (\x00\x00)\n",
+            b"FoamFile { format binary; }\n1000000000000000 {\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00}\n",
+            b"FoamFile { format binary; }\n1000000000000000 (\x00\x00)\n",
+        ];
+        for text in cases {
+            let err = reader(text).read_vector_list().unwrap_err();
+            assert!(matches!(err, IoError::Parse { .. }), "{err}");
+        }
+        let nested = b"FoamFile { format ascii; class faceList; }\n1000000000000000(3(0 1 2))\n";
+        assert!(reader(nested).read_compact_or_nested_list().is_err());
+    }
+
+    #[test]
+    fn compact_and_nested_face_lists() {
+        let compact =
+            b"FoamFile { format ascii; class faceCompactList; }\n3(0 3 7)\n7(0 1 2 3 4 5 6)\n";
+        let faces = reader(compact).read_compact_or_nested_list().unwrap();
+        assert_eq!(faces, vec![vec![0, 1, 2], vec![3, 4, 5, 6]]);
+
+        let nested = b"FoamFile { format ascii; class faceList; }\n2(3(0 1 2) 4(3 4 5 6))\n";
+        let faces = reader(nested).read_compact_or_nested_list().unwrap();
+        assert_eq!(faces, vec![vec![0, 1, 2], vec![3, 4, 5, 6]]);
+    }
+
+    #[test]
+    fn errors_report_line() {
+        let text = b"FoamFile { format ascii; }\n3\n(\n1.0\nfoo\n2.0\n)\n";
+        let mut r = reader(text);
+        let err = r.read_label_list().unwrap_err();
+        match err {
+            IoError::Parse { line, .. } => assert_eq!(line, 4),
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+
+    #[test]
+    fn writer_rejects_labels_beyond_label_size() {
+        let path = Path::new("owner");
+        let too_big = i32::MAX as usize + 1;
+        for format in [FoamFormat::Ascii, FoamFormat::Binary] {
+            let mut w = FoamWriter::new(format, 4, "labelList", "here", "owner", None);
+            w.label_list(&[1, too_big, too_big + 1]);
+            assert!(matches!(
+                w.into_bytes(path),
+                Err(IoError::LabelOverflow { label, bits: 32, .. }) if label == too_big
+            ));
+
+            let mut w = FoamWriter::new(format, 8, "labelList", "here", "owner", None);
+            w.label_list(&[1, too_big]);
+            let data = w.into_bytes(path).unwrap();
+            let mut r = reader(&data);
+            assert_eq!(r.read_label_list().unwrap(), [1, too_big as i64]);
+        }
+    }
+}
@@ -0,0 +1,864 @@
+//! Gmsh `.msh` mesh import.
+//!
+//! [`read_gmsh`] reads version 4.1 files in ASCII or binary form (8-byte
+//! `size_t`, either byte order). Linear tetrahedra, hexahedra, prisms and
+//! pyramids become cells; higher-order elements are rejected. Shared faces
+//! are merged and ordered as OpenFOAM expects.
+//!
+//! Physical groups map onto mesh regions:
+//!
+//! - Triangles and quadrilaterals on a physical surface become the faces of
+//!   a boundary patch of type `patch`, named after the group (or
+//!   `patch<tag>` if unnamed). Patches follow ascending physical tag; faces
+//!   of an entity with several physical tags go to the first one.
+//! - Volume elements on a physical volume are collected into a cell zone
+//!   named after the group (or `zone<tag>`).
+//!
+//! Boundary faces outside any physical surface go to a final `defaultFaces`
+//! patch. Nodes not used by any volume element are dropped; the others keep
+//! their file order. Lines, points and unknown sections are ignored.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+
+use dugong_mesh::{CellShape, PolyMesh, ShapeMesh, ShapePatch, Zone};
+use dugong_types::tensor::Vector;
+
+use crate::error::IoError;
+
+/// Reads the Gmsh mesh file at `path`.
+///
+/// # Errors
+///
+/// Returns `Err` if the file cannot be read, is malformed or uses an
+/// unsupported version or element type (the error carries the file and
+/// line), or the elements do not form a valid mesh.
+pub fn read_gmsh(path: impl AsRef<Path>) -> Result<PolyMesh, IoError> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).map_err(|source| IoError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_gmsh(path, &data)
+}
+
+fn parse_gmsh(path: &Path, data: &[u8]) -> Result<PolyMesh, IoError> {
+    let msh = MshFile::parse(path, data)?;
+    let (shape_mesh, zones) = msh.to_shape_mesh(path)?;
+    let mesh_error = |source| IoError::Mesh {
+        path: path.to_path_buf(),
+        source,
+    };
+    let (primitive, patches) = shape_mesh.build().map_err(mesh_error)?;
+    let mut mesh = PolyMesh::from_patch_defs(primitive, &patches).map_err(mesh_error)?;
+    for zone in zones {
+        mesh.add_cell_zone(zone).map_err(mesh_error)?;
+    }
+    Ok(mesh)
+}
+
+/// Number of nodes of a supported element type, or `None` if unsupported.
+fn element_nodes(element_type: i32) -> Option<usize> {
+    match element_type {
+        1 => Some(2),  // line
+        2 => Some(3),  // triangle
+        3 => Some(4),  // quadrilateral
+        4 => Some(4),  // tetrahedron
+        5 => Some(8),  // hexahedron
+        6 => Some(6),  // prism
+        7 => Some(5),  // pyramid
+        15 => Some(1), // point
+        _ => None,
+    }
+}
+
+/// A block of elements of one type on one entity.
+struct ElementBlock {
+    /// Line of the block header, for error messages.
+    line: usize,
+    dim: i32,
+    entity: i32,
+    element_type: i32,
+    tags: Vec<usize>,
+    /// Node tags of all elements, `element_nodes(element_type)` per element.
+    nodes: Vec<usize>,
+}
+
+/// The sections of a `.msh` file relevant to mesh import.
+#[derive(Default)]
+struct MshFile {
+    /// `(dim, physical tag)` -> name
+    physical_names: HashMap<(i32, i32), String>,
+    /// `(dim, entity tag)` -> physical tags
+    entity_physicals: HashMap<(i32, i32), Vec<i32>>,
+    /// Node tag -> position in `nodes`
+    node_index: HashMap<usize, usize>,
+    nodes: Vec<Vector>,
+    elements: Vec<ElementBlock>,
+}
+
+impl MshFile {
+    fn parse(path: &Path, data: &[u8]) -> Result<Self, IoError> {
+        let mut reader = MshReader::new(path, data);
+        match reader.next_section()? {
+            Some("$MeshFormat") => reader.read_mesh_format()?,
+            _ => return Err(reader.error("expected $MeshFormat")),
+        }
+        let mut msh = MshFile::default();
+        while let Some(section) = reader.next_section()? {
+            let Some(name) = section.strip_prefix('$') else {
+                return Err(reader.error(format!("expected a section, found {section:?}")));
+            };
+            match name {
+                "PhysicalNames" => msh.read_physical_names(&mut reader)?,
+                "Entities" => msh.read_entities(&mut reader)?,
+                "Nodes" => msh.read_nodes(&mut reader)?,
+                "Elements" => msh.read_elements(&mut reader)?,
+                _ => {
+                    reader.skip_section(name)?;
+                    continue;
+                }
+            }
+            reader.end_section(name)?;
+        }
+        Ok(msh)
+    }
+
+    /// Physical names are text even in binary files.
+    fn read_physical_names(&mut self, reader: &mut MshReader<'_>) -> Result<(), IoError> {
+        let n: usize = reader.parse_token()?;
+        for _ in 0..n {
+            let dim = reader.parse_token()?;
+            let tag = reader.parse_token()?;
+            let name = reader.quoted()?;
+            self.physical_names.insert((dim, tag), name);
+        }
+        Ok(())
+    }
+
+    fn read_entities(&mut self, reader: &mut MshReader<'_>) -> Result<(), IoError> {
+        reader.begin_data();
+        let mut counts = [0; 4];
+        for count in &mut counts {
+            *count = reader.size()?;
+        }
+        for (dim, &count) in counts.iter().enumerate() {
+            for _ in 0..count {
+                let tag = reader.int()?;
+                // Points store a position, other entities a bounding box
+                let n_coords = if dim == 0 { 3 } else { 6 };
+                for _ in 0..n_coords {
+                    reader.float()?;
+                }
+                let n_physicals = reader.size()?;
+                let physicals = (0..n_physicals)
+                    .map(|_| reader.int())
+                    .collect::<Result<Vec<_>, _>>()?;
+                if dim > 0 {
+                    let n_bounding = reader.size()?;
+                    for _ in 0..n_bounding {
+                        reader.int()?;
+                    }
+                }
+                self.entity_physicals.insert((dim as i32, tag), physicals);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_nodes(&mut self, reader: &mut MshReader<'_>) -> Result<(), IoError> {
+        reader.begin_data();
+        let n_blocks = reader.size()?;
+        let n_nodes = reader.size()?;
+        reader.size()?; // min node tag
+        reader.size()?; // max node tag
+        self.node_index.reserve(reader.capacity_for(n_nodes));
+        self.nodes.reserve(reader.capacity_for(n_nodes));
+        for _ in 0..n_blocks {
+            let dim = reader.int()?;
+            reader.int()?; // entity tag
+            let parametric = reader.int()? != 0;
+            let n = reader.size()?;
+            for _ in 0..n {
+                let tag = reader.size()?;
+                if self.node_index.insert(tag, self.node_index.len()).is_some() {
+                    return Err(reader.error(format!("duplicate node tag {tag}")));
+                }
+            }
+            for _ in 0..n {
+                let x = reader.float()?;
+                let y = reader.float()?;
+                let z = reader.float()?;
+                self.nodes.push(Vector::new(x, y, z));
+                if parametric {
+                    for _ in 0..dim {
+                        reader.float()?;
+                    }
+                }
+            }
+        }
+        if self.nodes.len() != n_nodes {
+            return Err(reader.error(format!(
+                "expected {n_nodes} nodes, found {}",
+                self.nodes.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn read_elements(&mut self, reader: &mut MshReader<'_>) -> Result<(), IoError> {
+        reader.begin_data();
+        let n_blocks = reader.size()?;
+        reader.size()?; // number of elements
+        reader.size()?; // min element tag
+        reader.size()?; // max element tag
+        for _ in 0..n_blocks {
+            let line = reader.next_line();
+            let dim = reader.int()?;
+            let entity = reader.int()?;
+            let element_type = reader.int()?;
+            let n = reader.size()?;
+            let Some(n_nodes) = element_nodes(element_type) else {
+                return Err(reader.error(format!("unsupported element type {element_type}")));
+            };
+            let Some(n_values) = n.checked_mul(n_nodes) else {
+                return Err(reader.error(format!("element count {n} is too large")));
+            };
+            let mut tags = Vec::with_capacity(reader.capacity_for(n));
+            let mut nodes = Vec::with_capacity(reader.capacity_for(n_values));
+            for _ in 0..n {
+                tags.push(reader.size()?);
+                for _ in 0..n_nodes {
+                    nodes.push(reader.size()?);
+                }
+            }
+            self.elements.push(ElementBlock {
+                line,
+                dim,
+                entity,
+                element_type,
+                tags,
+                nodes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Converts the elements into a [`ShapeMesh`] and its cell zones.
+    fn to_shape_mesh(&self, path: &Path) -> Result<(ShapeMesh, Vec<Zone>), IoError> {
+        let error = |line, message: String| IoError::Parse {
+            path: path.to_path_buf(),
+            line,
+            message,
+        };
+
+        let physicals = |dim, entity| {
+            self.entity_physicals
+                .get(&(dim, entity))
+                .map_or(&[][..], Vec::as_slice)
+        };
+
+        let mut cells = Vec::new();
+        let mut zone_cells: BTreeMap<i32, Vec<usize>> = BTreeMap::new();
+        let mut patch_faces: BTreeMap<i32, Vec<(usize, usize, Vec<usize>)>> = BTreeMap::new();
+        for block in &self.elements {
+            let n_nodes = block.nodes.len() / block.tags.len().max(1);
+            for (e, &tag) in block.tags.iter().enumerate() {
+                let mut vertices = Vec::with_capacity(n_nodes);
+                for &node in &block.nodes[e * n_nodes..(e + 1) * n_nodes] {
+                    match self.node_index.get(&node) {
+                        Some(&i) => vertices.push(i),
+                        None => {
+                            return Err(error(
+                                block.line,
+                                format!("element {tag} refers to unknown node {node}"),
+                            ));
+                        }
+                    }
+                }
+                let shape = match block.element_type {
+                    2 | 3 => {
+                        if let Some(&physical) = physicals(block.dim, block.entity).first() {
+                            patch_faces
+                                .entry(physical)
+                                .or_default()
+                                .push((block.line, tag, vertices));
+                        }
+                        continue;
+                    }
+                    4 => CellShape::Tet(vertices.try_into().unwrap()),
+                    5 => CellShape::Hex(vertices.try_into().unwrap()),
+                    6 => CellShape::Prism(vertices.try_into().unwrap()),
+                    7 => CellShape::Pyramid(vertices.try_into().unwrap()),
+                    _ => continue,
+                };
+                for &physical in physicals(block.dim, block.entity) {
+                    zone_cells.entry(physical).or_default().push(cells.len());
+                }
+                cells.push(shape);
+            }
+        }
+
+        // Keep only the nodes used by cells, in file order
+        let mut used = vec![false; self.nodes.len()];
+        for cell in &cells {
+            for &v in cell.vertices() {
+                used[v] = true;
+            }
+        }
+        let mut renumber = vec![usize::MAX; self.nodes.len()];
+        let mut points = Vec::new();
+        for (i, _) in used.iter().enumerate().filter(|(_, u)| **u) {
+            renumber[i] = points.len();
+            points.push(self.nodes[i]);
+        }
+        let cells = cells
+            .into_iter()
+            .map(|cell| match cell {
+                CellShape::Tet(v) => CellShape::Tet(v.map(|i| renumber[i])),
+                CellShape::Pyramid(v) => CellShape::Pyramid(v.map(|i| renumber[i])),
+                CellShape::Prism(v) => CellShape::Prism(v.map(|i| renumber[i])),
+                CellShape::Hex(v) => CellShape::Hex(v.map(|i| renumber[i])),
+            })
+            .collect();
+
+        let mut patches = Vec::with_capacity(patch_faces.len());
+        for (physical, faces) in patch_faces {
+            let mut renumbered = Vec::with_capacity(faces.len());
+            for (line, tag, face) in faces {
+                let face: Vec<usize> = face.iter().map(|&i| renumber[i]).collect();
+                if face.contains(&usize::MAX) {
+                    return Err(error(
+                        line,
+                        format!("element {tag} is not a face of any volume element"),
+                    ));
+                }
+                renumbered.push(face);
+            }
+            let name = self
+                .physical_names
+                .get(&(2, physical))
+                .cloned()
+                .unwrap_or_else(|| format!("patch{physical}"));
+            patches.push(ShapePatch::new(name, "patch", renumbered));
+        }
+
+        let zones = zone_cells
+            .into_iter()
+            .map(|(physical, indices)| {
+                let name = self
+                    .physical_names
+                    .get(&(3, physical))
+                    .cloned()
+                    .unwrap_or_else(|| format!("zone{physical}"));
+                Zone::new(name, indices)
+            })
+            .collect();
+
+        Ok((ShapeMesh::new(points, cells, patches), zones))
+    }
+}
+
+/// Cursor over the bytes of a `.msh` file.
+///
+/// Headers and ASCII data are read as whitespace-separated tokens; binary
+/// data as raw values in the byte order declared by `$MeshFormat`.
+struct MshReader<'a> {
+    path: &'a Path,
+    data: &'a [u8],
+    pos: usize,
+    line: usize,
+    binary: bool,
+    big_endian: bool,
+}
+
+impl<'a> MshReader<'a> {
+    fn new(path: &'a Path, data: &'a [u8]) -> Self {
+        Self {
+            path,
+            data,
+            pos: 0,
+            line: 1,
+            binary: false,
+            big_endian: false,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> IoError {
+        IoError::Parse {
+            path: self.path.to_path_buf(),
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    /// Returns how many of `n` values the rest of the data can hold, each
+    /// value taking at least one byte. Counts read from a corrupt file must
+    /// not size allocations beyond that.
+    fn capacity_for(&self, n: usize) -> usize {
+        n.min(self.data.len() - self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&b) = self.data.get(self.pos) {
+            if !b.is_ascii_whitespace() {
+                break;
+            }
+            if b == b'\n' {
+                self.line += 1;
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Returns the line of the next value. Binary data has no lines, so this
+    /// is the line the data started on.
+    fn next_line(&mut self) -> usize {
+        if !self.binary {
+            self.skip_whitespace();
+        }
+        self.line
+    }
+
+    /// Returns the next section header, or `None` at the end of the file.
+    fn next_section(&mut self) -> Result<Option<&'a str>, IoError> {
+        self.skip_whitespace();
+        if self.pos == self.data.len() {
+            return Ok(None);
+        }
+        self.token().map(Some)
+    }
+
+    fn token(&mut self) -> Result<&'a str, IoError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|b| !b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("unexpected end of file"));
+        }
+        std::str::from_utf8(&self.data[start..self.pos])
+            .map_err(|_| self.error("invalid UTF-8 in text data"))
+    }
+
+    fn parse_token<T: FromStr>(&mut self) -> Result<T, IoError> {
+        let token = self.token()?;
+        token
+            .parse()
+            .map_err(|_| self.error(format!("invalid number {token:?}")))
+    }
+
+    fn quoted(&mut self) -> Result<String, IoError> {
+        self.skip_whitespace();
+        if self.data.get(self.pos) != Some(&b'"') {
+            return Err(self.error("expected a quoted name"));
+        }
+        let start = self.pos + 1;
+        let Some(len) = self.data[start..].iter().position(|&b| b == b'"') else {
+            return Err(self.error("unterminated quoted name"));
+        };
+        self.pos = start + len + 1;
+        String::from_utf8(self.data[start..start + len].to_vec())
+            .map_err(|_| self.error("invalid UTF-8 in quoted name"))
+    }
+
+    /// Reads the body of `$MeshFormat` and switches to binary mode if needed.
+    fn read_mesh_format(&mut self) -> Result<(), IoError> {
+        let version = self.token()?;
+        if version != "4.1" {
+            return Err(self.error(format!(
+                "unsupported format version {version} (only 4.1 is supported)"
+            )));
+        }
+        let file_type: i32 = self.parse_token()?;
+        let data_size: usize = self.parse_token()?;
+        if data_size != 8 {
+            return Err(self.error(format!("unsupported data size {data_size}")));
+        }
+        match file_type {
+            0 => {}
+            1 => {
+                self.binary = true;
+                self.begin_data();
+                let bytes = self.raw::<4>()?;
+                if i32::from_le_bytes(bytes) == 1 {
+                    self.big_endian = false;
+                } else if i32::from_be_bytes(bytes) == 1 {
+                    self.big_endian = true;
+                } else {
+                    return Err(self.error("invalid byte-order marker"));
+                }
+            }
+            _ => return Err(self.error(format!("invalid file type {file_type}"))),
+        }
+        self.end_section("MeshFormat")
+    }
+
+    /// Moves past the end of the section header line so that binary data
+    /// starts at the cursor. Does nothing for ASCII files.
+    fn begin_data(&mut self) {
+        if !self.binary {
+            return;
+        }
+        while let Some(&b) = self.data.get(self.pos) {
+            self.pos += 1;
+            if b == b'\n' {
+                self.line += 1;
+                break;
+            }
+        }
+    }
+
+    fn end_section(&mut self, name: &str) -> Result<(), IoError> {
+        let token = self.token()?;
+        if token.strip_prefix("$End") != Some(name) {
+            return Err(self.error(format!("expected $End{name}, found {token:?}")));
+        }
+        Ok(())
+    }
+
+    fn skip_section(&mut self, name: &str) -> Result<(), IoError> {
+        let end = format!("$End{name}");
+        let Some(offset) = self.data[self.pos..]
+            .windows(end.len())
+            .position(|w| w == end.as_bytes())
+        else {
+            return Err(self.error(format!("missing {end}")));
+        };
+        let skipped = &self.data[self.pos..self.pos + offset];
+        self.line += skipped.iter().filter(|&&b| b == b'\n').count();
+        self.pos += offset + end.len();
+        Ok(())
+    }
+
+    fn raw<const N: usize>(&mut self) -> Result<[u8; N], IoError> {
+        let Some(bytes) = self.data.get(self.pos..self.pos + N) else {
+            return Err(self.error("unexpected end of binary data"));
+        };
+        self.pos += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn size(&mut self) -> Result<usize, IoError> {
+        if !self.binary {
+            return self.parse_token();
+        }
+        let bytes = self.raw()?;
+        let value = if self.big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        };
+        usize::try_from(value).map_err(|_| self.error(format!("size {value} out of range")))
+    }
+
+    fn int(&mut self) -> Result<i32, IoError> {
+        if !self.binary {
+            return self.parse_token();
+        }
+        let bytes = self.raw()?;
+        Ok(if self.big_endian {
+            i32::from_be_bytes(bytes)
+        } else {
+            i32::from_le_bytes(bytes)
+        })
+    }
+
+    fn float(&mut self) -> Result<f64, IoError> {
+        if !self.binary {
+            return self.parse_token();
+        }
+        let bytes = self.raw()?;
+        Ok(if self.big_endian {
+            f64::from_be_bytes(bytes)
+        } else {
+            f64::from_le_bytes(bytes)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A unit-cube hex (physical volume "fluid") with a pyramid on top, a
+    /// named bottom patch, an unnamed `x = 0` patch, an unused node and an
+    /// ignored line element. Node tags are sparse.
+    const ASCII: &str = r#"$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$PhysicalNames
+2
+2 1 "bottom"
+3 10 "fluid"
+$EndPhysicalNames
+$Comments
+anything goes here
+$EndComments
+$Entities
+0 0 2 2
+1 0 0 0 1 1 0 1 1 0
+2 0 0 0 0 1 1 1 2 0
+1 0 0 0 1 1 1 1 10 0
+2 0 0 1 1 1 1.5 0 0
+$EndEntities
+$Nodes
+1 10 1 30
+3 1 0 10
+1
+2
+3
+4
+5
+6
+7
+8
+20
+30
+0 0 0
+1 0 0
+1 1 0
+0 1 0
+0 0 1
+1 0 1
+1 1 1
+0 1 1
+0.5 0.5 1.5
+0 0 5
+$EndNodes
+$Elements
+5 5 1 5
+2 1 3 1
+1 1 2 3 4
+2 2 3 1
+2 1 5 8 4
+1 1 1 1
+3 1 2
+3 1 5 1
+4 1 2 3 4 5 6 7 8
+3 2 7 1
+5 5 6 7 8 20
+$EndElements
+"#;
+
+    /// The [`ASCII`] mesh in binary form, big-endian if requested.
+    fn binary(big_endian: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        let size = |out: &mut Vec<u8>, v: u64| {
+            out.extend(if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            })
+        };
+        let int = |out: &mut Vec<u8>, v: i32| {
+            out.extend(if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            })
+        };
+        let float = |out: &mut Vec<u8>, v: f64| {
+            out.extend(if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            })
+        };
+
+        out.extend(b"$MeshFormat\n4.1 1 8\n");
+        int(&mut out, 1);
+        out.extend(b"\n$EndMeshFormat\n");
+        out.extend(b"$PhysicalNames\n2\n2 1 \"bottom\"\n3 10 \"fluid\"\n$EndPhysicalNames\n");
+
+        out.extend(b"$Entities\n");
+        for n in [0, 0, 2, 2] {
+            size(&mut out, n);
+        }
+        let entities: [(i32, [f64; 6], &[i32]); 4] = [
+            (1, [0.0, 0.0, 0.0, 1.0, 1.0, 0.0], &[1]),
+            (2, [0.0, 0.0, 0.0, 0.0, 1.0, 1.0], &[2]),
+            (1, [0.0, 0.0, 0.0, 1.0, 1.0, 1.0], &[10]),
+            (2, [0.0, 0.0, 1.0, 1.0, 1.0, 1.5], &[]),
+        ];
+        for (tag, bbox, physicals) in entities {
+            int(&mut out, tag);
+            for v in bbox {
+                float(&mut out, v);
+            }
+            size(&mut out, physicals.len() as u64);
+            for &p in physicals {
+                int(&mut out, p);
+            }
+            size(&mut out, 0);
+        }
+        out.extend(b"\n$EndEntities\n");
+
+        out.extend(b"$Nodes\n");
+        for n in [1, 10, 1, 30] {
+            size(&mut out, n);
+        }
+        int(&mut out, 3);
+        int(&mut out, 1);
+        int(&mut out, 0);
+        size(&mut out, 10);
+        for tag in [1, 2, 3, 4, 5, 6, 7, 8, 20, 30] {
+            size(&mut out, tag);
+        }
+        let coords = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [0.0, 1.0, 1.0],
+            [0.5, 0.5, 1.5],
+            [0.0, 0.0, 5.0],
+        ];
+        for p in coords.iter().flatten() {
+            float(&mut out, *p);
+        }
+        out.extend(b"\n$EndNodes\n");
+
+        out.extend(b"$Elements\n");
+        for n in [5, 5, 1, 5] {
+            size(&mut out, n);
+        }
+        let blocks: [(i32, i32, i32, u64, &[u64]); 5] = [
+            (2, 1, 3, 1, &[1, 2, 3, 4]),
+            (2, 2, 3, 2, &[1, 5, 8, 4]),
+            (1, 1, 1, 3, &[1, 2]),
+            (3, 1, 5, 4, &[1, 2, 3, 4, 5, 6, 7, 8]),
+            (3, 2, 7, 5, &[5, 6, 7, 8, 20]),
+        ];
+        for (dim, entity, element_type, tag, nodes) in blocks {
+            int(&mut out, dim);
+            int(&mut out, entity);
+            int(&mut out, element_type);
+            size(&mut out, 1);
+            size(&mut out, tag);
+            for &n in nodes {
+                size(&mut out, n);
+            }
+        }
+        out.extend(b"\n$EndElements\n");
+        out
+    }
+
+    fn parse(data: &[u8]) -> Result<PolyMesh, IoError> {
+        parse_gmsh(Path::new("test.msh"), data)
+    }
+
+    fn check_fixture(mesh: &PolyMesh) {
+        assert_eq!(mesh.n_points(), 9);
+        assert_eq!(mesh.n_cells(), 2);
+        assert_eq!(mesh.n_internal_faces(), 1);
+        let volumes = mesh.cell_volumes();
+        assert!((volumes[0] - 1.0).abs() < 1e-12);
+        assert!((volumes[1] - 1.0 / 6.0).abs() < 1e-12);
+
+        let patches: Vec<(&str, &str, usize)> = mesh
+            .patches()
+            .iter()
+            .map(|p| (p.name(), p.patch_type(), p.size()))
+            .collect();
+        assert_eq!(
+            patches,
+            [
+                ("bottom", "patch", 1),
+                ("patch2", "patch", 1),
+                ("defaultFaces", "patch", 7),
+            ]
+        );
+        assert_eq!(mesh.cell_zones(), [Zone::new("fluid", vec![0])]);
+    }
+
+    #[test]
+    fn reads_ascii() {
+        let mesh = parse(ASCII.as_bytes()).unwrap();
+        check_fixture(&mesh);
+    }
+
+    #[test]
+    fn reads_binary_in_either_byte_order() {
+        let little = parse(&binary(false)).unwrap();
+        check_fixture(&little);
+        let big = parse(&binary(true)).unwrap();
+        check_fixture(&big);
+        assert_eq!(little.points(), big.points());
+        assert_eq!(little.faces(), big.faces());
+    }
+
+    #[test]
+    fn rejects_unsupported_input() {
+        let old = ASCII.replace("4.1 0 8", "2.2 0 8");
+        let Err(IoError::Parse { line, .. }) = parse(old.as_bytes()) else {
+            panic!("version 2.2 accepted");
+        };
+        assert_eq!(line, 2);
+
+        // Second-order tetrahedron in place of the hex block
+        let quadratic = ASCII.replace("3 1 5 1\n", "3 1 11 1\n");
+        let Err(IoError::Parse { line, message, .. }) = parse(quadratic.as_bytes()) else {
+            panic!("second-order element accepted");
+        };
+        assert_eq!(line, 51);
+        assert!(message.contains("element type 11"), "{message}");
+
+        let dangling = ASCII.replace("5 6 7 8 20", "5 6 7 8 21");
+        let Err(IoError::Parse { message, .. }) = parse(dangling.as_bytes()) else {
+            panic!("unknown node accepted");
+        };
+        assert!(message.contains("unknown node 21"), "{message}");
+
+        // Bottom quad moved onto the unused node
+        let stray = ASCII.replace("1 1 2 3 4\n", "1 1 2 3 30\n");
+        let Err(IoError::Parse { line, message, .. }) = parse(stray.as_bytes()) else {
+            panic!("stray surface element accepted");
+        };
+        assert_eq!(line, 45);
+        assert!(message.contains("element 1 "), "{message}");
+    }
+
+    #[test]
+    fn corrupt_counts_are_parse_errors() {
+        let nodes = ASCII.replace(
+            "1 10 1 30
+",
+            "1 1000000000000000000 1 30
+",
+        );
+        assert!(matches!(
+            parse(nodes.as_bytes()),
+            Err(IoError::Parse { .. })
+        ));
+        let block = ASCII.replace(
+            "3 1 5 1
+",
+            "3 1 5 1000000000000000000
+",
+        );
+        assert!(matches!(
+            parse(block.as_bytes()),
+            Err(IoError::Parse { .. })
+        ));
+        let overflow = ASCII.replace(
+            "3 1 5 1
+",
+            "3 1 5 18446744073709551615
+",
+        );
+        let Err(IoError::Parse { message, .. }) = parse(overflow.as_bytes()) else {
+            panic!("overflowing element count accepted");
+        };
+        assert!(message.contains("too large"), "{message}");
+    }
+}
@@ -1,5 +1,21 @@
 //! Input/output operations
 //!
-//! Provides configuration file parsing, field I/O, and mesh reading.
+//! Provides configuration file parsing, field I/O, mesh reading and
+//! writing (OpenFOAM `polyMesh`, Gmsh import), VTK export, and STL/OBJ
+//! export of boundary surfaces.
 
-// TODO: Implement I/O operations
+pub mod block_mesh;
+mod error;
+mod foam_file;
+pub mod gmsh;
+pub mod poly_mesh;
+pub mod surface;
+pub mod vtk;
+
+pub use block_mesh::{parse_block_mesh, read_block_mesh};
+pub use error::IoError;
+pub use foam_file::FoamFormat;
+pub use gmsh::read_gmsh;
+pub use poly_mesh::{poly_mesh_dir, read_poly_mesh, write_poly_mesh};
+pub use surface::{StlEncoding, write_obj, write_stl};
+pub use vtk::{CellData, CellValues, VtkEncoding, write_pvd, write_vtk, write_vtu};
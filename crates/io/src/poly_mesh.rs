@@ -0,0 +1,754 @@
+//! OpenFOAM `polyMesh` directories.
+//!
+//! A `polyMesh` directory (usually `constant/polyMesh` of a case) holds the
+//! files `points`, `faces`, `owner`, `neighbour` and `boundary`. ASCII and
+//! binary `FoamFile` formats are supported, and faces may be stored either as
+//! a `faceList` or as a `faceCompactList`. Compressed (`.gz`) files are not
+//! supported.
+//!
+//! Cyclic and processor patches keep their coupling entries. OpenFOAM's
+//! `separationVector`, `rotationAxis`, `rotationCentre` and `rotationAngle`
+//! (in degrees) map a cyclic patch onto its neighbor, the inverse of the
+//! [`Transform`] in [`PatchCoupling::Cyclic`].
+//!
+//! [`write_poly_mesh`] produces the same layout, so a mesh written and read
+//! back is bit-identical in either format.
+
+use std::path::{Path, PathBuf};
+
+use dugong_mesh::{
+    CyclicPolyPatch, PatchCoupling, PatchDef, PrimitiveMesh, ProcessorPolyPatch, Transform,
+};
+use dugong_types::tensor::Vector;
+
+use crate::error::IoError;
+use crate::foam_file::{FoamFormat, FoamReader, FoamWriter, Token};
+
+/// Reads the mesh stored in the `polyMesh` directory `dir`.
+///
+/// Returns the mesh together with the patches listed in `boundary`, in file
+/// order.
+///
+/// # Errors
+///
+/// Returns `Err` if a file cannot be read, is malformed (the error carries
+/// the file and line), or the data does not form a valid [`PrimitiveMesh`].
+pub fn read_poly_mesh(dir: impl AsRef<Path>) -> Result<(PrimitiveMesh, Vec<PatchDef>), IoError> {
+    let dir = dir.as_ref();
+    let points = read_file(&dir.join("points"), parse_points)?;
+    let faces = read_file(&dir.join("faces"), parse_faces)?;
+    let owner = read_file(&dir.join("owner"), parse_owner)?;
+    let neighbor = read_file(&dir.join("neighbour"), parse_neighbor)?;
+    let patches = read_file(&dir.join("boundary"), parse_boundary)?;
+
+    let mesh =
+        PrimitiveMesh::new(points, faces, owner, neighbor).map_err(|source| IoError::Mesh {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+    Ok((mesh, patches))
+}
+
+/// Reads `path` and parses it with `parse`.
+fn read_file<T>(
+    path: &Path,
+    parse: fn(&mut FoamReader<'_>) -> Result<T, IoError>,
+) -> Result<T, IoError> {
+    let data = std::fs::read(path).map_err(|source| IoError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut reader = FoamReader::new(path, &data)?;
+    let value = parse(&mut reader)?;
+    if !reader.at_end() {
+        return Err(reader.error("unexpected data after the list"));
+    }
+    Ok(value)
+}
+
+fn parse_points(reader: &mut FoamReader<'_>) -> Result<Vec<Vector>, IoError> {
+    reader.read_vector_list()
+}
+
+fn parse_faces(reader: &mut FoamReader<'_>) -> Result<Vec<Vec<usize>>, IoError> {
+    reader.read_compact_or_nested_list()
+}
+
+fn parse_owner(reader: &mut FoamReader<'_>) -> Result<Vec<usize>, IoError> {
+    reader.read_index_list()
+}
+
+/// Parses the neighbour list. Older files list one entry per face with `-1`
+/// for boundary faces; those entries are dropped.
+fn parse_neighbor(reader: &mut FoamReader<'_>) -> Result<Vec<usize>, IoError> {
+    let labels = reader.read_label_list()?;
+    let n_internal = labels.iter().take_while(|&&l| l >= 0).count();
+    if let Some(&l) = labels[n_internal..].iter().find(|&&l| l >= 0) {
+        return Err(reader.error(format!(
+            "neighbour {l} follows a boundary entry at index {n_internal}"
+        )));
+    }
+    Ok(labels[..n_internal].iter().map(|&l| l as usize).collect())
+}
+
+/// Parses the `boundary` file: a list of `name { type ...; nFaces ...;
+/// startFace ...; }` dictionaries. Cyclic patches also read
+/// `neighbourPatch` and their `transform`, processor patches `myProcNo` and
+/// `neighbProcNo`. Other entries are ignored.
+fn parse_boundary(reader: &mut FoamReader<'_>) -> Result<Vec<PatchDef>, IoError> {
+    let n = reader.read_list_size()?;
+    reader.expect_punct(b'(')?;
+    let mut patches = Vec::new();
+    for _ in 0..n {
+        let name = reader.read_word()?;
+        reader.expect_punct(b'{')?;
+        let (mut patch_type, mut n_faces, mut start_face) = (None, None, None);
+        let (mut neighbor_patch, mut transform) = (None, None);
+        let (mut separation, mut axis, mut center, mut angle) = (None, None, None, None);
+        let (mut my_rank, mut neighbor_rank) = (None, None);
+        loop {
+            let key = match reader.next_token()? {
+                Token::Punct(b'}') => break,
+                Token::Word(w) => w,
+                other => {
+                    return Err(reader.error(format!("unexpected {other:?} in patch {name}")));
+                }
+            };
+            if reader.peek_token()? == Token::Punct(b'{') {
+                skip_dictionary(reader)?;
+                continue;
+            }
+            match key.as_str() {
+                "type" => patch_type = Some(reader.read_entry_value()?),
+                "nFaces" => n_faces = Some(read_index_entry(reader)?),
+                "startFace" => start_face = Some(read_index_entry(reader)?),
+                "neighbourPatch" => neighbor_patch = Some(reader.read_entry_value()?),
+                "transform" => transform = Some(reader.read_entry_value()?),
+                "separationVector" => separation = Some(read_vector_entry(reader)?),
+                "rotationAxis" => axis = Some(read_vector_entry(reader)?),
+                "rotationCentre" => center = Some(read_vector_entry(reader)?),
+                "rotationAngle" => angle = Some(read_scalar_entry(reader)?),
+                "myProcNo" => my_rank = Some(read_rank_entry(reader)?),
+                "neighbProcNo" => neighbor_rank = Some(read_rank_entry(reader)?),
+                _ => {
+                    reader.read_entry_value()?;
+                }
+            }
+        }
+        let missing = |entry: &str| reader.error(format!("patch {name} has no `{entry}` entry"));
+        let patch_type = patch_type.ok_or_else(|| missing("type"))?;
+        let size = n_faces.ok_or_else(|| missing("nFaces"))?;
+        let start = start_face.ok_or_else(|| missing("startFace"))?;
+        let coupling = match patch_type.as_str() {
+            CyclicPolyPatch::TYPE_NAME => {
+                // OpenFOAM describes the map from this patch onto its
+                // neighbor; a Transform maps the other way
+                let transform = match transform.as_deref() {
+                    Some("translational") => Transform::Translation {
+                        separation: -separation.ok_or_else(|| missing("separationVector"))?,
+                    },
+                    Some("rotational") => {
+                        let axis = axis.ok_or_else(|| missing("rotationAxis"))?;
+                        if !(axis.mag() > 0.0 && axis.mag().is_finite()) {
+                            return Err(
+                                reader.error(format!("patch {name} has an invalid rotation axis"))
+                            );
+                        }
+                        let angle = angle.ok_or_else(|| missing("rotationAngle"))?;
+                        Transform::rotation(
+                            axis,
+                            -angle.to_radians(),
+                            center.unwrap_or(Vector::zero()),
+                        )
+                    }
+                    _ => Transform::Identity,
+                };
+                Some(PatchCoupling::Cyclic {
+                    neighbor_patch: neighbor_patch.ok_or_else(|| missing("neighbourPatch"))?,
+                    transform,
+                })
+            }
+            ProcessorPolyPatch::TYPE_NAME => Some(PatchCoupling::Processor {
+                my_rank: my_rank.ok_or_else(|| missing("myProcNo"))?,
+                neighbor_rank: neighbor_rank.ok_or_else(|| missing("neighbProcNo"))?,
+            }),
+            _ => None,
+        };
+        patches.push(PatchDef {
+            coupling,
+            ..PatchDef::new(name, patch_type, start, size)
+        });
+    }
+    reader.expect_punct(b')')?;
+    Ok(patches)
+}
+
+/// Reads `<index>;`.
+fn read_index_entry(reader: &mut FoamReader<'_>) -> Result<usize, IoError> {
+    let value = reader.read_index()?;
+    reader.expect_punct(b';')?;
+    Ok(value)
+}
+
+/// Reads `(x y z);`.
+fn read_vector_entry(reader: &mut FoamReader<'_>) -> Result<Vector, IoError> {
+    let value = reader.read_vector()?;
+    reader.expect_punct(b';')?;
+    Ok(value)
+}
+
+/// Reads `<scalar>;`.
+fn read_scalar_entry(reader: &mut FoamReader<'_>) -> Result<f64, IoError> {
+    let value = reader.read_scalar()?;
+    reader.expect_punct(b';')?;
+    Ok(value)
+}
+
+/// Reads a processor number, `<label>;`.
+fn read_rank_entry(reader: &mut FoamReader<'_>) -> Result<i32, IoError> {
+    let value = reader.read_label()?;
+    reader.expect_punct(b';')?;
+    i32::try_from(value).map_err(|_| reader.error(format!("processor number {value} is too large")))
+}
+
+/// Skips a `{ ... }` sub-dictionary, including nested ones.
+fn skip_dictionary(reader: &mut FoamReader<'_>) -> Result<(), IoError> {
+    reader.expect_punct(b'{')?;
+    let mut depth = 1;
+    while depth > 0 {
+        match reader.next_token()? {
+            Token::Punct(b'{') => depth += 1,
+            Token::Punct(b'}') => depth -= 1,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Writes `mesh` and its `patches` to the `polyMesh` directory `dir`,
+/// creating it if needed.
+///
+/// Faces are written as a `faceList` in ASCII and as a `faceCompactList` in
+/// binary, as OpenFOAM does. Labels use 32 bits unless the mesh is too large
+/// for them.
+///
+/// # Errors
+///
+/// Returns `Err` if the directory or a file cannot be written, or a label
+/// does not fit in 64 bits.
+pub fn write_poly_mesh(
+    dir: impl AsRef<Path>,
+    mesh: &PrimitiveMesh,
+    patches: &[PatchDef],
+    format: FoamFormat,
+) -> Result<(), IoError> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir).map_err(|source| IoError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+
+    // The binary face offsets run up to the total number of face points
+    let max_label = [
+        mesh.n_points(),
+        mesh.n_faces(),
+        mesh.n_cells(),
+        mesh.faces().values().len(),
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or(0);
+    let label_bytes = if max_label > i32::MAX as usize { 8 } else { 4 };
+    let note = format!(
+        "nPoints:{} nCells:{} nFaces:{} nInternalFaces:{}",
+        mesh.n_points(),
+        mesh.n_cells(),
+        mesh.n_faces(),
+        mesh.n_internal_faces()
+    );
+    let writer = |class: &str, object: &str, note: Option<&str>| {
+        FoamWriter::new(format, label_bytes, class, LOCATION, object, note)
+    };
+
+    let mut points = writer("vectorField", "points", None);
+    points.vector_list(mesh.points());
+    write_file(&dir.join("points"), points)?;
+
+    let face_class = match format {
+        FoamFormat::Ascii => "faceList",
+        FoamFormat::Binary => "faceCompactList",
+    };
+    let mut faces = writer(face_class, "faces", None);
+    faces.nested_label_list(mesh.faces());
+    write_file(&dir.join("faces"), faces)?;
+
+    let mut owner = writer("labelList", "owner", Some(&note));
+    owner.label_list(mesh.owner());
+    write_file(&dir.join("owner"), owner)?;
+
+    let mut neighbour = writer("labelList", "neighbour", Some(&note));
+    neighbour.label_list(mesh.neighbor());
+    write_file(&dir.join("neighbour"), neighbour)?;
+
+    // The boundary dictionary is always text, whatever the data format
+    let mut boundary = FoamWriter::new(
+        FoamFormat::Ascii,
+        label_bytes,
+        "polyBoundaryMesh",
+        LOCATION,
+        "boundary",
+        None,
+    );
+    boundary.text(&format!("{}\n(\n", patches.len()));
+    for patch in patches {
+        boundary.text(&format!(
+            "    {}\n    {{\n        type            {};\n        nFaces          {};\n        startFace       {};\n",
+            patch.name, patch.patch_type, patch.size, patch.start
+        ));
+        if let Some(coupling) = &patch.coupling {
+            boundary.text(&coupling_entries(coupling));
+        }
+        boundary.text("    }\n");
+    }
+    boundary.text(")\n");
+    write_file(&dir.join("boundary"), boundary)
+}
+
+/// Returns the `boundary` entries of a coupled patch, the inverse of what
+/// [`parse_boundary`] reads.
+fn coupling_entries(coupling: &PatchCoupling) -> String {
+    let vector = |v: Vector| format!("({} {} {})", v.x(), v.y(), v.z());
+    match coupling {
+        PatchCoupling::Cyclic {
+            neighbor_patch,
+            transform,
+        } => {
+            let mut text = format!("        neighbourPatch  {neighbor_patch};\n");
+            match transform {
+                Transform::Identity => {}
+                Transform::Translation { separation } => {
+                    text.push_str("        transform       translational;\n");
+                    text.push_str(&format!(
+                        "        separationVector {};\n",
+                        vector(-*separation)
+                    ));
+                }
+                Transform::Rotation { origin, .. } => {
+                    let (axis, angle) = transform.axis_angle().expect("a rotation");
+                    text.push_str("        transform       rotational;\n");
+                    text.push_str(&format!("        rotationAxis    {};\n", vector(axis)));
+                    text.push_str(&format!("        rotationCentre  {};\n", vector(*origin)));
+                    text.push_str(&format!(
+                        "        rotationAngle   {};\n",
+                        -angle.to_degrees()
+                    ));
+                }
+            }
+            text
+        }
+        PatchCoupling::Processor {
+            my_rank,
+            neighbor_rank,
+        } => format!(
+            "        myProcNo        {my_rank};\n        neighbProcNo    {neighbor_rank};\n"
+        ),
+    }
+}
+
+/// `location` header entry of the mesh files.
+const LOCATION: &str = "constant/polyMesh";
+
+fn write_file(path: &Path, writer: FoamWriter) -> Result<(), IoError> {
+    std::fs::write(path, writer.into_bytes(path)?).map_err(|source| IoError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Returns the `constant/polyMesh` directory of the case at `case_dir`.
+pub fn poly_mesh_dir(case_dir: impl AsRef<Path>) -> PathBuf {
+    case_dir.as_ref().join("constant").join("polyMesh")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty scratch directory unique to `name`.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dugong-io-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn header(class: &str, format: &str) -> String {
+        format!(
+            "FoamFile\n{{\n    version     2.0;\n    format      {format};\n    class       {class};\n    arch        \"LSB;label=32;scalar=64\";\n    object      x;\n}}\n"
+        )
+    }
+
+    /// Two unit hexes side by side in x.
+    const POINTS: &str = "12\n(\n(0 0 0)\n(1 0 0)\n(2 0 0)\n(0 1 0)\n(1 1 0)\n(2 1 0)\n(0 0 1)\n(1 0 1)\n(2 0 1)\n(0 1 1)\n(1 1 1)\n(2 1 1)\n)\n";
+    const FACES: &[[usize; 4]] = &[
+        [1, 4, 10, 7],
+        [0, 6, 9, 3],
+        [2, 5, 11, 8],
+        [0, 1, 7, 6],
+        [1, 2, 8, 7],
+        [3, 9, 10, 4],
+        [4, 10, 11, 5],
+        [0, 3, 4, 1],
+        [1, 4, 5, 2],
+        [6, 7, 10, 9],
+        [7, 8, 11, 10],
+    ];
+    const OWNER: [usize; 11] = [0, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1];
+    const BOUNDARY: &str = "3\n(\n    inlet\n    {\n        type patch;\n        nFaces 1;\n        startFace 1;\n    }\n    outlet\n    {\n        type patch;\n        inGroups List<word> 1(outflow);\n        nFaces 1;\n        startFace 2;\n    }\n    walls\n    {\n        type wall;\n        nFaces 8;\n        startFace 3;\n    }\n)\n";
+
+    fn write_ascii_mesh(dir: &Path, compact: bool) {
+        std::fs::write(dir.join("points"), header("vectorField", "ascii") + POINTS).unwrap();
+        let faces = if compact {
+            let offsets: Vec<String> = (0..=FACES.len()).map(|i| (4 * i).to_string()).collect();
+            let flat: Vec<String> = FACES.iter().flatten().map(|p| p.to_string()).collect();
+            format!(
+                "{}\n({})\n{}\n({})\n",
+                offsets.len(),
+                offsets.join(" "),
+                flat.len(),
+                flat.join(" ")
+            )
+        } else {
+            let items: Vec<String> = FACES
+                .iter()
+                .map(|f| format!("4({} {} {} {})", f[0], f[1], f[2], f[3]))
+                .collect();
+            format!("{}\n(\n{}\n)\n", FACES.len(), items.join("\n"))
+        };
+        let class = if compact {
+            "faceCompactList"
+        } else {
+            "faceList"
+        };
+        std::fs::write(dir.join("faces"), header(class, "ascii") + &faces).unwrap();
+        let owner: Vec<String> = OWNER.iter().map(|o| o.to_string()).collect();
+        std::fs::write(
+            dir.join("owner"),
+            header("labelList", "ascii") + &format!("11\n(\n{}\n)\n", owner.join("\n")),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("neighbour"),
+            header("labelList", "ascii") + "1\n(\n1\n)\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("boundary"),
+            header("polyBoundaryMesh", "ascii") + BOUNDARY,
+        )
+        .unwrap();
+    }
+
+    fn check_two_cells(mesh: &PrimitiveMesh, patches: &[PatchDef]) {
+        assert_eq!(mesh.n_cells(), 2);
+        assert_eq!(mesh.n_points(), 12);
+        assert_eq!(mesh.n_faces(), 11);
+        assert_eq!(mesh.n_internal_faces(), 1);
+        for &v in mesh.cell_volumes() {
+            assert!((v - 1.0).abs() < 1e-12, "volume {v}");
+        }
+        assert_eq!(
+            patches,
+            [
+                PatchDef::new("inlet", "patch", 1, 1),
+                PatchDef::new("outlet", "patch", 2, 1),
+                PatchDef::new("walls", "wall", 3, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_ascii_face_list() {
+        let dir = scratch_dir("ascii");
+        write_ascii_mesh(&dir, false);
+        let (mesh, patches) = read_poly_mesh(&dir).unwrap();
+        check_two_cells(&mesh, &patches);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_ascii_compact_face_list() {
+        let dir = scratch_dir("compact");
+        write_ascii_mesh(&dir, true);
+        let (mesh, patches) = read_poly_mesh(&dir).unwrap();
+        check_two_cells(&mesh, &patches);
+        assert_eq!(mesh.faces()[0], vec![1, 4, 10, 7]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_binary_files() {
+        let dir = scratch_dir("binary");
+        write_ascii_mesh(&dir, false);
+
+        let binary_list = |class: &str, n: usize, bytes: Vec<u8>| {
+            let mut data = header(class, "binary").into_bytes();
+            data.extend_from_slice(format!("{n}\n(").as_bytes());
+            data.extend_from_slice(&bytes);
+            data.extend_from_slice(b")\n");
+            data
+        };
+        let label_bytes = |labels: &[usize]| -> Vec<u8> {
+            labels
+                .iter()
+                .flat_map(|&l| (l as i32).to_le_bytes())
+                .collect()
+        };
+
+        let mut points = Vec::new();
+        for line in POINTS.lines().filter(|l| l.starts_with("(") && l.len() > 1) {
+            for c in line.trim_matches(|c| c == '(' || c == ')').split(' ') {
+                points.extend_from_slice(&c.parse::<f64>().unwrap().to_le_bytes());
+            }
+        }
+        std::fs::write(dir.join("points"), binary_list("vectorField", 12, points)).unwrap();
+
+        let offsets: Vec<usize> = (0..=FACES.len()).map(|i| 4 * i).collect();
+        let flat: Vec<usize> = FACES.iter().flatten().copied().collect();
+        let mut faces = binary_list("faceCompactList", offsets.len(), label_bytes(&offsets));
+        faces.extend_from_slice(format!("{}\n(", flat.len()).as_bytes());
+        faces.extend_from_slice(&label_bytes(&flat));
+        faces.extend_from_slice(b")\n");
+        std::fs::write(dir.join("faces"), faces).unwrap();
+        std::fs::write(
+            dir.join("owner"),
+            binary_list("labelList", 11, label_bytes(&OWNER)),
+        )
+        .unwrap();
+
+        let (mesh, patches) = read_poly_mesh(&dir).unwrap();
+        check_two_cells(&mesh, &patches);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn old_style_neighbour_list_is_truncated() {
+        let dir = scratch_dir("old-neighbour");
+        write_ascii_mesh(&dir, false);
+        let neighbour = format!("11\n(\n1{}\n)\n", " -1".repeat(10));
+        std::fs::write(
+            dir.join("neighbour"),
+            header("labelList", "ascii") + &neighbour,
+        )
+        .unwrap();
+        let (mesh, _) = read_poly_mesh(&dir).unwrap();
+        assert_eq!(mesh.neighbor(), &[1]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn malformed_file_reports_path_and_line() {
+        let dir = scratch_dir("malformed");
+        write_ascii_mesh(&dir, false);
+        let bad = POINTS.replace("(1 1 0)", "(1 one 0)");
+        std::fs::write(dir.join("points"), header("vectorField", "ascii") + &bad).unwrap();
+        let Err(err) = read_poly_mesh(&dir) else {
+            panic!("malformed points accepted");
+        };
+        match &err {
+            IoError::Parse { path, line, .. } => {
+                assert_eq!(path, &dir.join("points"));
+                // 8 header lines, then "12", "(" and four points before it
+                assert_eq!(*line, 15);
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+        assert!(err.to_string().contains("points:15:"), "{err}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_file_and_invalid_mesh_are_reported() {
+        let dir = scratch_dir("invalid");
+        assert!(matches!(read_poly_mesh(&dir), Err(IoError::Io { .. })));
+        write_ascii_mesh(&dir, false);
+        std::fs::write(
+            dir.join("neighbour"),
+            header("labelList", "ascii") + "1\n(\n5\n)\n",
+        )
+        .unwrap();
+        assert!(matches!(read_poly_mesh(&dir), Err(IoError::Mesh { .. })));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn assert_round_trip(format: FoamFormat, name: &str) {
+        use dugong_mesh::CartesianBox;
+
+        // Grading gives coordinates without short decimal representations
+        let (mesh, patches) = CartesianBox::new(
+            Vector::new(-0.1, 0.0, 1.0 / 3.0),
+            Vector::new(1.0, 0.7, 2.0),
+            [3, 2, 4],
+        )
+        .with_grading([3.0, 0.3, 1.7])
+        .build()
+        .unwrap();
+        let dir = scratch_dir(name);
+        write_poly_mesh(&dir, &mesh, &patches, format).unwrap();
+        let (read, read_patches) = read_poly_mesh(&dir).unwrap();
+
+        assert_eq!(read_patches, patches);
+        assert_eq!(read.faces(), mesh.faces());
+        assert_eq!(read.owner(), mesh.owner());
+        assert_eq!(read.neighbor(), mesh.neighbor());
+        assert_eq!(read.n_points(), mesh.n_points());
+        for (a, b) in read.points().iter().zip(mesh.points()) {
+            for (x, y) in a.as_array().iter().zip(b.as_array()) {
+                assert_eq!(x.to_bits(), y.to_bits());
+            }
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn ascii_round_trip_is_bit_identical() {
+        assert_round_trip(FoamFormat::Ascii, "round-trip-ascii");
+    }
+
+    #[test]
+    fn binary_round_trip_is_bit_identical() {
+        assert_round_trip(FoamFormat::Binary, "round-trip-binary");
+    }
+
+    #[test]
+    fn coupled_patches_round_trip() {
+        let mesh = PrimitiveMesh::unit_cube(1, 1, 1).unwrap();
+        let cyclic = |name: &str, neighbor: &str, start, transform| {
+            PatchDef::new(name, "cyclic", start, 1).with_coupling(PatchCoupling::Cyclic {
+                neighbor_patch: neighbor.into(),
+                transform,
+            })
+        };
+        let shift = Vector::new(0.0, 0.0, 1.5);
+        let turn = Transform::rotation(
+            Vector::new(1.0, 1.0, 0.0),
+            0.7,
+            Vector::new(0.5, -0.25, 0.0),
+        );
+        let patches = [
+            cyclic(
+                "left",
+                "right",
+                0,
+                Transform::Translation { separation: shift },
+            ),
+            cyclic(
+                "right",
+                "left",
+                1,
+                Transform::Translation { separation: -shift },
+            ),
+            cyclic("front", "back", 2, turn.clone()),
+            cyclic("back", "front", 3, turn.inverse()),
+            cyclic("top", "bottom", 4, Transform::Identity),
+            PatchDef::new("bottom", "processor", 5, 1).with_coupling(PatchCoupling::Processor {
+                my_rank: 2,
+                neighbor_rank: 0,
+            }),
+        ];
+        for format in [FoamFormat::Ascii, FoamFormat::Binary] {
+            let dir = scratch_dir(&format!("coupled-{format:?}"));
+            write_poly_mesh(&dir, &mesh, &patches, format).unwrap();
+            let (_, read) = read_poly_mesh(&dir).unwrap();
+            for (a, b) in read.iter().zip(&patches) {
+                let (
+                    Some(PatchCoupling::Cyclic {
+                        transform: ta @ Transform::Rotation { .. },
+                        ..
+                    }),
+                    Some(PatchCoupling::Cyclic { transform: tb, .. }),
+                ) = (&a.coupling, &b.coupling)
+                else {
+                    assert_eq!(a, b);
+                    continue;
+                };
+                // Rotations pass through an angle in degrees
+                let p = Vector::new(0.3, 2.0, -1.0);
+                let diff = ta.transform_position(p) - tb.transform_position(p);
+                assert!(diff.mag() < 1e-12, "{a:?} != {b:?}");
+            }
+            assert_eq!(read.len(), patches.len());
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn read_openfoam_coupled_boundary() {
+        let dir = scratch_dir("openfoam-coupled");
+        let path = dir.join("boundary");
+        let boundary = "3\n(\n    periodic0\n    {\n        type            cyclic;\n        inGroups        List<word> 1(cyclic);\n        nFaces          2;\n        startFace       10;\n        matchTolerance  0.0001;\n        transform       translational;\n        neighbourPatch  periodic1;\n        separationVector (0 0 -2);\n    }\n    periodic1\n    {\n        type            cyclic;\n        nFaces          2;\n        startFace       12;\n        neighbourPatch  periodic0;\n        transform       rotational;\n        rotationAxis    (0 0 1);\n        rotationCentre  (0 0 0);\n        rotationAngle   90;\n    }\n    procBoundary1to0\n    {\n        type            processor;\n        inGroups        List<word> 1(processor);\n        nFaces          3;\n        startFace       14;\n        matchTolerance  0.0001;\n        transform       unknown;\n        myProcNo        1;\n        neighbProcNo    0;\n    }\n)\n";
+        std::fs::write(&path, header("polyBoundaryMesh", "ascii") + boundary).unwrap();
+        let patches = read_file(&path, parse_boundary).unwrap();
+
+        assert_eq!(
+            patches[0],
+            PatchDef::new("periodic0", "cyclic", 10, 2).with_coupling(PatchCoupling::Cyclic {
+                neighbor_patch: "periodic1".into(),
+                transform: Transform::Translation {
+                    separation: Vector::new(0.0, 0.0, 2.0),
+                },
+            })
+        );
+        let Some(PatchCoupling::Cyclic {
+            neighbor_patch,
+            transform,
+        }) = &patches[1].coupling
+        else {
+            panic!("{:?}", patches[1]);
+        };
+        assert_eq!(neighbor_patch, "periodic0");
+        // periodic1 turns onto periodic0 by +90 degrees, so a point of
+        // periodic0 maps back by -90 degrees
+        let p = transform.transform_position(Vector::new(0.0, 1.0, 0.0));
+        assert!((p - Vector::new(1.0, 0.0, 0.0)).mag() < 1e-12, "{p:?}");
+        assert_eq!(
+            patches[2],
+            PatchDef::new("procBoundary1to0", "processor", 14, 3).with_coupling(
+                PatchCoupling::Processor {
+                    my_rank: 1,
+                    neighbor_rank: 0,
+                }
+            )
+        );
+
+        // A cyclic patch needs its neighbor
+        let unpaired = boundary.replace("        neighbourPatch  periodic0;\n", "");
+        std::fs::write(&path, header("polyBoundaryMesh", "ascii") + &unpaired).unwrap();
+        let err = read_file(&path, parse_boundary).unwrap_err();
+        assert!(err.to_string().contains("neighbourPatch"), "{err}");
+
+        // A corrupt patch count is an error, not an allocation
+        std::fs::write(
+            &path,
+            header("polyBoundaryMesh", "ascii") + "1000000000000000\n(\n)\n",
+        )
+        .unwrap();
+        assert!(read_file(&path, parse_boundary).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn written_header_carries_class_and_note() {
+        let mesh = PrimitiveMesh::unit_cube(1, 1, 1).unwrap();
+        let patches = [PatchDef::new("walls", "wall", 0, 6)];
+        let dir = scratch_dir("header");
+        write_poly_mesh(&dir, &mesh, &patches, FoamFormat::Binary).unwrap();
+        let owner = std::fs::read(dir.join("owner")).unwrap();
+        let text = String::from_utf8_lossy(&owner);
+        assert!(text.contains("format      binary;"));
+        assert!(text.contains("note        \"nPoints:8 nCells:1 nFaces:6 nInternalFaces:0\";"));
+        let faces = std::fs::read(dir.join("faces")).unwrap();
+        assert!(String::from_utf8_lossy(&faces).contains("class       faceCompactList;"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+//! STL and OBJ export of boundary surfaces.
+//!
+//! Both writers take a [`BoundarySurface`] as extracted by
+//! [`PrimitiveMesh::boundary_surface`](dugong_mesh::PrimitiveMesh::boundary_surface).
+//! STL only holds triangles, so [`write_stl`] splits every polygon into a
+//! fan around its vertex average; ASCII files hold one `solid` per patch,
+//! binary files store the patch index in the attribute word of each
+//! triangle. OBJ keeps the polygons as they are, in one group per patch,
+//! and can carry feature edges as line elements.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use dugong_mesh::BoundarySurface;
+use dugong_types::tensor::Vector;
+
+use crate::error::IoError;
+
+/// Data encoding of an STL file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StlEncoding {
+    /// Human-readable text.
+    #[default]
+    Ascii,
+    /// Little-endian single-precision binary.
+    Binary,
+}
+
+/// Writes `surface` as an STL file.
+///
+/// # Errors
+///
+/// Returns `Err` if a binary file would need a patch index above 65535, or
+/// the file cannot be written.
+pub fn write_stl(
+    path: impl AsRef<Path>,
+    surface: &BoundarySurface,
+    encoding: StlEncoding,
+) -> Result<(), IoError> {
+    let path = path.as_ref();
+    let data = match encoding {
+        StlEncoding::Ascii => {
+            let mut text = String::new();
+            for (patch, name) in surface.patch_names.iter().enumerate() {
+                let _ = writeln!(text, "solid {name}");
+                let faces = (0..surface.n_faces()).filter(|&f| surface.patch_ids[f] == patch);
+                for triangle in faces.flat_map(|f| triangles(surface, f)) {
+                    let n = normal(&triangle);
+                    let _ = writeln!(text, "  facet normal {} {} {}", n.x(), n.y(), n.z());
+                    text.push_str("    outer loop\n");
+                    for v in &triangle {
+                        let _ = writeln!(text, "      vertex {} {} {}", v.x(), v.y(), v.z());
+                    }
+                    text.push_str("    endloop\n  endfacet\n");
+                }
+                let _ = writeln!(text, "endsolid {name}");
+            }
+            text.into_bytes()
+        }
+        StlEncoding::Binary => {
+            let mut header = [b' '; 80];
+            let title = b"dugong boundary surface";
+            header[..title.len()].copy_from_slice(title);
+            let mut data = header.to_vec();
+            let mut count: u32 = 0;
+            data.extend_from_slice(&count.to_le_bytes());
+            for f in 0..surface.n_faces() {
+                for triangle in triangles(surface, f) {
+                    for v in std::iter::once(normal(&triangle)).chain(triangle) {
+                        for c in v.as_array() {
+                            data.extend_from_slice(&(*c as f32).to_le_bytes());
+                        }
+                    }
+                    let patch = surface.patch_ids[f];
+                    let attribute = u16::try_from(patch).map_err(|_| IoError::StlPatchIndex {
+                        path: path.to_path_buf(),
+                        patch,
+                    })?;
+                    data.extend_from_slice(&attribute.to_le_bytes());
+                    count += 1;
+                }
+            }
+            data[80..84].copy_from_slice(&count.to_le_bytes());
+            data
+        }
+    };
+    write_bytes(path, &data)
+}
+
+/// Writes `surface` as a Wavefront OBJ file, followed by `feature_edges`
+/// (pairs of surface point indices, e.g. from
+/// [`BoundarySurface::feature_edges`]) as line elements in a
+/// `featureEdges` group. Pass an empty slice to write the faces only.
+///
+/// # Errors
+///
+/// Returns `Err` if the file cannot be written.
+pub fn write_obj(
+    path: impl AsRef<Path>,
+    surface: &BoundarySurface,
+    feature_edges: &[[usize; 2]],
+) -> Result<(), IoError> {
+    let mut text = String::from("# dugong boundary surface\n");
+    for p in &surface.points {
+        let _ = writeln!(text, "v {} {} {}", p.x(), p.y(), p.z());
+    }
+    for (patch, name) in surface.patch_names.iter().enumerate() {
+        let _ = writeln!(text, "g {name}");
+        for (face, _) in surface
+            .faces
+            .iter()
+            .zip(&surface.patch_ids)
+            .filter(|&(_, &id)| id == patch)
+        {
+            text.push('f');
+            // OBJ indices are 1-based
+            for &p in face {
+                let _ = write!(text, " {}", p + 1);
+            }
+            text.push('\n');
+        }
+    }
+    if !feature_edges.is_empty() {
+        text.push_str("g featureEdges\n");
+        for &[a, b] in feature_edges {
+            let _ = writeln!(text, "l {} {}", a + 1, b + 1);
+        }
+    }
+    write_bytes(path.as_ref(), text.as_bytes())
+}
+
+/// Splits face `f` into triangles: a triangle stays as it is, larger
+/// polygons become a fan around their vertex average.
+fn triangles(surface: &BoundarySurface, f: usize) -> Vec<[Vector; 3]> {
+    let (points, face) = (&surface.points, &surface.faces[f]);
+    let n = face.len();
+    if n == 3 {
+        return vec![[points[face[0]], points[face[1]], points[face[2]]]];
+    }
+    let center = face.iter().fold(Vector::zero(), |sum, &p| sum + points[p]) / n as f64;
+    (0..n)
+        .map(|i| [center, points[face[i]], points[face[(i + 1) % n]]])
+        .collect()
+}
+
+/// Returns the unit normal of a triangle, or zero if it is degenerate.
+fn normal([a, b, c]: &[Vector; 3]) -> Vector {
+    let n = (*b - *a).cross(&(*c - *a));
+    let mag = n.mag();
+    if mag > 0.0 { n / mag } else { n }
+}
+
+fn write_bytes(path: &Path, data: &[u8]) -> Result<(), IoError> {
+    std::fs::write(path, data).map_err(|source| IoError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dugong_mesh::CartesianBox;
+
+    fn scratch_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("dugong-io-{}-{name}", std::process::id()))
+    }
+
+    fn box_surface() -> BoundarySurface {
+        let (mesh, patches) =
+            CartesianBox::new(Vector::zero(), Vector::new(2.0, 1.0, 1.0), [2, 1, 1])
+                .build()
+                .unwrap();
+        mesh.boundary_surface(&patches).unwrap()
+    }
+
+    #[test]
+    fn stl_holds_one_solid_per_patch() {
+        let surface = box_surface();
+        let path = scratch_file("box.stl");
+        write_stl(&path, &surface, StlEncoding::Ascii).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.matches("endsolid").count(), 6);
+        assert!(text.starts_with("solid xMin\n"));
+        // Ten quads, four triangles each
+        assert_eq!(text.matches("endfacet").count(), 40);
+        assert!(text.contains("facet normal -1 0 0"));
+
+        write_stl(&path, &surface, StlEncoding::Binary).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        assert_eq!(u32::from_le_bytes(data[80..84].try_into().unwrap()), 40);
+        assert_eq!(data.len(), 84 + 40 * 50);
+        std::fs::remove_file(&path).unwrap();
+
+        let mut surface = surface;
+        surface.patch_ids[0] = 70_000;
+        assert!(matches!(
+            write_stl(&path, &surface, StlEncoding::Binary),
+            Err(IoError::StlPatchIndex { patch: 70_000, .. })
+        ));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn obj_keeps_polygons_and_feature_edges() {
+        let surface = box_surface();
+        let edges = surface.feature_edges(45.0);
+        let path = scratch_file("box.obj");
+        write_obj(&path, &surface, &edges).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let count = |prefix: &str| text.lines().filter(|l| l.starts_with(prefix)).count();
+        assert_eq!(count("v "), 12);
+        assert_eq!(count("f "), 10);
+        assert_eq!(count("g "), 7);
+        assert_eq!(count("l "), edges.len());
+        // 12 box edges, the four along x in two segments each
+        assert_eq!(edges.len(), 16);
+        // Indices are 1-based
+        for line in text
+            .lines()
+            .filter(|l| l.starts_with("f ") || l.starts_with("l "))
+        {
+            for index in line.split_whitespace().skip(1) {
+                assert!((1..=12).contains(&index.parse::<usize>().unwrap()));
+            }
+        }
+    }
+}
@@ -0,0 +1,645 @@
+//! VTK unstructured-grid output for ParaView.
+//!
+//! Meshes are written as legacy `.vtk` files ([`write_vtk`]) or XML `.vtu`
+//! files ([`write_vtu`]). Every cell is exported as a `VTK_POLYHEDRON` with
+//! an explicit face stream, so arbitrary polyhedral cells are supported; each
+//! face is oriented outward from the cell that lists it. Per-cell arrays are
+//! attached as cell data, and [`write_pvd`] writes the `.pvd` index that ties
+//! a time series of files together.
+//!
+//! In [`VtkEncoding::Binary`], legacy files carry raw big-endian data and
+//! XML files carry base64-encoded little-endian data with a 64-bit byte-count
+//! header per array.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use dugong_mesh::PrimitiveMesh;
+use dugong_types::tensor::{SymmTensor, Tensor, Vector};
+
+use crate::error::IoError;
+
+/// VTK cell type code of `VTK_POLYHEDRON`.
+const VTK_POLYHEDRON: u8 = 42;
+
+/// Data encoding of a VTK file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VtkEncoding {
+    /// Human-readable text.
+    #[default]
+    Ascii,
+    /// Raw big-endian data (legacy) or base64 (XML).
+    Binary,
+}
+
+/// Values of one per-cell array.
+#[derive(Clone, Copy, Debug)]
+pub enum CellValues<'a> {
+    Scalar(&'a [f64]),
+    Vector(&'a [Vector]),
+    /// Nine components per cell in row-major order.
+    Tensor(&'a [Tensor]),
+    /// Six components per cell in VTK order (`xx yy zz xy yz xz`).
+    SymmTensor(&'a [SymmTensor]),
+}
+
+impl CellValues<'_> {
+    fn len(&self) -> usize {
+        match self {
+            CellValues::Scalar(v) => v.len(),
+            CellValues::Vector(v) => v.len(),
+            CellValues::Tensor(v) => v.len(),
+            CellValues::SymmTensor(v) => v.len(),
+        }
+    }
+
+    fn n_components(&self) -> usize {
+        match self {
+            CellValues::Scalar(_) => 1,
+            CellValues::Vector(_) => 3,
+            CellValues::Tensor(_) => 9,
+            CellValues::SymmTensor(_) => 6,
+        }
+    }
+
+    /// Returns the components of all cells, flattened.
+    fn components(&self) -> Vec<f64> {
+        match self {
+            CellValues::Scalar(v) => v.to_vec(),
+            CellValues::Vector(v) => v.iter().flat_map(|x| *x.as_array()).collect(),
+            CellValues::Tensor(v) => v.iter().flat_map(|x| *x.as_array()).collect(),
+            CellValues::SymmTensor(v) => v
+                .iter()
+                .flat_map(|s| [s.xx(), s.yy(), s.zz(), s.xy(), s.yz(), s.xz()])
+                .collect(),
+        }
+    }
+}
+
+/// A named per-cell array.
+#[derive(Clone, Copy, Debug)]
+pub struct CellData<'a> {
+    /// Array name shown in ParaView.
+    pub name: &'a str,
+    /// One value per cell.
+    pub values: CellValues<'a>,
+}
+
+impl<'a> CellData<'a> {
+    /// Creates a named array.
+    pub fn new(name: &'a str, values: CellValues<'a>) -> Self {
+        Self { name, values }
+    }
+}
+
+/// Polyhedral cell description shared by both file formats.
+struct PolyCells {
+    /// Points of each cell, concatenated.
+    connectivity: Vec<i64>,
+    /// End of each cell in `connectivity`.
+    offsets: Vec<i64>,
+    /// Face stream of each cell (`n_faces, n_pts, ids..., n_pts, ids...`),
+    /// concatenated.
+    faces: Vec<i64>,
+    /// End of each cell in `faces`.
+    face_offsets: Vec<i64>,
+}
+
+impl PolyCells {
+    fn new(mesh: &PrimitiveMesh) -> Self {
+        let owner = mesh.owner();
+        let mut cells = Self {
+            connectivity: Vec::new(),
+            offsets: Vec::with_capacity(mesh.n_cells()),
+            faces: Vec::new(),
+            face_offsets: Vec::with_capacity(mesh.n_cells()),
+        };
+        for (c, cell_faces) in mesh.cell_faces().iter().enumerate() {
+            cells
+                .connectivity
+                .extend(mesh.cell_points()[c].iter().map(|&p| p as i64));
+            cells.offsets.push(cells.connectivity.len() as i64);
+
+            cells.faces.push(cell_faces.len() as i64);
+            for &f in cell_faces {
+                let face = &mesh.faces()[f];
+                cells.faces.push(face.len() as i64);
+                if owner[f] == c {
+                    cells.faces.extend(face.iter().map(|&p| p as i64));
+                } else {
+                    // Neighbor side: reverse so the normal points out of `c`
+                    cells.faces.extend(face.iter().rev().map(|&p| p as i64));
+                }
+            }
+            cells.face_offsets.push(cells.faces.len() as i64);
+        }
+        cells
+    }
+
+    /// Returns the legacy `CELLS` stream: `n_values, n_faces, face stream`
+    /// per cell.
+    fn legacy_stream(&self) -> Vec<i64> {
+        let mut stream = Vec::with_capacity(self.faces.len() + self.face_offsets.len());
+        let mut start = 0;
+        for &end in &self.face_offsets {
+            let cell = &self.faces[start as usize..end as usize];
+            stream.push(cell.len() as i64);
+            stream.extend_from_slice(cell);
+            start = end;
+        }
+        stream
+    }
+}
+
+/// Writes `mesh` and `cell_data` as a legacy `.vtk` unstructured grid.
+///
+/// # Errors
+///
+/// Returns `Err` if an array does not have one value per cell, a `CELLS`
+/// entry does not fit the format's 32-bit `int`, or the file cannot be
+/// written.
+pub fn write_vtk(
+    path: impl AsRef<Path>,
+    mesh: &PrimitiveMesh,
+    cell_data: &[CellData<'_>],
+    encoding: VtkEncoding,
+) -> Result<(), IoError> {
+    let path = path.as_ref();
+    check_lengths(path, mesh, cell_data)?;
+    let cells = PolyCells::new(mesh);
+    let mut out = LegacyWriter {
+        data: Vec::new(),
+        encoding,
+    };
+
+    out.text("# vtk DataFile Version 4.2\ndugong mesh\n");
+    out.text(match encoding {
+        VtkEncoding::Ascii => "ASCII\n",
+        VtkEncoding::Binary => "BINARY\n",
+    });
+    out.text("DATASET UNSTRUCTURED_GRID\n");
+
+    out.text(&format!("POINTS {} double\n", mesh.n_points()));
+    let coords: Vec<f64> = mesh.points().iter().flat_map(|p| *p.as_array()).collect();
+    out.values(&coords, 3);
+
+    let stream = cells.legacy_stream();
+    out.text(&format!("CELLS {} {}\n", mesh.n_cells(), stream.len()));
+    let stream = legacy_labels(path, &stream)?;
+    out.cell_values(&stream, &cells.face_offsets);
+
+    out.text(&format!("CELL_TYPES {}\n", mesh.n_cells()));
+    let types = vec![VTK_POLYHEDRON as i32; mesh.n_cells()];
+    out.values(&types, 1);
+
+    if !cell_data.is_empty() {
+        out.text(&format!("CELL_DATA {}\n", mesh.n_cells()));
+        out.text(&format!("FIELD FieldData {}\n", cell_data.len()));
+        for array in cell_data {
+            let n_comp = array.values.n_components();
+            out.text(&format!(
+                "{} {} {} double\n",
+                array.name,
+                n_comp,
+                array.values.len()
+            ));
+            out.values(&array.values.components(), n_comp);
+        }
+    }
+
+    write_bytes(path, &out.data)
+}
+
+/// Writes `mesh` and `cell_data` as an XML `.vtu` unstructured grid.
+///
+/// # Errors
+///
+/// Returns `Err` if an array does not have one value per cell, or the file
+/// cannot be written.
+pub fn write_vtu(
+    path: impl AsRef<Path>,
+    mesh: &PrimitiveMesh,
+    cell_data: &[CellData<'_>],
+    encoding: VtkEncoding,
+) -> Result<(), IoError> {
+    let path = path.as_ref();
+    check_lengths(path, mesh, cell_data)?;
+    let cells = PolyCells::new(mesh);
+    let mut xml = String::new();
+
+    xml.push_str("<?xml version=\"1.0\"?>\n");
+    xml.push_str(
+        "<VTKFile type=\"UnstructuredGrid\" version=\"1.0\" byte_order=\"LittleEndian\" header_type=\"UInt64\">\n",
+    );
+    xml.push_str("  <UnstructuredGrid>\n");
+    let _ = writeln!(
+        xml,
+        "    <Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">",
+        mesh.n_points(),
+        mesh.n_cells()
+    );
+
+    xml.push_str("      <Points>\n");
+    let coords: Vec<f64> = mesh.points().iter().flat_map(|p| *p.as_array()).collect();
+    data_array(&mut xml, None, 3, &coords, encoding);
+    xml.push_str("      </Points>\n");
+
+    xml.push_str("      <Cells>\n");
+    data_array(
+        &mut xml,
+        Some("connectivity"),
+        1,
+        &cells.connectivity,
+        encoding,
+    );
+    data_array(&mut xml, Some("offsets"), 1, &cells.offsets, encoding);
+    let types = vec![VTK_POLYHEDRON; mesh.n_cells()];
+    data_array(&mut xml, Some("types"), 1, &types, encoding);
+    data_array(&mut xml, Some("faces"), 1, &cells.faces, encoding);
+    data_array(
+        &mut xml,
+        Some("faceoffsets"),
+        1,
+        &cells.face_offsets,
+        encoding,
+    );
+    xml.push_str("      </Cells>\n");
+
+    if !cell_data.is_empty() {
+        xml.push_str("      <CellData>\n");
+        for array in cell_data {
+            data_array(
+                &mut xml,
+                Some(array.name),
+                array.values.n_components(),
+                &array.values.components(),
+                encoding,
+            );
+        }
+        xml.push_str("      </CellData>\n");
+    }
+
+    xml.push_str("    </Piece>\n  </UnstructuredGrid>\n</VTKFile>\n");
+    write_bytes(path, xml.as_bytes())
+}
+
+/// Writes a `.pvd` collection listing `(time, file)` entries.
+///
+/// File paths are written as given; relative paths are resolved by ParaView
+/// against the directory of the `.pvd` file.
+///
+/// # Errors
+///
+/// Returns `Err` if the file cannot be written.
+pub fn write_pvd<P: AsRef<Path>>(
+    path: impl AsRef<Path>,
+    entries: &[(f64, P)],
+) -> Result<(), IoError> {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\"?>\n");
+    xml.push_str("<VTKFile type=\"Collection\" version=\"0.1\" byte_order=\"LittleEndian\">\n");
+    xml.push_str("  <Collection>\n");
+    for (time, file) in entries {
+        let file = file.as_ref().to_string_lossy();
+        let _ = writeln!(
+            xml,
+            "    <DataSet timestep=\"{time}\" group=\"\" part=\"0\" file=\"{}\"/>",
+            xml_escape(&file)
+        );
+    }
+    xml.push_str("  </Collection>\n</VTKFile>\n");
+    write_bytes(path.as_ref(), xml.as_bytes())
+}
+
+fn check_lengths(
+    path: &Path,
+    mesh: &PrimitiveMesh,
+    cell_data: &[CellData<'_>],
+) -> Result<(), IoError> {
+    for array in cell_data {
+        if array.values.len() != mesh.n_cells() {
+            return Err(IoError::FieldLength {
+                path: path.to_path_buf(),
+                name: array.name.to_string(),
+                expected: mesh.n_cells(),
+                got: array.values.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Narrows the legacy `CELLS` stream to the 32-bit `int` the format stores.
+fn legacy_labels(path: &Path, values: &[i64]) -> Result<Vec<i32>, IoError> {
+    values
+        .iter()
+        .map(|&v| {
+            i32::try_from(v).map_err(|_| IoError::LabelOverflow {
+                path: path.to_path_buf(),
+                label: v as usize,
+                bits: 32,
+            })
+        })
+        .collect()
+}
+
+fn write_bytes(path: &Path, data: &[u8]) -> Result<(), IoError> {
+    std::fs::write(path, data).map_err(|source| IoError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// A numeric type that can be written to VTK files.
+trait VtkValue: Copy + std::fmt::Display {
+    /// XML `type` attribute.
+    const XML_TYPE: &'static str;
+    fn extend_le(self, out: &mut Vec<u8>);
+    fn extend_be(self, out: &mut Vec<u8>);
+}
+
+macro_rules! vtk_value {
+    ($ty:ty, $name:literal) => {
+        impl VtkValue for $ty {
+            const XML_TYPE: &'static str = $name;
+
+            fn extend_le(self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn extend_be(self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_be_bytes());
+            }
+        }
+    };
+}
+
+vtk_value!(f64, "Float64");
+vtk_value!(i64, "Int64");
+vtk_value!(i32, "Int32");
+vtk_value!(u8, "UInt8");
+
+/// Appends one XML `<DataArray>` element.
+fn data_array<T: VtkValue>(
+    xml: &mut String,
+    name: Option<&str>,
+    n_components: usize,
+    values: &[T],
+    encoding: VtkEncoding,
+) {
+    let name = name.map_or(String::new(), |n| format!(" Name=\"{}\"", xml_escape(n)));
+    let format = match encoding {
+        VtkEncoding::Ascii => "ascii",
+        VtkEncoding::Binary => "binary",
+    };
+    let _ = write!(
+        xml,
+        "        <DataArray type=\"{}\"{name} NumberOfComponents=\"{n_components}\" format=\"{format}\">\n          ",
+        T::XML_TYPE
+    );
+    match encoding {
+        VtkEncoding::Ascii => {
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    xml.push(' ');
+                }
+                let _ = write!(xml, "{v}");
+            }
+        }
+        VtkEncoding::Binary => {
+            let mut bytes = Vec::with_capacity(8 + values.len() * 8);
+            bytes.extend_from_slice(&(size_of_val(values) as u64).to_le_bytes());
+            for &v in values {
+                v.extend_le(&mut bytes);
+            }
+            xml.push_str(&base64(&bytes));
+        }
+    }
+    xml.push_str("\n        </DataArray>\n");
+}
+
+/// Builds the body of a legacy file.
+struct LegacyWriter {
+    data: Vec<u8>,
+    encoding: VtkEncoding,
+}
+
+impl LegacyWriter {
+    fn text(&mut self, text: &str) {
+        self.data.extend_from_slice(text.as_bytes());
+    }
+
+    /// Appends `values`, `per_line` to a line in ASCII.
+    fn values<T: VtkValue>(&mut self, values: &[T], per_line: usize) {
+        match self.encoding {
+            VtkEncoding::Ascii => {
+                for row in values.chunks(per_line.max(1)) {
+                    let row: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+                    self.text(&row.join(" "));
+                    self.text("\n");
+                }
+            }
+            VtkEncoding::Binary => {
+                for &v in values {
+                    v.extend_be(&mut self.data);
+                }
+                self.text("\n");
+            }
+        }
+    }
+
+    /// Appends the `CELLS` stream, one cell per line in ASCII.
+    fn cell_values(&mut self, stream: &[i32], face_offsets: &[i64]) {
+        match self.encoding {
+            VtkEncoding::Ascii => {
+                let mut start = 0;
+                for (c, &end) in face_offsets.iter().enumerate() {
+                    // Each cell has one extra leading count in the stream
+                    let end = end as usize + c + 1;
+                    self.values(&stream[start..end], end - start);
+                    start = end;
+                }
+            }
+            VtkEncoding::Binary => self.values(stream, 1),
+        }
+    }
+}
+
+/// Encodes `bytes` as standard base64 with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Escapes the characters that are special in XML attribute values.
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("dugong-io-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn base64_matches_rfc4648_vectors() {
+        let cases = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(base64(input.as_bytes()), expected);
+        }
+    }
+
+    #[test]
+    fn legacy_labels_reject_values_beyond_i32() {
+        let path = Path::new("test.vtk");
+        assert_eq!(legacy_labels(path, &[0, 7, 42]).unwrap(), [0, 7, 42]);
+        let too_big = i64::from(i32::MAX) + 1;
+        assert!(matches!(
+            legacy_labels(path, &[0, too_big]),
+            Err(IoError::LabelOverflow { label, bits: 32, .. }) if label == too_big as usize
+        ));
+    }
+
+    #[test]
+    fn poly_cells_orient_faces_outward() {
+        let mesh = PrimitiveMesh::unit_cube(2, 1, 1).unwrap();
+        let cells = PolyCells::new(&mesh);
+        assert_eq!(cells.offsets, vec![8, 16]);
+        // Six quads per cell: 1 + 6 * 5 values
+        assert_eq!(cells.face_offsets, vec![31, 62]);
+        // Face 0 is internal, owned by cell 0: cell 1 lists it reversed
+        let face0: Vec<i64> = mesh.faces()[0].iter().map(|&p| p as i64).collect();
+        let cell1 = &cells.faces[31..62];
+        let pos = mesh.cell_faces()[1].iter().position(|&f| f == 0).unwrap();
+        let listed = &cell1[1 + 5 * pos + 1..1 + 5 * pos + 5];
+        let reversed: Vec<i64> = face0.iter().rev().copied().collect();
+        assert_eq!(listed, reversed.as_slice());
+        assert_eq!(cells.legacy_stream().len(), 64);
+    }
+
+    #[test]
+    fn vtu_ascii_contains_polyhedra_and_cell_data() {
+        let mesh = PrimitiveMesh::unit_cube(2, 1, 1).unwrap();
+        let p = [1.0, 2.5];
+        let u = [Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)];
+        let s = [SymmTensor::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0); 2];
+        let path = scratch_file("ascii.vtu");
+        let data = [
+            CellData::new("p", CellValues::Scalar(&p)),
+            CellData::new("U", CellValues::Vector(&u)),
+            CellData::new("R", CellValues::SymmTensor(&s)),
+        ];
+        write_vtu(&path, &mesh, &data, VtkEncoding::Ascii).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("NumberOfPoints=\"12\" NumberOfCells=\"2\""));
+        assert!(text.contains(
+            "Name=\"types\" NumberOfComponents=\"1\" format=\"ascii\">\n          42 42\n"
+        ));
+        assert!(
+            text.contains(
+                "Name=\"p\" NumberOfComponents=\"1\" format=\"ascii\">\n          1 2.5\n"
+            )
+        );
+        assert!(text.contains("Name=\"R\" NumberOfComponents=\"6\" format=\"ascii\">\n          1 4 6 2 5 3 1 4 6 2 5 3\n"));
+        assert!(text.contains("Name=\"faceoffsets\""));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn vtu_binary_arrays_carry_byte_count_header() {
+        let mesh = PrimitiveMesh::unit_cube(1, 1, 1).unwrap();
+        let path = scratch_file("binary.vtu");
+        write_vtu(&path, &mesh, &[], VtkEncoding::Binary).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        // One UInt8 type entry: 8-byte header (1) followed by 42
+        let mut expected = 1u64.to_le_bytes().to_vec();
+        expected.push(VTK_POLYHEDRON);
+        assert!(text.contains(&base64(&expected)), "{text}");
+        assert!(!text.contains("<CellData>"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn legacy_vtk_ascii_and_binary() {
+        let mesh = PrimitiveMesh::unit_cube(1, 1, 1).unwrap();
+        let t = [Tensor::identity()];
+        let data = [CellData::new("T", CellValues::Tensor(&t))];
+
+        let path = scratch_file("ascii.vtk");
+        write_vtk(&path, &mesh, &data, VtkEncoding::Ascii).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# vtk DataFile Version 4.2\n"));
+        assert!(text.contains("CELLS 1 32\n31 6 4 "));
+        assert!(text.contains("CELL_TYPES 1\n42\n"));
+        assert!(text.contains("T 9 1 double\n1 0 0 0 1 0 0 0 1\n"));
+
+        write_vtk(&path, &mesh, &data, VtkEncoding::Binary).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let marker = b"CELL_TYPES 1\n";
+        let at = bytes
+            .windows(marker.len())
+            .position(|w| w == marker)
+            .unwrap();
+        let start = at + marker.len();
+        assert_eq!(&bytes[start..start + 4], &42i32.to_be_bytes());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn wrong_array_length_is_rejected() {
+        let mesh = PrimitiveMesh::unit_cube(2, 1, 1).unwrap();
+        let p = [1.0];
+        let data = [CellData::new("p", CellValues::Scalar(&p))];
+        let result = write_vtu(scratch_file("bad.vtu"), &mesh, &data, VtkEncoding::Ascii);
+        assert!(matches!(
+            result,
+            Err(IoError::FieldLength {
+                expected: 2,
+                got: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn pvd_lists_time_steps() {
+        let path = scratch_file("series.pvd");
+        write_pvd(&path, &[(0.0, "case_0.vtu"), (0.5, "case_1.vtu")]).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            text.contains("<DataSet timestep=\"0\" group=\"\" part=\"0\" file=\"case_0.vtu\"/>")
+        );
+        assert!(text.contains("timestep=\"0.5\""));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+//! Affine transformation of whole meshes.
+//!
+//! The operations here map every point through `p ↦ A · p + b` and return a
+//! new [`PrimitiveMesh`] with the same topology. A transform with negative
+//! determinant (a mirror) turns cells inside out, so the point order of
+//! every face is reversed to keep face normals pointing out of the owner.
+
+use dugong_types::tensor::{Tensor, Vector};
+
+use crate::error::MeshError;
+use crate::primitive_mesh::PrimitiveMesh;
+use crate::transform::rotation_tensor;
+
+/// Determinant magnitude below which an affine map is treated as singular.
+const SINGULAR_DETERMINANT: f64 = 1e-300;
+
+impl PrimitiveMesh {
+    /// Returns the mesh with every point mapped through
+    /// `p ↦ tensor · p + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `tensor` is singular, which would collapse the cells.
+    pub fn affine_transformed(
+        &self,
+        tensor: &Tensor,
+        offset: Vector,
+    ) -> Result<PrimitiveMesh, MeshError> {
+        let det = tensor.det();
+        if det.abs() < SINGULAR_DETERMINANT || !det.is_finite() {
+            return Err(MeshError::SingularTransform { det });
+        }
+        Ok(self.map_points(|p| *tensor * p + offset, det < 0.0))
+    }
+
+    /// Returns the mesh shifted by `offset`.
+    pub fn translated(&self, offset: Vector) -> PrimitiveMesh {
+        self.map_points(|p| p + offset, false)
+    }
+
+    /// Returns the mesh rotated by `angle` radians, right-handed about the
+    /// axis through `origin` along `axis`.
+    ///
+    /// `axis` need not be normalized.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `axis` is zero.
+    pub fn rotated(
+        &self,
+        axis: Vector,
+        angle: f64,
+        origin: Vector,
+    ) -> Result<PrimitiveMesh, MeshError> {
+        let length = axis.mag();
+        if length == 0.0 || !length.is_finite() {
+            return Err(MeshError::SingularTransform { det: 0.0 });
+        }
+        let rotation = rotation_tensor(axis, angle);
+        Ok(self.map_points(|p| origin + rotation * (p - origin), false))
+    }
+
+    /// Returns the mesh scaled about `origin` by `factors` along x, y and z
+    /// (e.g. `0.001` on every axis to convert millimeters to meters).
+    ///
+    /// Negative factors mirror the mesh.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a factor is zero.
+    pub fn scaled(&self, factors: Vector, origin: Vector) -> Result<PrimitiveMesh, MeshError> {
+        let tensor = Tensor::new(
+            factors.x(),
+            0.0,
+            0.0,
+            0.0,
+            factors.y(),
+            0.0,
+            0.0,
+            0.0,
+            factors.z(),
+        );
+        self.affine_transformed(&tensor, origin - tensor * origin)
+    }
+
+    /// Returns the mirror image of the mesh in the plane through `point`
+    /// with normal `normal`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `normal` is zero.
+    pub fn mirrored(&self, point: Vector, normal: Vector) -> Result<PrimitiveMesh, MeshError> {
+        let n = normal / normal.mag();
+        if !n.mag().is_finite() {
+            return Err(MeshError::SingularTransform { det: 0.0 });
+        }
+        // Householder reflection I - 2 n n
+        let tensor = Tensor::identity() - n.outer(&n) * 2.0;
+        self.affine_transformed(&tensor, point - tensor * point)
+    }
+
+    /// Maps the points through `map`, reversing every face if `flip`.
+    fn map_points(&self, map: impl Fn(Vector) -> Vector, flip: bool) -> PrimitiveMesh {
+        let points = self.points().iter().map(|&p| map(p)).collect();
+        let mut faces = self.faces().to_nested();
+        if flip {
+            // Keep the first point, as OpenFOAM's face::flip does
+            for face in &mut faces {
+                face[1..].reverse();
+            }
+        }
+        PrimitiveMesh::new(
+            points,
+            faces,
+            self.owner().to_vec(),
+            self.neighbor().to_vec(),
+        )
+        .expect("point transforms preserve index ranges")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_valid(mesh: &PrimitiveMesh) {
+        let report = mesh.check_geometry();
+        assert!(report.is_ok(), "{:?}", report.errors);
+    }
+
+    #[test]
+    fn scaling_and_translation_keep_orientation() {
+        let mesh = PrimitiveMesh::unit_cube(2, 2, 2).unwrap();
+        let mm = mesh
+            .scaled(Vector::new(1e-3, 1e-3, 1e-3), Vector::zero())
+            .unwrap();
+        assert_valid(&mm);
+        assert!((mm.cell_volumes()[0] - 0.125e-9).abs() < 1e-24);
+        assert_eq!(mm.faces(), mesh.faces());
+
+        let moved = mesh.translated(Vector::new(1.0, 2.0, 3.0));
+        let d = moved.cell_centers()[7] - mesh.cell_centers()[7];
+        assert!((d - Vector::new(1.0, 2.0, 3.0)).mag() < 1e-12);
+    }
+
+    #[test]
+    fn rotation_preserves_volumes() {
+        let mesh = PrimitiveMesh::unit_cube(2, 1, 1).unwrap();
+        let turned = mesh
+            .rotated(
+                Vector::new(0.0, 0.0, 1.0),
+                std::f64::consts::FRAC_PI_2,
+                Vector::zero(),
+            )
+            .unwrap();
+        assert_valid(&turned);
+        // Cell 1 (center x = 0.75) swings onto the y axis
+        let c = turned.cell_centers()[1];
+        assert!((c - Vector::new(-0.5, 0.75, 0.5)).mag() < 1e-12);
+        assert!((turned.cell_volumes()[1] - 0.5).abs() < 1e-12);
+
+        assert!(matches!(
+            mesh.rotated(Vector::zero(), 1.0, Vector::zero()),
+            Err(MeshError::SingularTransform { .. })
+        ));
+    }
+
+    #[test]
+    fn mirroring_flips_faces() {
+        let mesh = PrimitiveMesh::unit_cube(2, 1, 1).unwrap();
+        let mirrored = mesh
+            .mirrored(Vector::zero(), Vector::new(1.0, 0.0, 0.0))
+            .unwrap();
+        assert_valid(&mirrored);
+        assert!(mirrored.check_topology().is_ok());
+        for (a, b) in mirrored.face_areas().iter().zip(mesh.face_areas()) {
+            // Reflected in x: the x component changes sign
+            assert!((*a - Vector::new(-b.x(), b.y(), b.z())).mag() < 1e-12);
+        }
+        assert_eq!(mirrored.faces()[0][0], mesh.faces()[0][0]);
+
+        assert!(matches!(
+            mesh.mirrored(Vector::zero(), Vector::zero()),
+            Err(MeshError::SingularTransform { .. })
+        ));
+        let flattened = mesh.scaled(Vector::new(1.0, 0.0, 1.0), Vector::zero());
+        assert!(matches!(
+            flattened,
+            Err(MeshError::SingularTransform { .. })
+        ));
+    }
+}
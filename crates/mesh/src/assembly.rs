@@ -0,0 +1,285 @@
+use std::collections::{HashMap, HashSet};
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::patch_def::PatchDef;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// Result of assembling a face-based mesh from cell-local face lists.
+pub(crate) struct Assembled {
+    /// The assembled mesh in OpenFOAM face order.
+    pub mesh: PrimitiveMesh,
+    /// Tag of each boundary face, indexed by `face - n_internal_faces`.
+    /// Boundary faces are sorted by tag, so equal tags form contiguous runs.
+    pub boundary_tags: Vec<usize>,
+}
+
+/// Builds a [`PrimitiveMesh`] from cells given as lists of outward-oriented
+/// faces.
+///
+/// `cells[c]` lists the faces of cell `c`, each as point indices ordered so
+/// that the right-hand rule gives a normal pointing out of `c`. Faces that
+/// appear in exactly two cells (compared as vertex sets) become internal
+/// faces owned by the lower-numbered cell; faces that appear once become
+/// boundary faces tagged with `boundary_tag(cell, local_face)`.
+///
+/// Internal faces are emitted in upper-triangular order (sorted by owner,
+/// then neighbor). Boundary faces follow, sorted by tag and then by their
+/// position in `cells`.
+///
+/// # Errors
+///
+/// Returns `Err` if a face is shared by more than two cells, appears twice in
+/// the same cell, or the resulting mesh fails [`PrimitiveMesh::new`]
+/// validation.
+pub(crate) fn assemble_cells(
+    points: Vec<Vector>,
+    cells: &[Vec<Vec<usize>>],
+    mut boundary_tag: impl FnMut(usize, usize) -> usize,
+) -> Result<Assembled, MeshError> {
+    // Sorted vertex set -> first occurrence
+    let mut seen: HashMap<Vec<usize>, (usize, usize)> = HashMap::new();
+    // (owner, neighbor, origin)
+    let mut internal: Vec<(usize, usize, (usize, usize))> = Vec::new();
+    let mut matched: HashSet<(usize, usize)> = HashSet::new();
+
+    for (c, cell_faces) in cells.iter().enumerate() {
+        for (lf, face) in cell_faces.iter().enumerate() {
+            let mut key = face.clone();
+            key.sort_unstable();
+            match seen.get(&key) {
+                None => {
+                    seen.insert(key, (c, lf));
+                }
+                Some(&(other, other_lf)) => {
+                    if other == c || matched.contains(&(other, other_lf)) {
+                        return Err(MeshError::NonManifoldFace { points: key });
+                    }
+                    matched.insert((other, other_lf));
+                    matched.insert((c, lf));
+                    // Cells are visited in ascending order, so `other < c`
+                    internal.push((other, c, (other, other_lf)));
+                }
+            }
+        }
+    }
+
+    internal.sort_by_key(|&(o, n, _)| (o, n));
+
+    let mut boundary: Vec<(usize, usize, usize)> = Vec::new();
+    for (c, cell_faces) in cells.iter().enumerate() {
+        for lf in 0..cell_faces.len() {
+            if !matched.contains(&(c, lf)) {
+                boundary.push((boundary_tag(c, lf), c, lf));
+            }
+        }
+    }
+    // Stable sort keeps cell order within each tag
+    boundary.sort_by_key(|&(tag, _, _)| tag);
+
+    let n_faces = internal.len() + boundary.len();
+    let mut faces = Vec::with_capacity(n_faces);
+    let mut owner = Vec::with_capacity(n_faces);
+    let mut neighbor = Vec::with_capacity(internal.len());
+    for &(o, n, (c, lf)) in &internal {
+        faces.push(cells[c][lf].clone());
+        owner.push(o);
+        neighbor.push(n);
+    }
+    let mut boundary_tags = Vec::with_capacity(boundary.len());
+    for &(tag, c, lf) in &boundary {
+        faces.push(cells[c][lf].clone());
+        owner.push(c);
+        boundary_tags.push(tag);
+    }
+
+    let mesh = PrimitiveMesh::new(points, faces, owner, neighbor)?;
+    Ok(Assembled {
+        mesh,
+        boundary_tags,
+    })
+}
+
+/// Returns the patches of a mesh assembled with boundary tags that index
+/// `patches`, keeping their names, types and coupling data. Patches without
+/// faces are kept with size zero.
+pub(crate) fn relabel_patches(patches: &[PatchDef], assembled: &Assembled) -> Vec<PatchDef> {
+    let mut start = assembled.mesh.n_internal_faces();
+    patches
+        .iter()
+        .enumerate()
+        .map(|(p, def)| {
+            let size = assembled.boundary_tags.iter().filter(|&&t| t == p).count();
+            let def = PatchDef {
+                start,
+                size,
+                ..def.clone()
+            };
+            start += size;
+            def
+        })
+        .collect()
+}
+
+/// Merges points that lie within `tol` of each other.
+///
+/// Returns the merged point list and, for every input point, the index of
+/// its representative in the merged list. The first point encountered in a
+/// cluster becomes the representative, so the result is deterministic.
+pub(crate) fn merge_points(points: &[Vector], tol: f64) -> (Vec<Vector>, Vec<usize>) {
+    let bin = |v: f64| (v / tol).floor() as i64;
+    let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+    let mut merged: Vec<Vector> = Vec::new();
+    let mut map = Vec::with_capacity(points.len());
+    let tol_sqr = tol * tol;
+
+    for &p in points {
+        let (bx, by, bz) = (bin(p.x()), bin(p.y()), bin(p.z()));
+        let mut found = None;
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if let Some(candidates) = grid.get(&(bx + dx, by + dy, bz + dz)) {
+                        for &m in candidates {
+                            if (merged[m] - p).mag_sqr() <= tol_sqr {
+                                found = Some(m);
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let idx = match found {
+            Some(m) => m,
+            None => {
+                merged.push(p);
+                let m = merged.len() - 1;
+                grid.entry((bx, by, bz)).or_default().push(m);
+                m
+            }
+        };
+        map.push(idx);
+    }
+    (merged, map)
+}
+
+/// Returns the six outward-oriented faces of a hexahedron given its eight
+/// vertices in OpenFOAM order (`0-3` bottom, `4-7` top, both counter-clockwise
+/// when viewed from above).
+///
+/// Faces are returned in the order x-, x+, y-, y+, z-, z+ of the local
+/// coordinate system (`0→1` is x, `0→3` is y, `0→4` is z).
+pub(crate) fn hex_faces(v: &[usize; 8]) -> [Vec<usize>; 6] {
+    [
+        vec![v[0], v[4], v[7], v[3]],
+        vec![v[1], v[2], v[6], v[5]],
+        vec![v[0], v[1], v[5], v[4]],
+        vec![v[3], v[7], v[6], v[2]],
+        vec![v[0], v[3], v[2], v[1]],
+        vec![v[4], v[5], v[6], v[7]],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube_points(offset_x: f64) -> Vec<Vector> {
+        vec![
+            Vector::new(offset_x, 0.0, 0.0),
+            Vector::new(offset_x + 1.0, 0.0, 0.0),
+            Vector::new(offset_x + 1.0, 1.0, 0.0),
+            Vector::new(offset_x, 1.0, 0.0),
+            Vector::new(offset_x, 0.0, 1.0),
+            Vector::new(offset_x + 1.0, 0.0, 1.0),
+            Vector::new(offset_x + 1.0, 1.0, 1.0),
+            Vector::new(offset_x, 1.0, 1.0),
+        ]
+    }
+
+    /// Two unit hexes side by side in x, sharing points 1, 2, 5, 6.
+    fn two_hexes() -> (Vec<Vector>, Vec<Vec<Vec<usize>>>) {
+        let mut points = cube_points(0.0);
+        points.extend([
+            Vector::new(2.0, 0.0, 0.0),
+            Vector::new(2.0, 1.0, 0.0),
+            Vector::new(2.0, 0.0, 1.0),
+            Vector::new(2.0, 1.0, 1.0),
+        ]);
+        let c0 = hex_faces(&[0, 1, 2, 3, 4, 5, 6, 7]).to_vec();
+        let c1 = hex_faces(&[1, 8, 9, 2, 5, 10, 11, 6]).to_vec();
+        (points, vec![c0, c1])
+    }
+
+    #[test]
+    fn hex_faces_are_outward() {
+        let points = cube_points(0.0);
+        let faces = hex_faces(&[0, 1, 2, 3, 4, 5, 6, 7]);
+        let mesh = PrimitiveMesh::new(points, faces.to_vec(), vec![0; 6], vec![]).unwrap();
+        assert!((mesh.cell_volumes()[0] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn assemble_two_hexes_finds_internal_face() {
+        let (points, cells) = two_hexes();
+        let a = assemble_cells(points, &cells, |_, lf| lf).unwrap();
+        assert_eq!(a.mesh.n_cells(), 2);
+        assert_eq!(a.mesh.n_internal_faces(), 1);
+        assert_eq!(a.mesh.n_faces(), 11);
+        assert_eq!(a.mesh.owner()[0], 0);
+        assert_eq!(a.mesh.neighbor()[0], 1);
+        // Internal face area points from owner to neighbor (+x)
+        assert!(a.mesh.face_areas()[0].x() > 0.0);
+        for (i, &v) in a.mesh.cell_volumes().iter().enumerate() {
+            assert!((v - 1.0).abs() < 1e-12, "cell {i} volume {v}");
+        }
+    }
+
+    #[test]
+    fn assemble_sorts_boundary_faces_by_tag() {
+        let (points, cells) = two_hexes();
+        // Tag by local face (x-, x+, y-, y+, z-, z+), reversed
+        let a = assemble_cells(points, &cells, |_, lf| 5 - lf).unwrap();
+        assert_eq!(a.boundary_tags, vec![0, 0, 1, 1, 2, 2, 3, 3, 4, 5]);
+        let expected = [
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(0.0, 0.0, -1.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, -1.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(-1.0, 0.0, 0.0),
+        ];
+        let nif = a.mesh.n_internal_faces();
+        for (i, &tag) in a.boundary_tags.iter().enumerate() {
+            let area = a.mesh.face_areas()[nif + i];
+            assert!((area - expected[tag]).mag() < 1e-12, "boundary face {i}");
+        }
+        // Within a tag, faces keep cell order
+        assert_eq!(a.mesh.owner()[nif], 0);
+        assert_eq!(a.mesh.owner()[nif + 1], 1);
+    }
+
+    #[test]
+    fn merge_points_collapses_coincident_points() {
+        let points = vec![
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(1e-9, -1e-9, 0.0),
+            Vector::new(1.0 + 1e-9, 0.0, 0.0),
+            Vector::new(0.5, 0.0, 0.0),
+        ];
+        let (merged, map) = merge_points(&points, 1e-6);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(map, vec![0, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn assemble_rejects_face_shared_by_three_cells() {
+        let (points, mut cells) = two_hexes();
+        cells.push(cells[0].clone());
+        let result = assemble_cells(points, &cells, |_, _| 0);
+        assert!(matches!(result, Err(MeshError::NonManifoldFace { .. })));
+    }
+}
@@ -0,0 +1,435 @@
+//! Mesh validation in the spirit of OpenFOAM's `checkMesh`.
+//!
+//! [`PrimitiveMesh::new`] only checks index ranges. The checks here verify
+//! the remaining topological conventions ([`PrimitiveMesh::check_topology`])
+//! and the geometric quality of the cells and faces
+//! ([`PrimitiveMesh::check_geometry`]).
+
+use std::collections::HashMap;
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// Relative face-area-sum magnitude above which a cell is reported as open.
+pub const CLOSED_CELL_TOLERANCE: f64 = 1e-6;
+
+/// Non-orthogonality angle (degrees) above which a face is counted as
+/// severely non-orthogonal.
+pub const NON_ORTHOGONALITY_THRESHOLD: f64 = 70.0;
+
+/// Skewness above which a face is counted as highly skewed.
+pub const SKEWNESS_THRESHOLD: f64 = 4.0;
+
+/// Outcome of [`PrimitiveMesh::check_topology`].
+#[derive(Debug, Default)]
+pub struct TopologyReport {
+    /// Every violation found, in the order the checks were run.
+    pub errors: Vec<MeshError>,
+}
+
+impl TopologyReport {
+    /// Returns `true` if no topology errors were found.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Converts the report into a `Result`, yielding the first error if any.
+    pub fn into_result(self) -> Result<(), MeshError> {
+        match self.errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Outcome of [`PrimitiveMesh::check_geometry`].
+///
+/// Angles are in degrees. Non-orthogonality statistics cover internal faces
+/// only; skewness covers all faces.
+#[derive(Debug, Default)]
+pub struct GeometryReport {
+    /// Sum of all cell volumes.
+    pub total_volume: f64,
+    /// Smallest cell volume.
+    pub min_volume: f64,
+    /// Largest cell volume.
+    pub max_volume: f64,
+    /// Largest `|Σ Sf| / Σ |Sf|` over all cells (0 for closed cells).
+    pub max_open_cell: f64,
+    /// Largest internal-face non-orthogonality angle.
+    pub max_non_orthogonality: f64,
+    /// Mean internal-face non-orthogonality angle.
+    pub avg_non_orthogonality: f64,
+    /// Number of internal faces above [`NON_ORTHOGONALITY_THRESHOLD`].
+    pub n_severely_non_orthogonal: usize,
+    /// Largest face skewness.
+    pub max_skewness: f64,
+    /// Number of faces above [`SKEWNESS_THRESHOLD`].
+    pub n_highly_skewed: usize,
+    /// Largest cell aspect ratio.
+    pub max_aspect_ratio: f64,
+    /// Hard geometric errors: open cells, non-positive volumes and inverted
+    /// face pyramids.
+    pub errors: Vec<MeshError>,
+}
+
+impl GeometryReport {
+    /// Returns `true` if no geometric errors were found.
+    ///
+    /// Threshold counts (non-orthogonality, skewness) are quality warnings
+    /// and do not affect the result.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Converts the report into a `Result`, yielding the first error if any.
+    pub fn into_result(self) -> Result<(), MeshError> {
+        match self.errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl PrimitiveMesh {
+    /// Checks the topological conventions not enforced by [`PrimitiveMesh::new`].
+    ///
+    /// The following are reported:
+    /// - faces with fewer than three points, or with a repeated point
+    /// - faces with the same point set as an earlier face
+    /// - points not used by any face
+    /// - cells bounded by fewer than four faces
+    /// - internal faces whose owner is not lower than their neighbor
+    /// - internal faces not sorted by owner and then neighbor
+    ///   (upper-triangular order)
+    pub fn check_topology(&self) -> TopologyReport {
+        let mut errors = Vec::new();
+
+        let mut point_used = vec![false; self.n_points()];
+        let mut face_index: HashMap<Vec<usize>, usize> = HashMap::new();
+        for (face, f) in self.faces().iter().enumerate() {
+            if f.len() < 3 {
+                errors.push(MeshError::TooFewFacePoints {
+                    face,
+                    n_points: f.len(),
+                });
+            }
+            let mut key = f.to_vec();
+            key.sort_unstable();
+            if let Some(w) = key.windows(2).find(|w| w[0] == w[1]) {
+                errors.push(MeshError::RepeatedFacePoint { face, point: w[0] });
+            }
+            for &p in f {
+                point_used[p] = true;
+            }
+            if let Some(&other) = face_index.get(&key) {
+                errors.push(MeshError::DuplicateFace { face, other });
+            } else {
+                face_index.insert(key, face);
+            }
+        }
+
+        for (point, &used) in point_used.iter().enumerate() {
+            if !used {
+                errors.push(MeshError::UnusedPoint { point });
+            }
+        }
+
+        for (cell, faces) in self.cell_faces().iter().enumerate() {
+            if faces.len() < 4 {
+                errors.push(MeshError::TooFewCellFaces {
+                    cell,
+                    n_faces: faces.len(),
+                });
+            }
+        }
+
+        let owner = self.owner();
+        let neighbor = self.neighbor();
+        for (face, &n) in neighbor.iter().enumerate() {
+            if owner[face] >= n {
+                errors.push(MeshError::OwnerNotLowerThanNeighbor {
+                    face,
+                    owner: owner[face],
+                    neighbor: n,
+                });
+            }
+            if face > 0 && (owner[face - 1], neighbor[face - 1]) > (owner[face], n) {
+                errors.push(MeshError::NotUpperTriangular { face });
+            }
+        }
+
+        TopologyReport { errors }
+    }
+
+    /// Computes geometric quality statistics and reports geometric errors.
+    ///
+    /// Errors are raised for open cells (relative face area sum above
+    /// [`CLOSED_CELL_TOLERANCE`]), non-positive cell volumes and face pyramids
+    /// with non-positive volume seen from the owner or neighbor center.
+    ///
+    /// The mesh should pass [`check_topology`](Self::check_topology) first;
+    /// geometric results on a topologically broken mesh are not meaningful.
+    pub fn check_geometry(&self) -> GeometryReport {
+        let mut report = GeometryReport {
+            min_volume: f64::INFINITY,
+            max_volume: f64::NEG_INFINITY,
+            ..Default::default()
+        };
+        let areas = self.face_areas();
+        let face_centers = self.face_centers();
+        let cell_centers = self.cell_centers();
+        let volumes = self.cell_volumes();
+        let owner = self.owner();
+        let neighbor = self.neighbor();
+
+        // Closed cells and volumes
+        let mut sum_closed = vec![Vector::zero(); self.n_cells()];
+        let mut sum_mag = vec![0.0_f64; self.n_cells()];
+        for (f, &o) in owner.iter().enumerate() {
+            sum_closed[o] += areas[f];
+            sum_mag[o] += areas[f].mag();
+        }
+        for (f, &n) in neighbor.iter().enumerate() {
+            sum_closed[n] -= areas[f];
+            sum_mag[n] += areas[f].mag();
+        }
+        for (cell, &volume) in volumes.iter().enumerate() {
+            let magnitude = if sum_mag[cell] > 0.0 {
+                sum_closed[cell].mag() / sum_mag[cell]
+            } else {
+                0.0
+            };
+            report.max_open_cell = report.max_open_cell.max(magnitude);
+            if magnitude > CLOSED_CELL_TOLERANCE {
+                report.errors.push(MeshError::OpenCell { cell, magnitude });
+            }
+            report.total_volume += volume;
+            report.min_volume = report.min_volume.min(volume);
+            report.max_volume = report.max_volume.max(volume);
+            if volume <= 0.0 {
+                report
+                    .errors
+                    .push(MeshError::NonPositiveVolume { cell, volume });
+            }
+        }
+
+        // Face pyramids
+        for (face, &o) in owner.iter().enumerate() {
+            let volume = areas[face] * (face_centers[face] - cell_centers[o]) / 3.0;
+            if volume <= 0.0 {
+                report.errors.push(MeshError::IncorrectFacePyramid {
+                    face,
+                    cell: o,
+                    volume,
+                });
+            }
+        }
+        for (face, &n) in neighbor.iter().enumerate() {
+            let volume = areas[face] * (cell_centers[n] - face_centers[face]) / 3.0;
+            if volume <= 0.0 {
+                report.errors.push(MeshError::IncorrectFacePyramid {
+                    face,
+                    cell: n,
+                    volume,
+                });
+            }
+        }
+
+        // Quality metrics
+        let non_orth = self.face_non_orthogonality();
+        let internal = &non_orth[..self.n_internal_faces()];
+        if !internal.is_empty() {
+            report.max_non_orthogonality = internal.iter().copied().fold(0.0, f64::max);
+            report.avg_non_orthogonality = internal.iter().sum::<f64>() / internal.len() as f64;
+            report.n_severely_non_orthogonal = internal
+                .iter()
+                .filter(|&&a| a > NON_ORTHOGONALITY_THRESHOLD)
+                .count();
+        }
+        let skewness = self.face_skewness();
+        report.max_skewness = skewness.iter().copied().fold(0.0, f64::max);
+        report.n_highly_skewed = skewness.iter().filter(|&&s| s > SKEWNESS_THRESHOLD).count();
+        let aspect = self.cell_aspect_ratio();
+        report.max_aspect_ratio = aspect.iter().copied().fold(0.0, f64::max);
+
+        if self.n_cells() == 0 {
+            report.min_volume = 0.0;
+            report.max_volume = 0.0;
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CartesianBox;
+
+    /// Unit cube points and the six outward faces of a single hex cell.
+    fn cube() -> (Vec<Vector>, Vec<Vec<usize>>) {
+        let points = vec![
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(1.0, 1.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(1.0, 0.0, 1.0),
+            Vector::new(1.0, 1.0, 1.0),
+            Vector::new(0.0, 1.0, 1.0),
+        ];
+        let faces = vec![
+            vec![0, 3, 2, 1],
+            vec![4, 5, 6, 7],
+            vec![0, 1, 5, 4],
+            vec![3, 7, 6, 2],
+            vec![0, 4, 7, 3],
+            vec![1, 2, 6, 5],
+        ];
+        (points, faces)
+    }
+
+    #[test]
+    fn generated_mesh_passes_all_checks() {
+        let (mesh, _) = CartesianBox::new(Vector::zero(), Vector::new(2.0, 1.0, 1.0), [4, 3, 2])
+            .with_grading([2.0, 1.0, 0.5])
+            .build()
+            .unwrap();
+        assert!(mesh.check_topology().is_ok());
+        let report = mesh.check_geometry();
+        assert!(report.is_ok(), "{:?}", report.errors);
+        assert!((report.total_volume - 2.0).abs() < 1e-12);
+        assert!(report.max_open_cell < 1e-12);
+        assert!(report.max_non_orthogonality < 1e-6);
+        assert_eq!(report.n_severely_non_orthogonal, 0);
+        assert_eq!(report.n_highly_skewed, 0);
+        assert!(report.max_aspect_ratio >= 1.0);
+    }
+
+    #[test]
+    fn degenerate_and_repeated_face_points_reported() {
+        let points = vec![Vector::zero(); 3];
+        let faces = vec![vec![0, 1], vec![0, 1, 1, 2]];
+        let mesh = PrimitiveMesh::new(points, faces, vec![0, 0], vec![]).unwrap();
+        let report = mesh.check_topology();
+        assert!(report.errors.iter().any(|e| matches!(
+            e,
+            MeshError::TooFewFacePoints {
+                face: 0,
+                n_points: 2
+            }
+        )));
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, MeshError::RepeatedFacePoint { face: 1, point: 1 }))
+        );
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, MeshError::TooFewCellFaces { cell: 0, .. }))
+        );
+    }
+
+    #[test]
+    fn duplicate_face_and_unused_point_reported() {
+        let (mut points, mut faces) = cube();
+        points.push(Vector::new(5.0, 5.0, 5.0));
+        faces.push(vec![1, 5, 6, 2]);
+        let mesh = PrimitiveMesh::new(points, faces, vec![0; 7], vec![]).unwrap();
+        let report = mesh.check_topology();
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, MeshError::DuplicateFace { face: 6, other: 5 }))
+        );
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, MeshError::UnusedPoint { point: 8 }))
+        );
+    }
+
+    #[test]
+    fn owner_above_neighbor_and_unsorted_faces_reported() {
+        let (mesh, _) = CartesianBox::new(Vector::zero(), Vector::new(3.0, 1.0, 1.0), [3, 1, 1])
+            .build()
+            .unwrap();
+        // Swap the two internal faces and flip owner/neighbor of one of them
+        let mut faces = mesh.faces().to_nested();
+        let mut owner = mesh.owner().to_vec();
+        let mut neighbor = mesh.neighbor().to_vec();
+        faces.swap(0, 1);
+        owner.swap(0, 1);
+        neighbor.swap(0, 1);
+        let report = PrimitiveMesh::new(
+            mesh.points().to_vec(),
+            faces.clone(),
+            owner.clone(),
+            neighbor.clone(),
+        )
+        .unwrap()
+        .check_topology();
+        assert!(matches!(
+            report.into_result(),
+            Err(MeshError::NotUpperTriangular { face: 1 })
+        ));
+
+        owner.swap(0, 1);
+        neighbor.swap(0, 1);
+        std::mem::swap(&mut owner[0], &mut neighbor[0]);
+        let report = PrimitiveMesh::new(mesh.points().to_vec(), faces, owner, neighbor)
+            .unwrap()
+            .check_topology();
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, MeshError::OwnerNotLowerThanNeighbor { face: 0, .. }))
+        );
+    }
+
+    #[test]
+    fn open_cell_reported() {
+        let (points, mut faces) = cube();
+        faces.pop();
+        let mesh = PrimitiveMesh::new(points, faces, vec![0; 5], vec![]).unwrap();
+        let report = mesh.check_geometry();
+        assert!(report.max_open_cell > CLOSED_CELL_TOLERANCE);
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, MeshError::OpenCell { cell: 0, .. }))
+        );
+    }
+
+    #[test]
+    fn inside_out_cell_reported() {
+        let (points, faces) = cube();
+        let faces = faces
+            .into_iter()
+            .map(|f| f.into_iter().rev().collect())
+            .collect();
+        let mesh = PrimitiveMesh::new(points, faces, vec![0; 6], vec![]).unwrap();
+        let report = mesh.check_geometry();
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, MeshError::NonPositiveVolume { cell: 0, .. }))
+        );
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, MeshError::IncorrectFacePyramid { cell: 0, .. }))
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Compact storage for lists of index lists.
+//!
+//! Face-to-point connectivity and the derived cell connectivity hold one
+//! short index list per face or cell. Storing them as `Vec<Vec<usize>>`
+//! costs an allocation and a 24-byte header per entry; [`CompactList`]
+//! stores all entries in one flat array with an offset table instead
+//! (the CSR layout), and hands out each entry as a slice.
+
+use std::fmt;
+use std::ops::Index;
+
+/// A list of variable-length sublists stored in compressed sparse row form.
+///
+/// Sublist `i` is `values[offsets[i]..offsets[i + 1]]`. The value type
+/// defaults to `usize`; `CompactList<u32>` halves the memory of large
+/// connectivity tables that only need to be stored.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CompactList<T = usize> {
+    offsets: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> CompactList<T> {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self {
+            offsets: vec![0],
+            values: Vec::new(),
+        }
+    }
+
+    /// Creates an empty list with room for `n_lists` sublists holding
+    /// `n_values` values in total.
+    pub fn with_capacity(n_lists: usize, n_values: usize) -> Self {
+        let mut offsets = Vec::with_capacity(n_lists + 1);
+        offsets.push(0);
+        Self {
+            offsets,
+            values: Vec::with_capacity(n_values),
+        }
+    }
+
+    /// Creates a list from its offset table and flat values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offsets` is empty, does not start at 0, is decreasing, or
+    /// does not end at `values.len()`.
+    pub fn from_parts(offsets: Vec<usize>, values: Vec<T>) -> Self {
+        assert_eq!(offsets.first(), Some(&0), "offsets must start at 0");
+        assert!(
+            offsets.windows(2).all(|w| w[0] <= w[1]),
+            "offsets must be non-decreasing"
+        );
+        assert_eq!(
+            offsets.last(),
+            Some(&values.len()),
+            "offsets must end at the number of values"
+        );
+        Self { offsets, values }
+    }
+
+    /// Returns the offset table, of length `len() + 1`.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Returns the values of all sublists, concatenated.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Splits the list into its offset table and flat values.
+    pub fn into_parts(self) -> (Vec<usize>, Vec<T>) {
+        (self.offsets, self.values)
+    }
+
+    /// Returns the number of sublists.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns `true` if there are no sublists.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns sublist `i`, or `None` if `i >= len()`.
+    pub fn get(&self, i: usize) -> Option<&[T]> {
+        let range = *self.offsets.get(i)?..*self.offsets.get(i + 1)?;
+        Some(&self.values[range])
+    }
+
+    /// Iterates over the sublists in order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+}
+
+impl<T: Clone> CompactList<T> {
+    /// Appends a sublist.
+    pub fn push(&mut self, list: &[T]) {
+        self.values.extend_from_slice(list);
+        self.offsets.push(self.values.len());
+    }
+
+    /// Returns the sublists as nested vectors.
+    pub fn to_nested(&self) -> Vec<Vec<T>> {
+        self.iter().map(<[T]>::to_vec).collect()
+    }
+}
+
+impl<T> Default for CompactList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CompactList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Index<usize> for CompactList<T> {
+    type Output = [T];
+
+    fn index(&self, i: usize) -> &[T] {
+        &self.values[self.offsets[i]..self.offsets[i + 1]]
+    }
+}
+
+impl<T: Clone, L: AsRef<[T]>> FromIterator<L> for CompactList<T> {
+    fn from_iter<I: IntoIterator<Item = L>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for sublist in iter {
+            list.push(sublist.as_ref());
+        }
+        list
+    }
+}
+
+impl<T: Clone> From<Vec<Vec<T>>> for CompactList<T> {
+    fn from(nested: Vec<Vec<T>>) -> Self {
+        nested.iter().collect()
+    }
+}
+
+impl<T: Clone> From<&[Vec<T>]> for CompactList<T> {
+    fn from(nested: &[Vec<T>]) -> Self {
+        nested.iter().collect()
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq<[Vec<T>]> for CompactList<T> {
+    fn eq(&self, other: &[Vec<T>]) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b.as_slice())
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq<Vec<Vec<T>>> for CompactList<T> {
+    fn eq(&self, other: &Vec<Vec<T>>) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CompactList<T> {
+    type Item = &'a [T];
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Iterator over the sublists of a [`CompactList`].
+#[derive(Clone)]
+pub struct Iter<'a, T> {
+    list: &'a CompactList<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(&self.list[self.front - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.back - self.front;
+        (n, Some(n))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<&'a [T]> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(&self.list[self.back])
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_nested_lists() {
+        let nested = vec![vec![0, 1, 2], vec![], vec![5, 3]];
+        let list = CompactList::from(nested.clone());
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.offsets(), &[0, 3, 3, 5]);
+        assert_eq!(list.values(), &[0, 1, 2, 5, 3]);
+        assert_eq!(&list[2], &[5, 3]);
+        assert!(list[1].is_empty());
+        assert_eq!(list.get(3), None);
+        assert_eq!(list, nested);
+        assert_eq!(list.to_nested(), nested);
+        assert_eq!(format!("{list:?}"), "[[0, 1, 2], [], [5, 3]]");
+    }
+
+    #[test]
+    fn iterates_from_both_ends() {
+        let list: CompactList<u32> = [vec![1], vec![2, 3], vec![4]].iter().collect();
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some(&[4][..]));
+        assert_eq!(iter.next(), Some(&[1][..]));
+        assert_eq!(iter.next(), Some(&[2, 3][..]));
+        assert_eq!(iter.next(), None);
+        assert_eq!(list.iter().skip(1).count(), 2);
+        assert_eq!(list.iter().nth(1), Some(&[2, 3][..]));
+    }
+
+    #[test]
+    #[should_panic(expected = "offsets must end")]
+    fn rejects_inconsistent_parts() {
+        let _ = CompactList::from_parts(vec![0, 2], vec![1, 2, 3]);
+    }
+}
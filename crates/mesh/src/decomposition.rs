@@ -0,0 +1,759 @@
+//! Domain decomposition into processor sub-meshes.
+//!
+//! [`PrimitiveMesh::partition`] assigns each cell to a rank, and
+//! [`Decomposition::new`] splits a [`PolyMesh`] accordingly. Every sub-mesh
+//! keeps all original patches (possibly empty) followed by one
+//! [`ProcessorPolyPatch`] per neighboring rank, named
+//! `procBoundary<rank>to<neighbor>`. Faces of a processor patch are ordered
+//! by global face index, so face `i` on both sides is the same face, each
+//! side holding it oriented out of its own cell.
+//!
+//! Each [`Subdomain`] records the global cell, face and point of each of its
+//! local ones, which is enough to scatter global fields and to gather local
+//! results back ([`Decomposition::reconstruct_cell_values`] and friends).
+//!
+//! Cyclic patches are kept as long as every pair of coupled faces ends up on
+//! the same rank; pairs split across ranks would need processor-cyclic
+//! patches, which are not supported.
+
+use std::collections::{BTreeMap, HashMap};
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::patches::{
+    CoupledPatch, PolyPatch, ProcessorPolyPatch, check_cyclic_pairs, patch_from_def,
+};
+use crate::poly_mesh::PolyMesh;
+use crate::primitive_mesh::PrimitiveMesh;
+use crate::zones::{FaceZone, Zone};
+
+mod graph;
+
+/// How cells are assigned to ranks by [`PrimitiveMesh::partition`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecompositionMethod {
+    /// Equal-count slabs along x, y and z, chosen independently per axis
+    /// from the cell centers. Cell `(i, j, k)` in slab space goes to rank
+    /// `i + n[0] * (j + n[1] * k)`.
+    Simple { n: [usize; 3] },
+    /// Nested equal-count splits: all cells along axis `order[0]`, then each
+    /// slab along `order[1]`, then along `order[2]`. Ranks are numbered as
+    /// for [`Simple`](Self::Simple). Parts differ by at most one cell.
+    Hierarchical { n: [usize; 3], order: [usize; 3] },
+    /// Multilevel recursive bisection of the cell graph
+    /// ([`PrimitiveMesh::cell_cells`]), minimizing the number of processor
+    /// faces.
+    Graph { n_parts: usize },
+}
+
+impl DecompositionMethod {
+    /// Returns the number of ranks produced.
+    pub fn n_parts(&self) -> usize {
+        match self {
+            DecompositionMethod::Simple { n } | DecompositionMethod::Hierarchical { n, .. } => {
+                n.iter().product()
+            }
+            DecompositionMethod::Graph { n_parts } => *n_parts,
+        }
+    }
+}
+
+impl PrimitiveMesh {
+    /// Returns the rank of each cell under `method`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the method asks for no ranks or for more ranks than
+    /// there are cells, a hierarchical `order` is not a permutation of the
+    /// axes, or a rank would receive no cells. [`Simple`] splits each axis
+    /// independently, so it leaves ranks empty when the slabs of different
+    /// axes select the same cells, e.g. `n: [2, 2, 1]` on a single row of
+    /// cells.
+    ///
+    /// [`Simple`]: DecompositionMethod::Simple
+    pub fn partition(&self, method: &DecompositionMethod) -> Result<Vec<usize>, MeshError> {
+        let n_parts = method.n_parts();
+        if n_parts == 0 || n_parts > self.n_cells() {
+            return Err(MeshError::InvalidPartCount {
+                n_parts,
+                n_cells: self.n_cells(),
+            });
+        }
+        let cell_to_rank = match method {
+            DecompositionMethod::Simple { n } => simple(self.cell_centers(), *n),
+            DecompositionMethod::Hierarchical { n, order } => {
+                let mut sorted = *order;
+                sorted.sort_unstable();
+                if sorted != [0, 1, 2] {
+                    return Err(MeshError::InvalidAxisOrder { order: *order });
+                }
+                hierarchical(self.cell_centers(), *n, *order)
+            }
+            DecompositionMethod::Graph { n_parts } => graph::partition(self.cell_cells(), *n_parts),
+        };
+        let mut used = vec![false; n_parts];
+        for &rank in &cell_to_rank {
+            used[rank] = true;
+        }
+        if let Some(rank) = used.iter().position(|&u| !u) {
+            return Err(MeshError::EmptyPart { rank, n_parts });
+        }
+        Ok(cell_to_rank)
+    }
+}
+
+impl PolyMesh {
+    /// Partitions the cells with `method` and splits the mesh accordingly.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if [`PrimitiveMesh::partition`] or
+    /// [`Decomposition::new`] fails.
+    pub fn decompose(&self, method: &DecompositionMethod) -> Result<Decomposition, MeshError> {
+        let cell_to_rank = self.partition(method)?;
+        Decomposition::new(self, cell_to_rank, method.n_parts())
+    }
+}
+
+/// The mesh of one rank and its addressing into the global mesh.
+pub struct Subdomain {
+    /// The local mesh.
+    pub mesh: PolyMesh,
+    /// Global cell of each local cell, ascending.
+    pub cell_addressing: Vec<usize>,
+    /// Global face of each local face.
+    pub face_addressing: Vec<usize>,
+    /// `true` for local faces stored reversed relative to the global face
+    /// (processor faces whose local cell is the global neighbor).
+    pub face_flipped: Vec<bool>,
+    /// Global point of each local point, ascending.
+    pub point_addressing: Vec<usize>,
+}
+
+/// A mesh split into per-rank [`Subdomain`]s.
+pub struct Decomposition {
+    cell_to_rank: Vec<usize>,
+    subdomains: Vec<Subdomain>,
+    n_faces: usize,
+    n_points: usize,
+}
+
+impl Decomposition {
+    /// Splits `mesh` into `n_parts` sub-meshes, cell `c` going to rank
+    /// `cell_to_rank[c]`.
+    ///
+    /// Local cells, points and non-processor faces keep their global
+    /// relative order. Processor patches carry the centers of the cells on
+    /// the other side, so the sub-meshes are ready for use without a
+    /// parallel exchange. Zones are split along with the mesh.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `cell_to_rank` has the wrong length or a rank
+    /// `>= n_parts`, the mesh already has processor patches, or a cyclic
+    /// face and its partner go to different ranks.
+    pub fn new(
+        mesh: &PolyMesh,
+        cell_to_rank: Vec<usize>,
+        n_parts: usize,
+    ) -> Result<Self, MeshError> {
+        if cell_to_rank.len() != mesh.n_cells() {
+            return Err(MeshError::CellRankLengthMismatch {
+                expected: mesh.n_cells(),
+                got: cell_to_rank.len(),
+            });
+        }
+        if let Some((cell, &rank)) = cell_to_rank
+            .iter()
+            .enumerate()
+            .find(|(_, r)| **r >= n_parts)
+        {
+            return Err(MeshError::CellRankOutOfRange {
+                cell,
+                rank,
+                n_parts,
+            });
+        }
+
+        if let Some(patch) = mesh
+            .patches()
+            .iter()
+            .find(|p| p.as_any().is::<ProcessorPolyPatch>())
+        {
+            return Err(MeshError::UnsupportedPatchType {
+                patch: patch.name().to_string(),
+                patch_type: patch.patch_type().to_string(),
+            });
+        }
+        check_cyclic_pairs(&mesh.patch_defs(), |f| cell_to_rank[mesh.owner()[f]])?;
+
+        let mut builders: Vec<SubdomainBuilder> = (0..n_parts)
+            .map(|_| SubdomainBuilder::new(mesh.patches().len()))
+            .collect();
+        let mut local_cell = vec![0; mesh.n_cells()];
+        for (c, &r) in cell_to_rank.iter().enumerate() {
+            local_cell[c] = builders[r].cell_addressing.len();
+            builders[r].cell_addressing.push(c);
+        }
+
+        let owner = mesh.owner();
+        let neighbor = mesh.neighbor();
+        let centers = mesh.cell_centers();
+        for (f, (&o, &n)) in owner.iter().zip(neighbor).enumerate() {
+            let (ro, rn) = (cell_to_rank[o], cell_to_rank[n]);
+            if ro == rn {
+                builders[ro]
+                    .internal
+                    .push((f, local_cell[o], local_cell[n]));
+            } else {
+                builders[ro]
+                    .processor
+                    .entry(rn)
+                    .or_default()
+                    .push(ProcessorFace {
+                        face: f,
+                        cell: local_cell[o],
+                        flipped: false,
+                        neighbor_center: centers[n],
+                    });
+                builders[rn]
+                    .processor
+                    .entry(ro)
+                    .or_default()
+                    .push(ProcessorFace {
+                        face: f,
+                        cell: local_cell[n],
+                        flipped: true,
+                        neighbor_center: centers[o],
+                    });
+            }
+        }
+        for (p, patch) in mesh.patches().iter().enumerate() {
+            for f in patch.range() {
+                let o = owner[f];
+                builders[cell_to_rank[o]].boundary[p].push((f, local_cell[o]));
+            }
+        }
+
+        let subdomains = builders
+            .into_iter()
+            .enumerate()
+            .map(|(rank, builder)| builder.build(mesh, rank, &local_cell))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            cell_to_rank,
+            subdomains,
+            n_faces: mesh.n_faces(),
+            n_points: mesh.n_points(),
+        })
+    }
+
+    /// Returns the number of ranks.
+    pub fn n_parts(&self) -> usize {
+        self.subdomains.len()
+    }
+
+    /// Returns the rank of each global cell.
+    pub fn cell_to_rank(&self) -> &[usize] {
+        &self.cell_to_rank
+    }
+
+    /// Returns the sub-mesh of each rank.
+    pub fn subdomains(&self) -> &[Subdomain] {
+        &self.subdomains
+    }
+
+    /// Consumes the decomposition, returning the sub-meshes.
+    pub fn into_subdomains(self) -> Vec<Subdomain> {
+        self.subdomains
+    }
+
+    /// Scatters per-cell values of the global mesh to the ranks.
+    pub fn decompose_cell_values<T: Clone>(&self, values: &[T]) -> Vec<Vec<T>> {
+        self.subdomains
+            .iter()
+            .map(|s| {
+                s.cell_addressing
+                    .iter()
+                    .map(|&c| values[c].clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Gathers per-cell values of the ranks into a global array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parts` does not hold one array per rank matching its
+    /// number of cells.
+    pub fn reconstruct_cell_values<T: Clone>(&self, parts: &[Vec<T>]) -> Vec<T> {
+        assert_eq!(parts.len(), self.n_parts(), "one array per rank expected");
+        let mut global: Vec<Option<T>> = vec![None; self.cell_to_rank.len()];
+        for (s, values) in self.subdomains.iter().zip(parts) {
+            assert_eq!(values.len(), s.cell_addressing.len(), "cell count mismatch");
+            for (&c, v) in s.cell_addressing.iter().zip(values) {
+                global[c] = Some(v.clone());
+            }
+        }
+        global.into_iter().flatten().collect()
+    }
+
+    /// Gathers per-face values of the ranks into a global array.
+    ///
+    /// Each processor face appears on two ranks; the value from the side
+    /// that is not flipped is used, so face fluxes come out in the global
+    /// orientation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parts` does not hold one array per rank matching its
+    /// number of faces.
+    pub fn reconstruct_face_values<T: Clone>(&self, parts: &[Vec<T>]) -> Vec<T> {
+        assert_eq!(parts.len(), self.n_parts(), "one array per rank expected");
+        let mut global: Vec<Option<T>> = vec![None; self.n_faces];
+        for (s, values) in self.subdomains.iter().zip(parts) {
+            assert_eq!(values.len(), s.face_addressing.len(), "face count mismatch");
+            for ((&f, &flipped), v) in s.face_addressing.iter().zip(&s.face_flipped).zip(values) {
+                if !flipped {
+                    global[f] = Some(v.clone());
+                }
+            }
+        }
+        global.into_iter().flatten().collect()
+    }
+
+    /// Gathers per-point values of the ranks into a global array. Points
+    /// shared by several ranks take the value of the lowest rank.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parts` does not hold one array per rank matching its
+    /// number of points.
+    pub fn reconstruct_point_values<T: Clone>(&self, parts: &[Vec<T>]) -> Vec<T> {
+        assert_eq!(parts.len(), self.n_parts(), "one array per rank expected");
+        let mut global: Vec<Option<T>> = vec![None; self.n_points];
+        for (s, values) in self.subdomains.iter().zip(parts) {
+            assert_eq!(
+                values.len(),
+                s.point_addressing.len(),
+                "point count mismatch"
+            );
+            for (&p, v) in s.point_addressing.iter().zip(values) {
+                global[p].get_or_insert_with(|| v.clone());
+            }
+        }
+        global.into_iter().flatten().collect()
+    }
+}
+
+/// A face on a processor boundary, seen from one side.
+struct ProcessorFace {
+    face: usize,
+    cell: usize,
+    flipped: bool,
+    neighbor_center: Vector,
+}
+
+/// Faces and cells of one rank collected in global order.
+struct SubdomainBuilder {
+    cell_addressing: Vec<usize>,
+    /// (global face, local owner, local neighbor)
+    internal: Vec<(usize, usize, usize)>,
+    /// Per global patch: (global face, local owner)
+    boundary: Vec<Vec<(usize, usize)>>,
+    /// Per neighboring rank, in global face order
+    processor: BTreeMap<usize, Vec<ProcessorFace>>,
+}
+
+impl SubdomainBuilder {
+    fn new(n_patches: usize) -> Self {
+        Self {
+            cell_addressing: Vec::new(),
+            internal: Vec::new(),
+            boundary: vec![Vec::new(); n_patches],
+            processor: BTreeMap::new(),
+        }
+    }
+
+    fn build(
+        self,
+        mesh: &PolyMesh,
+        rank: usize,
+        local_cell: &[usize],
+    ) -> Result<Subdomain, MeshError> {
+        let mut face_addressing = Vec::new();
+        let mut face_flipped = Vec::new();
+        let mut faces: Vec<Vec<usize>> = Vec::new();
+        let mut owner = Vec::new();
+        let mut neighbor = Vec::new();
+
+        for &(f, o, n) in &self.internal {
+            face_addressing.push(f);
+            face_flipped.push(false);
+            faces.push(mesh.faces()[f].to_vec());
+            owner.push(o);
+            neighbor.push(n);
+        }
+
+        let mut patches: Vec<Box<dyn PolyPatch>> = Vec::new();
+        for (patch, boundary) in mesh.patches().iter().zip(&self.boundary) {
+            let mut def = patch.to_patch_def();
+            def.start = faces.len();
+            def.size = boundary.len();
+            patches.push(patch_from_def(&def)?);
+            for &(f, o) in boundary {
+                face_addressing.push(f);
+                face_flipped.push(false);
+                faces.push(mesh.faces()[f].to_vec());
+                owner.push(o);
+            }
+        }
+        for (&neighbor_rank, proc_faces) in &self.processor {
+            let (my, other) = (rank as i32, neighbor_rank as i32);
+            let mut patch = ProcessorPolyPatch::new(
+                ProcessorPolyPatch::default_name(my, other),
+                faces.len(),
+                proc_faces.len(),
+                my,
+                other,
+            );
+            patch.set_neighbor_cell_centers(
+                proc_faces.iter().map(|pf| pf.neighbor_center).collect(),
+            );
+            patches.push(Box::new(patch));
+            for pf in proc_faces {
+                let mut face = mesh.faces()[pf.face].to_vec();
+                if pf.flipped {
+                    face.reverse();
+                }
+                face_addressing.push(pf.face);
+                face_flipped.push(pf.flipped);
+                faces.push(face);
+                owner.push(pf.cell);
+            }
+        }
+
+        // Keep the points used by local faces, in global order
+        let mut used = vec![false; mesh.n_points()];
+        for face in &faces {
+            for &p in face {
+                used[p] = true;
+            }
+        }
+        let mut local_point = vec![usize::MAX; mesh.n_points()];
+        let mut point_addressing = Vec::new();
+        for (p, _) in used.iter().enumerate().filter(|(_, u)| **u) {
+            local_point[p] = point_addressing.len();
+            point_addressing.push(p);
+        }
+        for face in &mut faces {
+            for p in face.iter_mut() {
+                *p = local_point[*p];
+            }
+        }
+        let points = point_addressing.iter().map(|&p| mesh.points()[p]).collect();
+
+        let primitive = PrimitiveMesh::new(points, faces, owner, neighbor)?;
+        let mut poly = PolyMesh::new(primitive, patches)?;
+
+        let local_face: HashMap<usize, (usize, bool)> = face_addressing
+            .iter()
+            .zip(&face_flipped)
+            .enumerate()
+            .map(|(i, (&f, &flipped))| (f, (i, flipped)))
+            .collect();
+        for zone in mesh.cell_zones() {
+            let indices = zone
+                .indices
+                .iter()
+                .filter(|&&c| self.cell_addressing.get(local_cell[c]) == Some(&c))
+                .map(|&c| local_cell[c])
+                .collect();
+            poly.add_cell_zone(Zone::new(zone.name.clone(), indices))?;
+        }
+        for zone in mesh.face_zones() {
+            let (indices, flip_map) = zone
+                .indices
+                .iter()
+                .zip(&zone.flip_map)
+                .filter_map(|(f, &flip)| local_face.get(f).map(|&(i, fl)| (i, flip != fl)))
+                .unzip();
+            poly.add_face_zone(FaceZone::new(zone.name.clone(), indices, flip_map)?)?;
+        }
+        for zone in mesh.point_zones() {
+            let indices = zone
+                .indices
+                .iter()
+                .filter(|&&p| local_point[p] != usize::MAX)
+                .map(|&p| local_point[p])
+                .collect();
+            poly.add_point_zone(Zone::new(zone.name.clone(), indices))?;
+        }
+
+        Ok(Subdomain {
+            mesh: poly,
+            cell_addressing: self.cell_addressing,
+            face_addressing,
+            face_flipped,
+            point_addressing,
+        })
+    }
+}
+
+/// Assigns each cell the index of its equal-count slab along each axis.
+fn simple(centers: &[Vector], n: [usize; 3]) -> Vec<usize> {
+    let n_cells = centers.len();
+    let mut slab = vec![[0usize; 3]; n_cells];
+    for axis in 0..3 {
+        let mut order: Vec<usize> = (0..n_cells).collect();
+        order.sort_by(|&a, &b| centers[a].as_array()[axis].total_cmp(&centers[b].as_array()[axis]));
+        for (position, &c) in order.iter().enumerate() {
+            slab[c][axis] = position * n[axis] / n_cells;
+        }
+    }
+    slab.iter()
+        .map(|s| s[0] + n[0] * (s[1] + n[1] * s[2]))
+        .collect()
+}
+
+/// Splits the cells along `order[0]`, then each group along `order[1]`,
+/// then along `order[2]`.
+fn hierarchical(centers: &[Vector], n: [usize; 3], order: [usize; 3]) -> Vec<usize> {
+    let mut slab = vec![[0usize; 3]; centers.len()];
+    let mut groups: Vec<Vec<usize>> = vec![(0..centers.len()).collect()];
+    for axis in order {
+        let mut next = Vec::with_capacity(groups.len() * n[axis]);
+        for mut group in groups {
+            group.sort_by(|&a, &b| {
+                centers[a].as_array()[axis].total_cmp(&centers[b].as_array()[axis])
+            });
+            let len = group.len();
+            for k in 0..n[axis] {
+                let part = group[k * len / n[axis]..(k + 1) * len / n[axis]].to_vec();
+                for &c in &part {
+                    slab[c][axis] = k;
+                }
+                next.push(part);
+            }
+        }
+        groups = next;
+    }
+    slab.iter()
+        .map(|s| s[0] + n[0] * (s[1] + n[1] * s[2]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CartesianBox;
+    use crate::patch_def::PatchCoupling;
+    use crate::transform::Transform;
+
+    fn box_mesh(nx: usize, ny: usize, nz: usize) -> PolyMesh {
+        let max = Vector::new(nx as f64, ny as f64, nz as f64);
+        let (primitive, patches) = CartesianBox::new(Vector::zero(), max, [nx, ny, nz])
+            .build()
+            .unwrap();
+        PolyMesh::from_patch_defs(primitive, &patches).unwrap()
+    }
+
+    fn processor(mesh: &PolyMesh, neighbor: i32) -> &ProcessorPolyPatch {
+        mesh.patches()
+            .iter()
+            .filter_map(|p| p.as_any().downcast_ref::<ProcessorPolyPatch>())
+            .find(|p| p.neighbor_rank() == Some(neighbor))
+            .unwrap()
+    }
+
+    #[test]
+    fn simple_split_couples_processor_faces() {
+        let mesh = box_mesh(4, 2, 1);
+        let d = mesh
+            .decompose(&DecompositionMethod::Simple { n: [2, 1, 1] })
+            .unwrap();
+        assert_eq!(d.n_parts(), 2);
+        let [s0, s1] = d.subdomains() else {
+            panic!("two subdomains expected");
+        };
+        assert_eq!(s0.mesh.n_cells(), 4);
+        assert_eq!(s1.mesh.n_cells(), 4);
+        assert!(s0.mesh.check_topology().is_ok());
+        assert!(s1.mesh.check_geometry().is_ok());
+        assert_eq!(
+            s0.mesh.patches().len(),
+            mesh.patches().len() + 1,
+            "original patches plus one processor patch"
+        );
+
+        let p01 = processor(&s0.mesh, 1);
+        let p10 = processor(&s1.mesh, 0);
+        assert_eq!(p01.name(), "procBoundary0to1");
+        assert!(p01.is_owner() && !p10.is_owner());
+        assert_eq!(p01.size(), 2);
+        assert_eq!(p10.size(), 2);
+        for i in 0..p01.size() {
+            let (f0, f1) = (p01.start() + i, p10.start() + i);
+            assert_eq!(s0.face_addressing[f0], s1.face_addressing[f1]);
+            let d = s0.mesh.face_centers()[f0] - s1.mesh.face_centers()[f1];
+            assert!(d.mag() < 1e-12);
+            let sum = s0.mesh.face_areas()[f0] + s1.mesh.face_areas()[f1];
+            assert!(sum.mag() < 1e-12, "processor faces must be opposed");
+            // Neighbor centers are the cells across the boundary
+            let c1 = s1.mesh.cell_centers()[p10.face_cells()[i]];
+            assert!((p01.neighbor_cell_centers()[i] - c1).mag() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn hierarchical_parts_are_balanced() {
+        let mesh = box_mesh(5, 3, 2);
+        let ranks = mesh
+            .partition(&DecompositionMethod::Hierarchical {
+                n: [2, 2, 1],
+                order: [0, 1, 2],
+            })
+            .unwrap();
+        let mut counts = [0; 4];
+        for r in ranks {
+            counts[r] += 1;
+        }
+        let max = counts.iter().max().unwrap();
+        let min = counts.iter().min().unwrap();
+        assert!(max - min <= 1, "{counts:?}");
+
+        let bad = mesh.partition(&DecompositionMethod::Hierarchical {
+            n: [2, 1, 1],
+            order: [0, 0, 2],
+        });
+        assert!(matches!(bad, Err(MeshError::InvalidAxisOrder { .. })));
+        let too_many = mesh.partition(&DecompositionMethod::Graph { n_parts: 31 });
+        assert!(matches!(too_many, Err(MeshError::InvalidPartCount { .. })));
+    }
+
+    #[test]
+    fn slab_methods_reject_empty_ranks() {
+        let row = box_mesh(4, 1, 1);
+        let simple = row.partition(&DecompositionMethod::Simple { n: [2, 2, 1] });
+        assert!(matches!(
+            simple,
+            Err(MeshError::EmptyPart {
+                rank: 1,
+                n_parts: 4
+            })
+        ));
+        // Nested splits never run out of cells
+        let hierarchical = row
+            .partition(&DecompositionMethod::Hierarchical {
+                n: [2, 2, 1],
+                order: [0, 1, 2],
+            })
+            .unwrap();
+        assert_eq!(hierarchical, [0, 2, 1, 3]);
+        assert!(
+            row.decompose(&DecompositionMethod::Simple { n: [2, 2, 1] })
+                .is_err()
+        );
+
+        // One rank per cell leaves none empty
+        let ranks = row
+            .partition(&DecompositionMethod::Simple { n: [4, 1, 1] })
+            .unwrap();
+        assert_eq!(ranks, [0, 1, 2, 3]);
+        let mut ranks = row
+            .partition(&DecompositionMethod::Graph { n_parts: 4 })
+            .unwrap();
+        ranks.sort_unstable();
+        assert_eq!(ranks, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn fields_round_trip_through_graph_decomposition() {
+        let mesh = box_mesh(6, 6, 2);
+        let d = mesh
+            .decompose(&DecompositionMethod::Graph { n_parts: 3 })
+            .unwrap();
+        let total: usize = d.subdomains().iter().map(|s| s.mesh.n_cells()).sum();
+        assert_eq!(total, mesh.n_cells());
+
+        let ids: Vec<usize> = (0..mesh.n_cells()).collect();
+        let parts = d.decompose_cell_values(&ids);
+        assert_eq!(d.reconstruct_cell_values(&parts), ids);
+
+        // Local face area vectors reassemble into the global ones
+        let areas: Vec<Vec<Vector>> = d
+            .subdomains()
+            .iter()
+            .map(|s| s.mesh.face_areas().to_vec())
+            .collect();
+        let global = d.reconstruct_face_values(&areas);
+        for (a, b) in global.iter().zip(mesh.face_areas()) {
+            assert!((*a - *b).mag() < 1e-12);
+        }
+
+        let points: Vec<Vec<Vector>> = d
+            .subdomains()
+            .iter()
+            .map(|s| s.mesh.points().to_vec())
+            .collect();
+        assert_eq!(d.reconstruct_point_values(&points), mesh.points());
+    }
+
+    #[test]
+    fn zones_follow_their_cells() {
+        let mut mesh = box_mesh(4, 1, 1);
+        mesh.add_cell_zone(Zone::new("right", vec![2, 3])).unwrap();
+        let d = Decomposition::new(&mesh, vec![0, 0, 1, 1], 2).unwrap();
+        assert_eq!(d.subdomains()[0].mesh.cell_zones()[0].len(), 0);
+        assert_eq!(d.subdomains()[1].mesh.cell_zones()[0].indices, vec![0, 1]);
+        let bad = Decomposition::new(&mesh, vec![0, 0, 1, 2], 2);
+        assert!(matches!(
+            bad,
+            Err(MeshError::CellRankOutOfRange { cell: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn cyclic_pairs_stay_on_one_rank() {
+        let (primitive, mut defs) =
+            CartesianBox::new(Vector::zero(), Vector::new(4.0, 2.0, 1.0), [4, 2, 1])
+                .build()
+                .unwrap();
+        for (p, neighbor_patch, dx) in [(0, "xMax", -4.0), (1, "xMin", 4.0)] {
+            defs[p].patch_type = "cyclic".to_string();
+            defs[p].coupling = Some(PatchCoupling::Cyclic {
+                neighbor_patch: neighbor_patch.to_string(),
+                transform: Transform::Translation {
+                    separation: Vector::new(dx, 0.0, 0.0),
+                },
+            });
+        }
+        let mesh = PolyMesh::from_patch_defs(primitive, &defs).unwrap();
+
+        let rows = mesh
+            .decompose(&DecompositionMethod::Simple { n: [1, 2, 1] })
+            .unwrap();
+        for s in rows.subdomains() {
+            let x_min = s.mesh.patches()[0].as_coupled().unwrap();
+            assert_eq!(x_min.face_cells(), [0]);
+            // The last cell of the row, one cell to the left of x = 0
+            let c = s.mesh.cell_centers()[0];
+            let expected = c - Vector::new(1.0, 0.0, 0.0);
+            assert!((x_min.neighbor_cell_centers()[0] - expected).mag() < 1e-12);
+        }
+
+        let columns = mesh.decompose(&DecompositionMethod::Simple { n: [2, 1, 1] });
+        assert!(matches!(
+            columns,
+            Err(MeshError::SplitCyclicPair { ref patch, .. }) if patch == "xMin"
+        ));
+
+        // Processor patches cannot be decomposed again
+        let sub = &rows.subdomains()[0].mesh;
+        let again = Decomposition::new(sub, vec![0; sub.n_cells()], 1);
+        assert!(matches!(again, Err(MeshError::UnsupportedPatchType { .. })));
+    }
+}
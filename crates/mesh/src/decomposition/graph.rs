@@ -0,0 +1,477 @@
+//! Multilevel recursive-bisection graph partitioner.
+//!
+//! Each bisection coarsens the graph by heavy-edge matching, splits the
+//! coarsest graph by greedy region growing, and projects the split back
+//! level by level with boundary refinement at each level. Parts are then
+//! bisected recursively until the requested count is reached.
+
+use crate::compact_list::CompactList;
+
+/// Coarsening stops once a graph has at most this many vertices.
+const COARSEST_SIZE: usize = 64;
+
+/// Number of region-growing seeds tried on the coarsest graph.
+const N_SEEDS: usize = 4;
+
+/// Maximum number of refinement passes per level.
+const MAX_REFINE_PASSES: usize = 8;
+
+/// Allowed part-weight deviation, as a fraction of the total weight.
+const IMBALANCE_TOLERANCE: f64 = 0.03;
+
+/// Partitions the graph `adjacency` into `n_parts` parts of nearly equal
+/// vertex count with few cut edges. Returns the part of each vertex.
+pub(crate) fn partition(adjacency: &CompactList, n_parts: usize) -> Vec<usize> {
+    let graph = Graph::from_adjacency(adjacency);
+    let vertices: Vec<usize> = (0..adjacency.len()).collect();
+    let mut part = vec![0; adjacency.len()];
+    recurse(&graph, &vertices, n_parts, 0, &mut part);
+    part
+}
+
+/// Assigns parts `first..first + n_parts` to `vertices`, the original ids
+/// of the vertices of `graph`.
+fn recurse(graph: &Graph, vertices: &[usize], n_parts: usize, first: usize, part: &mut [usize]) {
+    if n_parts <= 1 || graph.n_vertices() <= 1 {
+        for &v in vertices {
+            part[v] = first;
+        }
+        return;
+    }
+    let n_left = n_parts / 2;
+    let mut side = bisect(graph, n_left as f64 / n_parts as f64);
+    fill_sides(graph, &mut side, [n_left, n_parts - n_left]);
+    for (s, n_sub, first_sub) in [(0, n_left, first), (1, n_parts - n_left, first + n_left)] {
+        let selected: Vec<usize> = (0..graph.n_vertices()).filter(|&v| side[v] == s).collect();
+        let sub_vertices: Vec<usize> = selected.iter().map(|&v| vertices[v]).collect();
+        recurse(
+            &graph.subgraph(&selected),
+            &sub_vertices,
+            n_sub,
+            first_sub,
+            part,
+        );
+    }
+}
+
+/// Moves vertices across the split until side `s` holds at least
+/// `min_size[s]` vertices, so that no part ends up empty. Vertices adjacent
+/// to the receiving side are taken first. Needs `min_size[0] + min_size[1]`
+/// vertices in total.
+fn fill_sides(graph: &Graph, side: &mut [u8], min_size: [usize; 2]) {
+    let mut size = [0, 0];
+    for &s in side.iter() {
+        size[s as usize] += 1;
+    }
+    for to in [0u8, 1] {
+        let from = to ^ 1;
+        while size[to as usize] < min_size[to as usize] {
+            let v = (0..graph.n_vertices())
+                .filter(|&v| side[v] == from)
+                .max_by_key(|&v| graph.edge_split(v, side).0)
+                .expect("enough vertices for every part");
+            side[v] = to;
+            size[to as usize] += 1;
+            size[from as usize] -= 1;
+        }
+    }
+}
+
+/// Splits `graph` into sides 0 and 1 with side 0 holding `fraction` of the
+/// total vertex weight.
+fn bisect(graph: &Graph, fraction: f64) -> Vec<u8> {
+    let mut coarse: Vec<Graph> = Vec::new();
+    let mut maps: Vec<Vec<usize>> = Vec::new();
+    loop {
+        let g = coarse.last().unwrap_or(graph);
+        if g.n_vertices() <= COARSEST_SIZE {
+            break;
+        }
+        let (c, map) = g.coarsen();
+        // Stop when matching no longer shrinks the graph noticeably
+        if c.n_vertices() * 10 > g.n_vertices() * 9 {
+            break;
+        }
+        coarse.push(c);
+        maps.push(map);
+    }
+
+    let g = coarse.last().unwrap_or(graph);
+    let mut side = initial_bisection(g, fraction);
+    for level in (0..maps.len()).rev() {
+        let fine = if level == 0 {
+            graph
+        } else {
+            &coarse[level - 1]
+        };
+        side = maps[level].iter().map(|&c| side[c]).collect();
+        refine(fine, &mut side, fraction);
+    }
+    side
+}
+
+/// Grows side 0 from several seeds and keeps the refined split with the
+/// smallest cut.
+fn initial_bisection(graph: &Graph, fraction: f64) -> Vec<u8> {
+    let n = graph.n_vertices();
+    let mut best: Option<(u64, Vec<u8>)> = None;
+    for k in 0..N_SEEDS.min(n) {
+        let mut side = graph.grow(k * n / N_SEEDS, fraction);
+        refine(graph, &mut side, fraction);
+        let cut = graph.cut(&side);
+        if best.as_ref().is_none_or(|(c, _)| cut < *c) {
+            best = Some((cut, side));
+        }
+    }
+    best.map(|(_, side)| side).unwrap_or_default()
+}
+
+/// Greedily moves boundary vertices across the split to reduce the cut
+/// while keeping the side weights within tolerance, or to restore balance
+/// if they are outside it.
+fn refine(graph: &Graph, side: &mut [u8], fraction: f64) {
+    let total: u64 = graph.vwgt.iter().sum();
+    let target = fraction * total as f64;
+    let max_vwgt = graph.vwgt.iter().copied().max().unwrap_or(0);
+    let tolerance = (IMBALANCE_TOLERANCE * total as f64).max(max_vwgt as f64);
+    let mut weight0: u64 = (0..graph.n_vertices())
+        .filter(|&v| side[v] == 0)
+        .map(|v| graph.vwgt[v])
+        .sum();
+
+    for _ in 0..MAX_REFINE_PASSES {
+        let mut candidates: Vec<(i64, usize)> = (0..graph.n_vertices())
+            .filter_map(|v| {
+                let (external, internal) = graph.edge_split(v, side);
+                (external > 0).then_some((external as i64 - internal as i64, v))
+            })
+            .collect();
+        candidates.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut moved = false;
+        for (_, v) in candidates {
+            let (external, internal) = graph.edge_split(v, side);
+            let gain = external as i64 - internal as i64;
+            let w = graph.vwgt[v] as f64;
+            let imbalance = weight0 as f64 - target;
+            let new_imbalance = if side[v] == 0 {
+                imbalance - w
+            } else {
+                imbalance + w
+            };
+            let accept = if imbalance.abs() > tolerance {
+                new_imbalance.abs() < imbalance.abs()
+            } else {
+                new_imbalance.abs() <= tolerance
+                    && (gain > 0 || (gain == 0 && new_imbalance.abs() < imbalance.abs()))
+            };
+            if accept {
+                if side[v] == 0 {
+                    weight0 -= graph.vwgt[v];
+                } else {
+                    weight0 += graph.vwgt[v];
+                }
+                side[v] ^= 1;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// Weighted undirected graph in compressed sparse row form.
+struct Graph {
+    xadj: Vec<usize>,
+    adjncy: Vec<usize>,
+    adjwgt: Vec<u64>,
+    vwgt: Vec<u64>,
+}
+
+impl Graph {
+    fn from_adjacency(adjacency: &CompactList) -> Self {
+        let mut xadj = Vec::with_capacity(adjacency.len() + 1);
+        xadj.push(0);
+        let mut adjncy = Vec::new();
+        for (v, neighbors) in adjacency.iter().enumerate() {
+            adjncy.extend(neighbors.iter().copied().filter(|&u| u != v));
+            xadj.push(adjncy.len());
+        }
+        let adjwgt = vec![1; adjncy.len()];
+        Self {
+            xadj,
+            adjncy,
+            adjwgt,
+            vwgt: vec![1; adjacency.len()],
+        }
+    }
+
+    fn n_vertices(&self) -> usize {
+        self.vwgt.len()
+    }
+
+    /// Returns the neighbors of `v` with their edge weights.
+    fn edges(&self, v: usize) -> impl Iterator<Item = (usize, u64)> + '_ {
+        let range = self.xadj[v]..self.xadj[v + 1];
+        self.adjncy[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.adjwgt[range].iter().copied())
+    }
+
+    /// Returns the weight of the edges of `v` to the other side and to its
+    /// own side.
+    fn edge_split(&self, v: usize, side: &[u8]) -> (u64, u64) {
+        let mut external = 0;
+        let mut internal = 0;
+        for (u, w) in self.edges(v) {
+            if side[u] == side[v] {
+                internal += w;
+            } else {
+                external += w;
+            }
+        }
+        (external, internal)
+    }
+
+    /// Returns the total weight of the edges crossing the split.
+    fn cut(&self, side: &[u8]) -> u64 {
+        (0..self.n_vertices())
+            .map(|v| self.edge_split(v, side).0)
+            .sum::<u64>()
+            / 2
+    }
+
+    /// Collapses a heavy-edge matching. Returns the coarse graph and the
+    /// coarse vertex of each fine vertex.
+    fn coarsen(&self) -> (Graph, Vec<usize>) {
+        let n = self.n_vertices();
+        // Visiting low-degree vertices first leaves fewer of them unmatched
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&v| self.xadj[v + 1] - self.xadj[v]);
+        let mut mate = vec![usize::MAX; n];
+        for v in order {
+            if mate[v] != usize::MAX {
+                continue;
+            }
+            let heaviest = self
+                .edges(v)
+                .filter(|&(u, _)| mate[u] == usize::MAX)
+                .max_by_key(|&(u, w)| (w, std::cmp::Reverse(u)));
+            let u = heaviest.map_or(v, |(u, _)| u);
+            mate[v] = u;
+            mate[u] = v;
+        }
+
+        let mut map = vec![usize::MAX; n];
+        let mut members: Vec<(usize, usize)> = Vec::new();
+        for v in 0..n {
+            if map[v] == usize::MAX {
+                map[v] = members.len();
+                map[mate[v]] = members.len();
+                members.push((v, mate[v]));
+            }
+        }
+
+        let mut xadj = Vec::with_capacity(members.len() + 1);
+        xadj.push(0);
+        let mut adjncy = Vec::new();
+        let mut adjwgt = Vec::new();
+        let mut vwgt = Vec::with_capacity(members.len());
+        // Position of each coarse neighbor in the current row
+        let mut slot = vec![usize::MAX; members.len()];
+        for (c, &(a, b)) in members.iter().enumerate() {
+            let row_start = adjncy.len();
+            let fine = if a == b { &[a][..] } else { &[a, b][..] };
+            for &v in fine {
+                for (u, w) in self.edges(v) {
+                    let cu = map[u];
+                    if cu == c {
+                        continue;
+                    }
+                    if slot[cu] != usize::MAX && slot[cu] >= row_start {
+                        adjwgt[slot[cu]] += w;
+                    } else {
+                        slot[cu] = adjncy.len();
+                        adjncy.push(cu);
+                        adjwgt.push(w);
+                    }
+                }
+            }
+            xadj.push(adjncy.len());
+            vwgt.push(fine.iter().map(|&v| self.vwgt[v]).sum());
+        }
+
+        let coarse = Graph {
+            xadj,
+            adjncy,
+            adjwgt,
+            vwgt,
+        };
+        (coarse, map)
+    }
+
+    /// Grows side 0 breadth-first from `seed` until it holds `fraction` of
+    /// the total weight. Disconnected regions are entered from their lowest
+    /// vertex once the current one is exhausted.
+    fn grow(&self, seed: usize, fraction: f64) -> Vec<u8> {
+        let n = self.n_vertices();
+        let total: u64 = self.vwgt.iter().sum();
+        let target = (fraction * total as f64).round() as u64;
+        let mut side = vec![1u8; n];
+        let mut queued = vec![false; n];
+        let mut queue = std::collections::VecDeque::from([seed]);
+        queued[seed] = true;
+        let mut next_unvisited = 0;
+        let mut weight0 = 0;
+        while weight0 < target {
+            let v = match queue.pop_front() {
+                Some(v) => v,
+                None => {
+                    while next_unvisited < n && queued[next_unvisited] {
+                        next_unvisited += 1;
+                    }
+                    if next_unvisited == n {
+                        break;
+                    }
+                    queued[next_unvisited] = true;
+                    next_unvisited
+                }
+            };
+            side[v] = 0;
+            weight0 += self.vwgt[v];
+            for (u, _) in self.edges(v) {
+                if !queued[u] {
+                    queued[u] = true;
+                    queue.push_back(u);
+                }
+            }
+        }
+        side
+    }
+
+    /// Returns the subgraph induced by `selected`, renumbered in order.
+    fn subgraph(&self, selected: &[usize]) -> Graph {
+        let mut local = vec![usize::MAX; self.n_vertices()];
+        for (i, &v) in selected.iter().enumerate() {
+            local[v] = i;
+        }
+        let mut xadj = Vec::with_capacity(selected.len() + 1);
+        xadj.push(0);
+        let mut adjncy = Vec::new();
+        let mut adjwgt = Vec::new();
+        for &v in selected {
+            for (u, w) in self.edges(v) {
+                if local[u] != usize::MAX {
+                    adjncy.push(local[u]);
+                    adjwgt.push(w);
+                }
+            }
+            xadj.push(adjncy.len());
+        }
+        Graph {
+            xadj,
+            adjncy,
+            adjwgt,
+            vwgt: selected.iter().map(|&v| self.vwgt[v]).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adjacency of an `nx × ny` grid graph, x fastest.
+    fn grid(nx: usize, ny: usize) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![Vec::new(); nx * ny];
+        for j in 0..ny {
+            for i in 0..nx {
+                let v = i + nx * j;
+                if i + 1 < nx {
+                    adjacency[v].push(v + 1);
+                    adjacency[v + 1].push(v);
+                }
+                if j + 1 < ny {
+                    adjacency[v].push(v + nx);
+                    adjacency[v + nx].push(v);
+                }
+            }
+        }
+        adjacency
+    }
+
+    #[test]
+    fn grid_is_split_into_balanced_compact_parts() {
+        let adjacency = CompactList::from(grid(32, 32));
+        let part = partition(&adjacency, 4);
+        let mut counts = [0usize; 4];
+        for &p in &part {
+            counts[p] += 1;
+        }
+        for c in counts {
+            assert!(c.abs_diff(256) <= 256 / 10, "part sizes {counts:?}");
+        }
+        let cut = adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(v, ns)| ns.iter().map(move |&u| (v, u)))
+            .filter(|&(v, u)| part[v] != part[u])
+            .count()
+            / 2;
+        // Four quadrants cut 64 edges; far above that means a poor split
+        assert!(cut <= 96, "cut {cut}");
+    }
+
+    /// Returns the number of vertices in each of `n_parts` parts.
+    fn part_sizes(part: &[usize], n_parts: usize) -> Vec<usize> {
+        let mut sizes = vec![0; n_parts];
+        for &p in part {
+            sizes[p] += 1;
+        }
+        sizes
+    }
+
+    #[test]
+    fn odd_part_counts_are_balanced() {
+        let adjacency = CompactList::from(grid(30, 10));
+        for n_parts in [3, 5, 7] {
+            let part = partition(&adjacency, n_parts);
+            let ideal = 300 / n_parts;
+            for size in part_sizes(&part, n_parts) {
+                assert!(size.abs_diff(ideal) <= ideal / 5, "{n_parts} parts: {size}");
+            }
+        }
+    }
+
+    #[test]
+    fn small_graphs_leave_no_part_empty() {
+        for n in 1..=6 {
+            let adjacency = CompactList::from(grid(n, 1));
+            for n_parts in 1..=n {
+                let part = partition(&adjacency, n_parts);
+                let sizes = part_sizes(&part, n_parts);
+                assert!(sizes.iter().all(|&s| s > 0), "{n} vertices: {sizes:?}");
+            }
+        }
+        // One part per vertex
+        let part = partition(&CompactList::from(grid(3, 3)), 9);
+        assert_eq!(part_sizes(&part, 9), [1; 9]);
+    }
+
+    #[test]
+    fn disconnected_components_are_kept_apart() {
+        // Two 8 x 8 grids with no edge between them
+        let mut adjacency = grid(8, 8);
+        adjacency.extend(
+            grid(8, 8)
+                .into_iter()
+                .map(|ns| ns.into_iter().map(|u| u + 64).collect::<Vec<_>>()),
+        );
+        let part = partition(&CompactList::from(adjacency), 2);
+        assert_eq!(part_sizes(&part, 2), [64, 64]);
+        assert!(part[..64].iter().all(|&p| p == part[0]));
+        assert!(part[64..].iter().all(|&p| p == part[64]));
+    }
+}
@@ -0,0 +1,255 @@
+//! Conversion of a mesh into its polyhedral dual.
+//!
+//! Each point of the original mesh becomes one polyhedral cell made of the
+//! pieces of the original cells around it. The dual face between the cells
+//! of the two ends of an edge passes through the centers of the cells
+//! around that edge. On the boundary the dual cells are closed by one face
+//! per original boundary face around the point, bounded by the point, two
+//! edge midpoints and the face center. These pieces lie in the original
+//! boundary faces, so the boundary surface (and the total volume) is kept
+//! exactly.
+
+use std::collections::HashMap;
+
+use dugong_types::tensor::Vector;
+
+use crate::assembly::{assemble_cells, relabel_patches};
+use crate::error::MeshError;
+use crate::merge::check_patches;
+use crate::patch_def::PatchDef;
+use crate::primitive_mesh::PrimitiveMesh;
+
+impl PrimitiveMesh {
+    /// Returns the polyhedral dual of the mesh.
+    ///
+    /// `patches` describes the boundary of this mesh as in
+    /// [`PolyMesh`](crate::PolyMesh). Dual cell `p` surrounds original point
+    /// `p`, and the boundary pieces around it stay in the patch of their
+    /// original face, so the returned patches correspond one-to-one with
+    /// `patches`. Works for any cell shapes, though tetrahedral meshes give
+    /// the most useful result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `patches` does not partition the boundary or an edge
+    /// is not manifold (a cell with other than two faces on the edge, or
+    /// more than two boundary faces on it).
+    pub fn poly_dual(
+        &self,
+        patches: &[PatchDef],
+    ) -> Result<(PrimitiveMesh, Vec<PatchDef>), MeshError> {
+        check_patches(self, patches)?;
+        let faces = self.faces();
+        let owner = self.owner();
+        let n_internal = self.n_internal_faces();
+
+        // Faces around each edge
+        let mut edge_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (f, face) in faces.iter().enumerate() {
+            for i in 0..face.len() {
+                let (a, b) = (face[i], face[(i + 1) % face.len()]);
+                edge_faces.entry((a.min(b), a.max(b))).or_default().push(f);
+            }
+        }
+        let mut edges: Vec<(usize, usize)> = edge_faces.keys().copied().collect();
+        edges.sort_unstable();
+
+        // Dual points: cell centers, then boundary face centers, boundary
+        // edge midpoints and boundary points as they are first needed
+        let mut points = self.cell_centers().to_vec();
+        let mut boundary_point: HashMap<usize, usize> = HashMap::new();
+        let mut face_point: HashMap<usize, usize> = HashMap::new();
+        let mut edge_point: HashMap<(usize, usize), usize> = HashMap::new();
+        let add = |points: &mut Vec<Vector>, p: Vector| {
+            points.push(p);
+            points.len() - 1
+        };
+        for f in n_internal..self.n_faces() {
+            face_point.insert(f, add(&mut points, self.face_centers()[f]));
+        }
+        for &(a, b) in &edges {
+            if edge_faces[&(a, b)].iter().any(|&f| f >= n_internal) {
+                let mid = (self.points()[a] + self.points()[b]) * 0.5;
+                edge_point.insert((a, b), add(&mut points, mid));
+            }
+        }
+        for face in faces.iter().skip(n_internal) {
+            for &p in face {
+                boundary_point
+                    .entry(p)
+                    .or_insert_with(|| add(&mut points, self.points()[p]));
+            }
+        }
+
+        let mut cells: Vec<Vec<Vec<usize>>> = vec![Vec::new(); self.n_points()];
+        let mut tags: Vec<Vec<usize>> = vec![Vec::new(); self.n_points()];
+
+        // One dual face per edge, oriented from its lower to its higher end
+        for &(a, b) in &edges {
+            let around = &edge_faces[&(a, b)];
+            let boundary: Vec<usize> = around
+                .iter()
+                .copied()
+                .filter(|&f| f >= n_internal)
+                .collect();
+            let non_manifold = || MeshError::NonManifoldEdge { points: [a, b] };
+            let mut face = Vec::new();
+            let (start, last) = match boundary.as_slice() {
+                [] => (around[0], None),
+                &[first, last] => {
+                    face.push(edge_point[&(a, b)]);
+                    face.push(face_point[&first]);
+                    (first, Some(last))
+                }
+                _ => return Err(non_manifold()),
+            };
+
+            let first_cell = owner[start];
+            let (mut f, mut c) = (start, first_cell);
+            loop {
+                face.push(c);
+                let mut others = self.cell_faces()[c]
+                    .iter()
+                    .copied()
+                    .filter(|&g| g != f && faces[g].contains(&a) && faces[g].contains(&b));
+                let (Some(g), None) = (others.next(), others.next()) else {
+                    return Err(non_manifold());
+                };
+                if g >= n_internal {
+                    if Some(g) != last {
+                        return Err(non_manifold());
+                    }
+                    face.push(face_point[&g]);
+                    break;
+                }
+                let next = if owner[g] == c {
+                    self.neighbor()[g]
+                } else {
+                    owner[g]
+                };
+                if next == first_cell {
+                    break;
+                }
+                if face.len() > 2 * around.len() + 2 {
+                    return Err(non_manifold());
+                }
+                (f, c) = (g, next);
+            }
+
+            if polygon_normal(&points, &face) * (self.points()[b] - self.points()[a]) < 0.0 {
+                face.reverse();
+            }
+            let mut reversed = face.clone();
+            reversed.reverse();
+            cells[a].push(face);
+            tags[a].push(usize::MAX);
+            cells[b].push(reversed);
+            tags[b].push(usize::MAX);
+        }
+
+        // Boundary pieces around each point of each boundary face
+        for (p, def) in patches.iter().enumerate() {
+            for f in def.range() {
+                let face = &faces[f];
+                let n = face.len();
+                for i in 0..n {
+                    let (prev, v, next) = (face[(i + n - 1) % n], face[i], face[(i + 1) % n]);
+                    cells[v].push(vec![
+                        boundary_point[&v],
+                        edge_point[&(v.min(next), v.max(next))],
+                        face_point[&f],
+                        edge_point[&(v.min(prev), v.max(prev))],
+                    ]);
+                    tags[v].push(p);
+                }
+            }
+        }
+
+        let assembled = assemble_cells(points, &cells, |c, lf| tags[c][lf])?;
+        let dual_patches = relabel_patches(patches, &assembled);
+        let mesh = assembled.mesh;
+        Ok((mesh, dual_patches))
+    }
+}
+
+/// Returns the (unnormalized) normal of a possibly non-planar polygon,
+/// summed over the fan triangles around its vertex average.
+fn polygon_normal(points: &[Vector], face: &[usize]) -> Vector {
+    let mut center = Vector::zero();
+    for &p in face {
+        center += points[p];
+    }
+    center /= face.len() as f64;
+    let mut normal = Vector::zero();
+    for i in 0..face.len() {
+        let a = points[face[i]] - center;
+        let b = points[face[(i + 1) % face.len()]] - center;
+        normal += a.cross(&b);
+    }
+    normal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::{CartesianBox, CellShape, ShapeMesh};
+
+    fn assert_valid(mesh: &PrimitiveMesh) {
+        assert!(mesh.check_topology().is_ok());
+        let report = mesh.check_geometry();
+        assert!(report.is_ok(), "{:?}", report.errors);
+        assert!((report.total_volume - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn dual_of_a_hex_box() {
+        let (mesh, patches) =
+            CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [2, 2, 2])
+                .build()
+                .unwrap();
+        let (dual, dual_patches) = mesh.poly_dual(&patches).unwrap();
+
+        assert_eq!(dual.n_cells(), mesh.n_points());
+        assert_valid(&dual);
+        // The middle point becomes the cube [0.25, 0.75]³, corners 1/64
+        assert!((dual.cell_volumes()[13] - 0.125).abs() < 1e-12);
+        assert!((dual.cell_volumes()[0] - 1.0 / 64.0).abs() < 1e-12);
+        assert!((dual.cell_centers()[13] - Vector::new(0.5, 0.5, 0.5)).mag() < 1e-12);
+        // Each boundary face yields one piece per corner
+        let sizes: Vec<_> = dual_patches.iter().map(|p| p.size).collect();
+        assert_eq!(sizes, vec![16; 6]);
+    }
+
+    #[test]
+    fn dual_of_a_tet_cube() {
+        // Kuhn triangulation of the unit cube along the 0-7 diagonal
+        let points = (0..8)
+            .map(|i| Vector::new((i & 1) as f64, ((i >> 1) & 1) as f64, ((i >> 2) & 1) as f64))
+            .collect();
+        let cells = [
+            [0, 1, 3, 7],
+            [0, 1, 5, 7],
+            [0, 2, 3, 7],
+            [0, 2, 6, 7],
+            [0, 4, 5, 7],
+            [0, 4, 6, 7],
+        ]
+        .into_iter()
+        .map(CellShape::Tet)
+        .collect();
+        let (mesh, patches) = ShapeMesh::new(points, cells, Vec::new()).build().unwrap();
+        let (dual, dual_patches) = mesh.poly_dual(&patches).unwrap();
+
+        assert_eq!(dual.n_cells(), 8);
+        assert_valid(&dual);
+        // 12 boundary triangles with 3 pieces each
+        assert_eq!(dual_patches[0].size, 36);
+        // Every dual cell holds its original point
+        for p in 0..8 {
+            assert_eq!(
+                dual.find_cell(mesh.points()[p] * 0.98 + Vector::new(0.01, 0.01, 0.01)),
+                Some(p)
+            );
+        }
+    }
+}
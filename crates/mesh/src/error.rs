@@ -14,4 +14,156 @@ pub enum MeshError {
         point: usize,
         n_points: usize,
     },
+    #[error("cell count must be positive: direction {direction}")]
+    ZeroCellCount { direction: usize },
+    #[error("invalid extent: direction {direction}, min {min}, max {max}")]
+    InvalidExtent {
+        direction: usize,
+        min: f64,
+        max: f64,
+    },
+    #[error("grading must be positive and finite: direction {direction}, grading {grading}")]
+    InvalidGrading { direction: usize, grading: f64 },
+    #[error("face with points {points:?} is shared by more than two cells or repeated in one cell")]
+    NonManifoldFace { points: Vec<usize> },
+    #[error(
+        "block vertex index out of range: block {block}, vertex {vertex}, n_vertices {n_vertices}"
+    )]
+    BlockVertexOutOfRange {
+        block: usize,
+        vertex: usize,
+        n_vertices: usize,
+    },
+    #[error("block {block} is inside-out (negative volume)")]
+    InvertedBlock { block: usize },
+    #[error("patch {patch}: face {face:?} is not a face of any block")]
+    UnknownPatchFace { patch: String, face: [usize; 4] },
+    #[error("face {face:?} is listed in patch {patch} and in patch {other}")]
+    DuplicatePatchFace {
+        patch: String,
+        other: String,
+        face: [usize; 4],
+    },
+    #[error("patch {patch}: face {face:?} is shared by two blocks")]
+    InternalPatchFace { patch: String, face: [usize; 4] },
+    #[error("face {face} has {n_points} points, at least 3 required")]
+    TooFewFacePoints { face: usize, n_points: usize },
+    #[error("face {face} uses point {point} more than once")]
+    RepeatedFacePoint { face: usize, point: usize },
+    #[error("face {face} duplicates face {other}")]
+    DuplicateFace { face: usize, other: usize },
+    #[error("point {point} is not used by any face")]
+    UnusedPoint { point: usize },
+    #[error("cell {cell} has {n_faces} faces, at least 4 required")]
+    TooFewCellFaces { cell: usize, n_faces: usize },
+    #[error("internal face {face}: owner {owner} is not lower than neighbor {neighbor}")]
+    OwnerNotLowerThanNeighbor {
+        face: usize,
+        owner: usize,
+        neighbor: usize,
+    },
+    #[error("internal face {face} breaks upper-triangular ordering")]
+    NotUpperTriangular { face: usize },
+    #[error("cell {cell} is not closed: relative face area sum {magnitude}")]
+    OpenCell { cell: usize, magnitude: f64 },
+    #[error("cell {cell} has non-positive volume {volume}")]
+    NonPositiveVolume { cell: usize, volume: f64 },
+    #[error("face {face}: pyramid on cell {cell} has non-positive volume {volume}")]
+    IncorrectFacePyramid {
+        face: usize,
+        cell: usize,
+        volume: f64,
+    },
+    #[error(
+        "patch {patch}: start {start} does not follow the previous patch (expected {expected})"
+    )]
+    PatchStartMismatch {
+        patch: String,
+        start: usize,
+        expected: usize,
+    },
+    #[error("patches cover faces up to {covered}, but the mesh has {n_faces} faces")]
+    PatchCoverageMismatch { covered: usize, n_faces: usize },
+    #[error("duplicate patch name {name}")]
+    DuplicatePatchName { name: String },
+    #[error("patch {patch}: type {patch_type} cannot be built from a plain patch definition")]
+    UnsupportedPatchType { patch: String, patch_type: String },
+    #[error("cyclic patch {patch}: neighbor patch {neighbor} not found")]
+    UnknownNeighborPatch { patch: String, neighbor: String },
+    #[error("cyclic patch {patch}: size {size} differs from neighbor patch size {neighbor_size}")]
+    CyclicSizeMismatch {
+        patch: String,
+        size: usize,
+        neighbor_size: usize,
+    },
+    #[error("cyclic patch {patch}: face {face} has no unique partner on the neighbor patch")]
+    UnmatchedCyclicFace { patch: String, face: usize },
+    #[error("match tolerance {tolerance} is not positive and finite")]
+    InvalidMatchTolerance { tolerance: f64 },
+    #[error("cyclic patch {patch}: face {face} would be separated from its partner")]
+    SplitCyclicPair { patch: String, face: usize },
+    #[error("no zone named {name}")]
+    UnknownZone { name: String },
+    #[error("duplicate zone name {name}")]
+    DuplicateZoneName { name: String },
+    #[error("zone {zone}: index {index} out of range (len {len})")]
+    ZoneIndexOutOfRange {
+        zone: String,
+        index: usize,
+        len: usize,
+    },
+    #[error("face zone {zone}: {n_indices} indices but {n_flips} flip map entries")]
+    FlipMapLengthMismatch {
+        zone: String,
+        n_indices: usize,
+        n_flips: usize,
+    },
+    #[error("cell {cell}: vertex {vertex} out of range ({n_points} points)")]
+    CellVertexOutOfRange {
+        cell: usize,
+        vertex: usize,
+        n_points: usize,
+    },
+    #[error("patch {patch}: face {face:?} is not a face of any cell")]
+    UnknownBoundaryFace { patch: String, face: Vec<usize> },
+    #[error("patch {patch}: type {patch_type} conflicts with type {other_type}")]
+    PatchTypeMismatch {
+        patch: String,
+        patch_type: String,
+        other_type: String,
+    },
+    #[error("patch {patch}: coupling differs between the merged meshes")]
+    PatchCouplingMismatch { patch: String },
+    #[error("singular point transform (determinant {det})")]
+    SingularTransform { det: f64 },
+    #[error("cell index {cell} out of range ({n_cells} cells)")]
+    CellIndexOutOfRange { cell: usize, n_cells: usize },
+    #[error("no wall faces given")]
+    NoWallFaces,
+    #[error("face {face} is not a boundary face")]
+    NotBoundaryFace { face: usize },
+    #[error("cell {cell} is not a hexahedron")]
+    NotHexahedron { cell: usize },
+    #[error("cells {cell} and {neighbor} would differ by more than one refinement level")]
+    UnbalancedRefinement { cell: usize, neighbor: usize },
+    #[error("edge {points:?} is not manifold")]
+    NonManifoldEdge { points: [usize; 2] },
+    #[error("point count mismatch: expected {expected}, got {got}")]
+    PointCountMismatch { expected: usize, got: usize },
+    #[error("time step {delta_t} is not positive and finite")]
+    InvalidTimeStep { delta_t: f64 },
+    #[error("cannot split {n_cells} cells into {n_parts} parts")]
+    InvalidPartCount { n_parts: usize, n_cells: usize },
+    #[error("rank {rank} of {n_parts} receives no cells")]
+    EmptyPart { rank: usize, n_parts: usize },
+    #[error("axis order {order:?} is not a permutation of [0, 1, 2]")]
+    InvalidAxisOrder { order: [usize; 3] },
+    #[error("cell rank length mismatch: expected {expected}, got {got}")]
+    CellRankLengthMismatch { expected: usize, got: usize },
+    #[error("cell {cell}: rank {rank} out of range ({n_parts} parts)")]
+    CellRankOutOfRange {
+        cell: usize,
+        rank: usize,
+        n_parts: usize,
+    },
 }
@@ -0,0 +1,513 @@
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::generation::CartesianBox;
+use crate::ldu_addressing::{LduAddressing, LduMesh};
+use crate::motion::MeshMover;
+use crate::poly_mesh::PolyMesh;
+
+/// Lower bound of `n · d / |d|` used by the non-orthogonal delta
+/// coefficients, guarding against near-tangential cell-to-cell vectors.
+const MIN_NON_ORTH_COS: f64 = 0.05;
+
+/// A [`PolyMesh`] with the finite-volume data needed for discretization.
+///
+/// Interpolation factors are computed lazily on first access. Face-based
+/// quantities have one entry per face (`n_faces()`); boundary entries follow
+/// OpenFOAM: on coupled patches they use the neighbor cell center across the
+/// coupling, on all other patches the face center stands in for the
+/// neighbor cell center.
+///
+/// The mesh can move ([`FvMesh::move_points`], or [`FvMesh::update`] with a
+/// [`MeshMover`]); it then keeps the cell volumes of the two previous time
+/// levels and the mesh flux of the last step.
+///
+/// `FvMesh` dereferences to its [`PolyMesh`] (and from there to the
+/// [`PrimitiveMesh`](crate::PrimitiveMesh)).
+pub struct FvMesh {
+    poly: PolyMesh,
+
+    ldu_addressing: OnceLock<LduAddressing>,
+    deltas: OnceLock<Vec<Vector>>,
+    weights: OnceLock<Vec<f64>>,
+    delta_coeffs: OnceLock<Vec<f64>>,
+    non_orth_delta_coeffs: OnceLock<Vec<f64>>,
+    non_orth_correction_vectors: OnceLock<Vec<Vector>>,
+
+    v0: Option<Vec<f64>>,
+    v00: Option<Vec<f64>>,
+    mesh_phi: Option<Vec<f64>>,
+    mover: Option<Box<dyn MeshMover>>,
+}
+
+impl FvMesh {
+    /// Wraps a [`PolyMesh`].
+    pub fn new(poly: PolyMesh) -> Self {
+        Self {
+            poly,
+            ldu_addressing: OnceLock::new(),
+            deltas: OnceLock::new(),
+            weights: OnceLock::new(),
+            delta_coeffs: OnceLock::new(),
+            non_orth_delta_coeffs: OnceLock::new(),
+            non_orth_correction_vectors: OnceLock::new(),
+            v0: None,
+            v00: None,
+            mesh_phi: None,
+            mover: None,
+        }
+    }
+
+    /// Creates a uniform `nx × ny × nz` hexahedral mesh of the unit cube
+    /// `[0, 1]³` with the six [`CartesianBox`] patches.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if any of `nx`, `ny`, `nz` is zero.
+    pub fn unit_cube(nx: usize, ny: usize, nz: usize) -> Result<Self, MeshError> {
+        let (primitive, patches) =
+            CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [nx, ny, nz]).build()?;
+        Ok(Self::new(PolyMesh::from_patch_defs(primitive, &patches)?))
+    }
+
+    /// Returns the underlying poly mesh.
+    pub fn poly(&self) -> &PolyMesh {
+        &self.poly
+    }
+
+    /// Returns `true` once the mesh has moved.
+    pub fn moving(&self) -> bool {
+        self.mesh_phi.is_some()
+    }
+
+    /// Returns the cell volumes at the previous time level, or the current
+    /// volumes if the mesh has not moved.
+    pub fn v0(&self) -> &[f64] {
+        self.v0.as_deref().unwrap_or_else(|| self.cell_volumes())
+    }
+
+    /// Returns the cell volumes two time levels back, falling back to
+    /// [`v0`](Self::v0) before the second move.
+    pub fn v00(&self) -> &[f64] {
+        self.v00.as_deref().unwrap_or_else(|| self.v0())
+    }
+
+    /// Returns the mesh flux of each face (swept volume per unit time, out
+    /// of the owner) from the last move, or `None` if the mesh has not moved.
+    ///
+    /// With it the space conservation law `V - V0 = Δt Σ ±φ_mesh` holds for
+    /// every cell, the sign being `+` for owned faces.
+    pub fn mesh_phi(&self) -> Option<&[f64]> {
+        self.mesh_phi.as_deref()
+    }
+
+    /// Moves the points to `new_points` over a time step `delta_t`.
+    ///
+    /// The current volumes become [`v0`](Self::v0) and the previous `v0`
+    /// becomes [`v00`](Self::v00). The mesh flux is recomputed and the lazily
+    /// computed geometric data is discarded; the LDU addressing is kept, as
+    /// the topology does not change.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `delta_t` is not positive and finite, or if
+    /// `new_points` does not have `n_points()` entries; the mesh is left
+    /// unchanged.
+    pub fn move_points(&mut self, new_points: Vec<Vector>, delta_t: f64) -> Result<(), MeshError> {
+        if !(delta_t > 0.0 && delta_t.is_finite()) {
+            return Err(MeshError::InvalidTimeStep { delta_t });
+        }
+        let volumes = self.cell_volumes().to_vec();
+        let swept = self.poly.move_points(new_points)?;
+        self.v00 = Some(self.v0.take().unwrap_or_else(|| volumes.clone()));
+        self.v0 = Some(volumes);
+        self.mesh_phi = Some(swept.into_iter().map(|v| v / delta_t).collect());
+
+        self.deltas = OnceLock::new();
+        self.weights = OnceLock::new();
+        self.delta_coeffs = OnceLock::new();
+        self.non_orth_delta_coeffs = OnceLock::new();
+        self.non_orth_correction_vectors = OnceLock::new();
+        Ok(())
+    }
+
+    /// Installs the mover used by [`update`](Self::update).
+    pub fn set_mover(&mut self, mover: Box<dyn MeshMover>) {
+        self.mover = Some(mover);
+    }
+
+    /// Advances the mesh to `time` with the installed mover, if any. Returns
+    /// `true` if the mesh moved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `delta_t` is not positive and finite, or if the
+    /// mover returns the wrong number of points.
+    pub fn update(&mut self, time: f64, delta_t: f64) -> Result<bool, MeshError> {
+        let Some(mover) = self.mover.as_mut() else {
+            return Ok(false);
+        };
+        let points = mover.new_points(&self.poly, time);
+        self.move_points(points, delta_t)?;
+        Ok(true)
+    }
+
+    /// Returns the owner-to-neighbor vector of each face. Lazily computed on
+    /// first access.
+    ///
+    /// For internal faces this is `C_N - C_P`; for coupled boundary faces the
+    /// neighbor cell center across the coupling is used, and for other
+    /// boundary faces `C_f - C_P`. The returned slice has length `n_faces()`.
+    pub fn delta(&self) -> &[Vector] {
+        self.deltas.get_or_init(|| {
+            let cell_centers = self.cell_centers();
+            let face_centers = self.face_centers();
+            let owner = self.owner();
+            let neighbor = self.neighbor();
+
+            let mut deltas = Vec::with_capacity(self.n_faces());
+            for (f, &n) in neighbor.iter().enumerate() {
+                deltas.push(cell_centers[n] - cell_centers[owner[f]]);
+            }
+            for patch in self.patches() {
+                let range = patch.range();
+                match patch.as_coupled() {
+                    Some(coupled) => {
+                        let far = coupled.neighbor_cell_centers();
+                        for (i, f) in range.enumerate() {
+                            deltas.push(far[i] - cell_centers[owner[f]]);
+                        }
+                    }
+                    None => {
+                        for f in range {
+                            deltas.push(face_centers[f] - cell_centers[owner[f]]);
+                        }
+                    }
+                }
+            }
+            deltas
+        })
+    }
+
+    /// Returns the linear interpolation weight of each face. Lazily computed
+    /// on first access.
+    ///
+    /// The face value is `w φ_P + (1 - w) φ_N`, with
+    /// `w = |n · (C_N - C_f)| / (|n · (C_f - C_P)| + |n · (C_N - C_f)|)`.
+    /// Non-coupled boundary faces have weight 1. The returned slice has
+    /// length `n_faces()`.
+    pub fn weights(&self) -> &[f64] {
+        self.weights.get_or_init(|| {
+            let face_areas = self.face_areas();
+            let face_centers = self.face_centers();
+            let cell_centers = self.cell_centers();
+            let owner = self.owner();
+            let deltas = self.delta();
+
+            let mut weights = vec![1.0; self.n_faces()];
+            for f in self.interpolated_faces() {
+                let sf = face_areas[f];
+                let c_own = cell_centers[owner[f]];
+                let d_own = (sf * (face_centers[f] - c_own)).abs();
+                let d_nei = (sf * (c_own + deltas[f] - face_centers[f])).abs();
+                weights[f] = d_nei / (d_own + d_nei);
+            }
+            weights
+        })
+    }
+
+    /// Returns `1 / |d|` for each face, where `d` is the [`delta`](Self::delta)
+    /// vector. Lazily computed on first access.
+    ///
+    /// The returned slice has length `n_faces()`.
+    pub fn delta_coeffs(&self) -> &[f64] {
+        self.delta_coeffs
+            .get_or_init(|| self.delta().iter().map(|d| 1.0 / d.mag()).collect())
+    }
+
+    /// Returns `1 / max(n · d, 0.05 |d|)` for each face, the coefficient of
+    /// the orthogonal part of the face-normal gradient. Lazily computed on
+    /// first access.
+    ///
+    /// The returned slice has length `n_faces()`.
+    pub fn non_orth_delta_coeffs(&self) -> &[f64] {
+        self.non_orth_delta_coeffs.get_or_init(|| {
+            self.face_areas()
+                .iter()
+                .zip(self.delta())
+                .map(|(&sf, &d)| {
+                    let n = sf / sf.mag();
+                    1.0 / (n * d).max(MIN_NON_ORTH_COS * d.mag())
+                })
+                .collect()
+        })
+    }
+
+    /// Returns the non-orthogonal correction vector `n - d · k` of each face,
+    /// where `k` is the [`non_orth_delta_coeffs`](Self::non_orth_delta_coeffs)
+    /// entry. Lazily computed on first access.
+    ///
+    /// The vector vanishes on orthogonal faces and on non-coupled boundary
+    /// faces. The returned slice has length `n_faces()`.
+    pub fn non_orth_correction_vectors(&self) -> &[Vector] {
+        self.non_orth_correction_vectors.get_or_init(|| {
+            let face_areas = self.face_areas();
+            let deltas = self.delta();
+            let coeffs = self.non_orth_delta_coeffs();
+
+            let mut corr = vec![Vector::zero(); self.n_faces()];
+            for f in self.interpolated_faces() {
+                let n = face_areas[f] / face_areas[f].mag();
+                corr[f] = n - deltas[f] * coeffs[f];
+            }
+            corr
+        })
+    }
+
+    /// Returns the indices of the faces that interpolate between two cells:
+    /// all internal faces and the faces of coupled patches.
+    fn interpolated_faces(&self) -> impl Iterator<Item = usize> + '_ {
+        let coupled = self
+            .patches()
+            .iter()
+            .filter(|p| p.as_coupled().is_some())
+            .flat_map(|p| p.range());
+        (0..self.n_internal_faces()).chain(coupled)
+    }
+}
+
+impl LduMesh for FvMesh {
+    fn ldu_addressing(&self) -> &LduAddressing {
+        self.ldu_addressing.get_or_init(|| {
+            LduAddressing::new(
+                self.poly.n_cells(),
+                self.owner()[..self.n_internal_faces()].to_vec(),
+                self.neighbor().to_vec(),
+            )
+        })
+    }
+
+    fn n_cells(&self) -> usize {
+        self.poly.n_cells()
+    }
+}
+
+impl Deref for FvMesh {
+    type Target = PolyMesh;
+
+    fn deref(&self) -> &PolyMesh {
+        &self.poly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patches::{CyclicPolyPatch, PolyPatch, WallPolyPatch};
+    use crate::transform::Transform;
+
+    /// Stretches the unit cube along x to length `1 + time`.
+    struct Piston;
+
+    impl MeshMover for Piston {
+        fn new_points(&mut self, mesh: &PolyMesh, time: f64) -> Vec<Vector> {
+            let length = mesh.points().iter().map(|p| p.x()).fold(0.0, f64::max);
+            mesh.points()
+                .iter()
+                .map(|p| Vector::new(p.x() / length * (1.0 + time), p.y(), p.z()))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn moving_mesh_keeps_old_volumes_and_conserves_space() {
+        let mut mesh = FvMesh::unit_cube(4, 1, 1).unwrap();
+        assert!(!mesh.update(0.1, 0.1).unwrap());
+        assert_eq!(mesh.v0(), mesh.cell_volumes());
+        assert!((mesh.delta()[0].x() - 0.25).abs() < 1e-12);
+
+        mesh.set_mover(Box::new(Piston));
+        assert!(mesh.update(0.1, 0.1).unwrap());
+        assert!(mesh.update(0.3, 0.2).unwrap());
+        assert!(mesh.moving());
+        assert!((mesh.cell_volumes()[0] - 1.3 / 4.0).abs() < 1e-12);
+        assert!((mesh.v0()[0] - 1.1 / 4.0).abs() < 1e-12);
+        assert!((mesh.v00()[0] - 1.0 / 4.0).abs() < 1e-12);
+        assert!((mesh.poly().old_points().unwrap()[1].x() - 1.1 / 4.0).abs() < 1e-12);
+        // Geometry caches follow the motion
+        assert!((mesh.delta()[0].x() - 1.3 / 4.0).abs() < 1e-12);
+
+        let phi = mesh.mesh_phi().unwrap();
+        let mut change = vec![0.0; mesh.n_cells()];
+        for (f, &p) in phi.iter().enumerate() {
+            change[mesh.owner()[f]] += p * 0.2;
+            if let Some(&n) = mesh.neighbor().get(f) {
+                change[n] -= p * 0.2;
+            }
+        }
+        for (c, dv) in change.iter().enumerate() {
+            assert!((dv - (mesh.cell_volumes()[c] - mesh.v0()[c])).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_time_steps() {
+        let mut mesh = FvMesh::unit_cube(2, 1, 1).unwrap();
+        mesh.set_mover(Box::new(Piston));
+        for delta_t in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                mesh.update(0.1, delta_t),
+                Err(MeshError::InvalidTimeStep { .. })
+            ));
+        }
+        assert!(!mesh.moving());
+        assert!(mesh.mesh_phi().is_none());
+    }
+
+    #[test]
+    fn unit_cube_delegates_to_primitive_mesh() {
+        let mesh = FvMesh::unit_cube(2, 2, 2).unwrap();
+        assert_eq!(LduMesh::n_cells(&mesh), 8);
+        assert_eq!(mesh.n_internal_faces(), 12);
+        assert_eq!(mesh.n_faces(), 36);
+        assert_eq!(mesh.poly().patches().len(), 6);
+        assert!((mesh.cell_volumes().iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ldu_addressing_matches_owner_and_neighbor() {
+        let mesh = FvMesh::unit_cube(3, 2, 2).unwrap();
+        let ldu = mesh.ldu_addressing();
+        assert_eq!(ldu.n_cells(), 12);
+        assert_eq!(ldu.lower, &mesh.owner()[..mesh.n_internal_faces()]);
+        assert_eq!(ldu.upper, mesh.neighbor());
+        for c in 0..ldu.n_cells() {
+            for f in ldu.owner_start[c]..ldu.owner_start[c + 1] {
+                assert_eq!(ldu.lower[f], c);
+            }
+            for &f in &ldu.losort[ldu.losort_start[c]..ldu.losort_start[c + 1]] {
+                assert_eq!(ldu.upper[f], c);
+            }
+        }
+        assert_eq!(ldu.owner_start[12], ldu.n_faces());
+        assert_eq!(ldu.losort_start[12], ldu.n_faces());
+    }
+
+    #[test]
+    fn uniform_mesh_interpolation_factors() {
+        let mesh = FvMesh::unit_cube(4, 2, 1).unwrap();
+        let nif = mesh.n_internal_faces();
+        for f in 0..nif {
+            assert!((mesh.weights()[f] - 0.5).abs() < 1e-12, "face {f}");
+            assert!(mesh.non_orth_correction_vectors()[f].mag() < 1e-12);
+        }
+        // Face 0 is the +x face of cell 0; dx = 0.25
+        assert!((mesh.delta_coeffs()[0] - 4.0).abs() < 1e-12);
+        assert!((mesh.non_orth_delta_coeffs()[0] - 4.0).abs() < 1e-12);
+        for f in nif..mesh.n_faces() {
+            assert_eq!(mesh.weights()[f], 1.0);
+            assert_eq!(mesh.non_orth_correction_vectors()[f], Vector::zero());
+        }
+        // Boundary face of the xMin patch: half a cell width from the center
+        let x_min = mesh.patch_by_name("xMin").unwrap().start();
+        assert!((mesh.delta_coeffs()[x_min] - 8.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn graded_mesh_weights_follow_distances() {
+        let (primitive, patches) =
+            CartesianBox::new(Vector::zero(), Vector::new(3.0, 1.0, 1.0), [2, 1, 1])
+                .with_grading([2.0, 1.0, 1.0])
+                .build()
+                .unwrap();
+        let mesh = FvMesh::new(PolyMesh::from_patch_defs(primitive, &patches).unwrap());
+        // Widths 1 and 2: centers at 0.5 and 2.0, face at 1.0
+        assert!((mesh.weights()[0] - 1.0 / 1.5).abs() < 1e-12);
+        assert!((mesh.delta_coeffs()[0] - 1.0 / 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cyclic_faces_interpolate_across_the_coupling() {
+        let (primitive, defs) =
+            CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [2, 1, 1])
+                .build()
+                .unwrap();
+        let separation = Vector::new(1.0, 0.0, 0.0);
+        let mut patches: Vec<Box<dyn PolyPatch>> = vec![
+            Box::new(CyclicPolyPatch::new(
+                "xMin",
+                defs[0].start,
+                1,
+                "xMax",
+                Transform::Translation {
+                    separation: -separation,
+                },
+            )),
+            Box::new(CyclicPolyPatch::new(
+                "xMax",
+                defs[1].start,
+                1,
+                "xMin",
+                Transform::Translation { separation },
+            )),
+        ];
+        for def in &defs[2..] {
+            patches.push(Box::new(WallPolyPatch::new(
+                def.name.clone(),
+                def.start,
+                def.size,
+            )));
+        }
+        let mesh = FvMesh::new(PolyMesh::new(primitive, patches).unwrap());
+        let f = defs[0].start;
+        assert!((mesh.delta()[f] - Vector::new(-0.5, 0.0, 0.0)).mag() < 1e-12);
+        assert!((mesh.weights()[f] - 0.5).abs() < 1e-12);
+        assert!((mesh.delta_coeffs()[f] - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn non_orthogonal_face_has_correction_vector() {
+        // Two cells whose center line is at 45 degrees to the shared face
+        let points = vec![
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(1.0, 1.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(1.0, 0.0, 1.0),
+            Vector::new(1.0, 1.0, 1.0),
+            Vector::new(0.0, 1.0, 1.0),
+            Vector::new(2.0, 0.0, 2.0),
+            Vector::new(3.0, 0.0, 2.0),
+            Vector::new(3.0, 1.0, 2.0),
+            Vector::new(2.0, 1.0, 2.0),
+        ];
+        let faces = vec![
+            vec![4, 5, 6, 7],
+            vec![0, 3, 2, 1],
+            vec![0, 1, 5, 4],
+            vec![3, 7, 6, 2],
+            vec![0, 4, 7, 3],
+            vec![1, 2, 6, 5],
+            vec![8, 9, 10, 11],
+            vec![4, 5, 9, 8],
+            vec![7, 11, 10, 6],
+            vec![4, 8, 11, 7],
+            vec![5, 6, 10, 9],
+        ];
+        let owner = vec![0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1];
+        let primitive = crate::PrimitiveMesh::new(points, faces, owner, vec![1]).unwrap();
+        let poly =
+            PolyMesh::from_patch_defs(primitive, &[crate::PatchDef::new("walls", "wall", 1, 10)])
+                .unwrap();
+        let mesh = FvMesh::new(poly);
+        // d = (1, 0, 1), n = (0, 0, 1): n·d = 1
+        assert!((mesh.non_orth_delta_coeffs()[0] - 1.0).abs() < 1e-12);
+        assert!((mesh.delta_coeffs()[0] - 1.0 / 2.0_f64.sqrt()).abs() < 1e-12);
+        let corr = mesh.non_orth_correction_vectors()[0];
+        assert!((corr - Vector::new(-1.0, 0.0, 0.0)).mag() < 1e-12);
+    }
+}
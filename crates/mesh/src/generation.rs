@@ -0,0 +1,71 @@
+//! Mesh generators.
+//!
+//! Generators build a [`PrimitiveMesh`](crate::PrimitiveMesh) together with the
+//! [`PatchDef`](crate::PatchDef) ranges of its boundary faces, following the
+//! OpenFOAM face ordering (internal faces in upper-triangular order, then
+//! boundary faces grouped by patch).
+//!
+//! [`BlockMesh`] and [`CartesianBox`] generate structured hex meshes;
+//! [`ShapeMesh`] assembles unstructured meshes of tets, pyramids, prisms and
+//! hexes produced by external generators.
+
+mod block_mesh;
+mod cartesian;
+mod shape_mesh;
+
+pub use block_mesh::{BlockMesh, BlockPatch, DEFAULT_PATCH_NAME, DEFAULT_PATCH_TYPE, HexBlock};
+pub use cartesian::CartesianBox;
+pub use shape_mesh::{CellShape, ShapeMesh, ShapePatch};
+
+/// Returns `n + 1` normalized node positions in `[0, 1]` for `n` cells with
+/// geometric grading.
+///
+/// `grading` is the OpenFOAM `simpleGrading` expansion ratio: the width of the
+/// last cell divided by the width of the first. A grading of `1.0` gives
+/// uniform spacing.
+///
+/// # Panics
+///
+/// Panics if `n == 0`.
+pub(crate) fn graded_positions(n: usize, grading: f64) -> Vec<f64> {
+    assert!(n > 0, "graded_positions requires at least one cell");
+    if n == 1 || (grading - 1.0).abs() < 1e-12 {
+        return (0..=n).map(|k| k as f64 / n as f64).collect();
+    }
+    // Ratio between consecutive cell widths
+    let r = grading.powf(1.0 / (n - 1) as f64);
+    let denom = r.powi(n as i32) - 1.0;
+    let mut positions: Vec<f64> = (0..=n).map(|k| (r.powi(k as i32) - 1.0) / denom).collect();
+    // Pin the end point exactly to avoid round-off at the far boundary
+    positions[n] = 1.0;
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graded_positions_uniform() {
+        let p = graded_positions(4, 1.0);
+        assert_eq!(p, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn graded_positions_expansion_ratio() {
+        let p = graded_positions(5, 4.0);
+        assert_eq!(p[0], 0.0);
+        assert_eq!(p[5], 1.0);
+        let first = p[1] - p[0];
+        let last = p[5] - p[4];
+        assert!((last / first - 4.0).abs() < 1e-10, "ratio {}", last / first);
+        for w in p.windows(2) {
+            assert!(w[1] > w[0]);
+        }
+    }
+
+    #[test]
+    fn graded_positions_single_cell_ignores_grading() {
+        assert_eq!(graded_positions(1, 3.0), vec![0.0, 1.0]);
+    }
+}
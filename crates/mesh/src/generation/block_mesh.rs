@@ -0,0 +1,504 @@
+use std::collections::HashMap;
+
+use dugong_types::tensor::Vector;
+
+use super::graded_positions;
+use crate::assembly::{assemble_cells, hex_faces, merge_points};
+use crate::error::MeshError;
+use crate::patch_def::PatchDef;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// Name of the patch collecting boundary faces not listed in any
+/// [`BlockPatch`], as in OpenFOAM's blockMesh.
+pub const DEFAULT_PATCH_NAME: &str = "defaultFaces";
+
+/// Type of the default patch.
+pub const DEFAULT_PATCH_TYPE: &str = "empty";
+
+/// Relative tolerance (with respect to the smallest cell edge) used to merge
+/// coincident points of neighboring blocks.
+const MERGE_TOLERANCE: f64 = 1e-4;
+
+/// A hexahedral block with straight edges and `simpleGrading`.
+///
+/// `vertices` index into [`BlockMesh::vertices`] in OpenFOAM order: `0-3`
+/// form the bottom face and `4-7` the top face, both counter-clockwise when
+/// viewed from above. The local x direction runs `0→1`, y runs `0→3` and z
+/// runs `0→4`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HexBlock {
+    /// Block corner vertex indices.
+    pub vertices: [usize; 8],
+    /// Number of cells in the local x, y, z directions.
+    pub n_cells: [usize; 3],
+    /// Expansion ratio (last cell width / first cell width) per direction.
+    pub grading: [f64; 3],
+}
+
+impl HexBlock {
+    /// Creates a uniformly spaced block.
+    pub fn new(vertices: [usize; 8], n_cells: [usize; 3]) -> Self {
+        Self {
+            vertices,
+            n_cells,
+            grading: [1.0; 3],
+        }
+    }
+
+    /// Sets the per-direction expansion ratio.
+    pub fn with_grading(mut self, grading: [f64; 3]) -> Self {
+        self.grading = grading;
+        self
+    }
+}
+
+/// A named boundary patch made of block faces.
+///
+/// Each entry of `faces` lists the four block vertices of one block face, in
+/// any order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockPatch {
+    /// Patch name.
+    pub name: String,
+    /// OpenFOAM-style patch type name.
+    pub patch_type: String,
+    /// Block faces belonging to the patch.
+    pub faces: Vec<[usize; 4]>,
+}
+
+impl BlockPatch {
+    /// Creates a new block patch.
+    pub fn new(
+        name: impl Into<String>,
+        patch_type: impl Into<String>,
+        faces: Vec<[usize; 4]>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            patch_type: patch_type.into(),
+            faces,
+        }
+    }
+}
+
+/// A blockMeshDict-style multi-block mesh description.
+///
+/// Blocks that share block vertices (or whose faces coincide geometrically)
+/// are merged into a single conforming mesh. Boundary faces are grouped into
+/// the listed patches in order; faces not covered by any patch go to a final
+/// [`DEFAULT_PATCH_NAME`] patch of type [`DEFAULT_PATCH_TYPE`], which is only
+/// emitted when non-empty.
+///
+/// # Examples
+///
+/// ```
+/// use dugong_mesh::generation::{BlockMesh, BlockPatch, HexBlock};
+/// use dugong_types::tensor::Vector;
+///
+/// let vertices = vec![
+///     Vector::new(0.0, 0.0, 0.0),
+///     Vector::new(1.0, 0.0, 0.0),
+///     Vector::new(1.0, 1.0, 0.0),
+///     Vector::new(0.0, 1.0, 0.0),
+///     Vector::new(0.0, 0.0, 0.1),
+///     Vector::new(1.0, 0.0, 0.1),
+///     Vector::new(1.0, 1.0, 0.1),
+///     Vector::new(0.0, 1.0, 0.1),
+/// ];
+/// let blocks = vec![HexBlock::new([0, 1, 2, 3, 4, 5, 6, 7], [4, 4, 1])];
+/// let patches = vec![BlockPatch::new("lid", "wall", vec![[3, 7, 6, 2]])];
+/// let (mesh, patches) = BlockMesh::new(vertices, blocks, patches).build().unwrap();
+/// assert_eq!(mesh.n_cells(), 16);
+/// assert_eq!(patches[0].size, 4);
+/// assert_eq!(patches[1].name, "defaultFaces");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockMesh {
+    /// Block corner coordinates.
+    pub vertices: Vec<Vector>,
+    /// Hexahedral blocks.
+    pub blocks: Vec<HexBlock>,
+    /// Named boundary patches.
+    pub patches: Vec<BlockPatch>,
+}
+
+impl BlockMesh {
+    /// Creates a new block mesh description.
+    pub fn new(vertices: Vec<Vector>, blocks: Vec<HexBlock>, patches: Vec<BlockPatch>) -> Self {
+        Self {
+            vertices,
+            blocks,
+            patches,
+        }
+    }
+
+    /// Generates the merged mesh and its boundary patches.
+    ///
+    /// Cells are numbered block by block, `i + nx * (j + ny * k)` within each
+    /// block. Faces follow the OpenFOAM ordering: internal faces in
+    /// upper-triangular order, then boundary faces grouped by patch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a block references a missing vertex, has a zero cell
+    /// count or invalid grading, is inside-out, if a patch face is not a face
+    /// of exactly one block or is listed more than once, or if blocks overlap
+    /// so that a face is shared by more than two cells.
+    pub fn build(&self) -> Result<(PrimitiveMesh, Vec<PatchDef>), MeshError> {
+        self.validate()?;
+
+        // Block face (sorted vertex set) -> (number of blocks, patch index)
+        let mut block_face_patch: HashMap<[usize; 4], (usize, usize)> = HashMap::new();
+        for block in &self.blocks {
+            for face in hex_faces(&block.vertices) {
+                let mut key = [face[0], face[1], face[2], face[3]];
+                key.sort_unstable();
+                block_face_patch.entry(key).or_insert((0, usize::MAX)).0 += 1;
+            }
+        }
+        for (pi, patch) in self.patches.iter().enumerate() {
+            for face in &patch.faces {
+                let mut key = *face;
+                key.sort_unstable();
+                let Some((n_blocks, slot)) = block_face_patch.get_mut(&key) else {
+                    return Err(MeshError::UnknownPatchFace {
+                        patch: patch.name.clone(),
+                        face: *face,
+                    });
+                };
+                if *n_blocks > 1 {
+                    return Err(MeshError::InternalPatchFace {
+                        patch: patch.name.clone(),
+                        face: *face,
+                    });
+                }
+                if *slot != usize::MAX {
+                    return Err(MeshError::DuplicatePatchFace {
+                        patch: patch.name.clone(),
+                        other: self.patches[*slot].name.clone(),
+                        face: *face,
+                    });
+                }
+                *slot = pi;
+            }
+        }
+        let default_tag = self.patches.len();
+
+        // Generate points block by block
+        let mut raw_points = Vec::new();
+        let mut block_point_start = Vec::with_capacity(self.blocks.len());
+        let mut min_edge = f64::INFINITY;
+        for block in &self.blocks {
+            block_point_start.push(raw_points.len());
+            let corners: Vec<Vector> = block.vertices.iter().map(|&v| self.vertices[v]).collect();
+            let t: Vec<Vec<f64>> = (0..3)
+                .map(|d| graded_positions(block.n_cells[d], block.grading[d]))
+                .collect();
+            for (d, td) in t.iter().enumerate() {
+                let min_spacing = td
+                    .windows(2)
+                    .map(|w| w[1] - w[0])
+                    .fold(f64::INFINITY, f64::min);
+                for (a, b) in block_edges(d) {
+                    min_edge = min_edge.min((corners[b] - corners[a]).mag() * min_spacing);
+                }
+            }
+            for &w in &t[2] {
+                for &v in &t[1] {
+                    for &u in &t[0] {
+                        raw_points.push(trilinear(&corners, u, v, w));
+                    }
+                }
+            }
+        }
+        let (points, point_map) = merge_points(&raw_points, MERGE_TOLERANCE * min_edge);
+
+        // Cells as outward-oriented hex faces, with their boundary tags
+        let mut cells = Vec::new();
+        let mut cell_tags: Vec<[usize; 6]> = Vec::new();
+        for (b, block) in self.blocks.iter().enumerate() {
+            let [nx, ny, nz] = block.n_cells;
+            let base = block_point_start[b];
+            let p =
+                |i: usize, j: usize, k: usize| point_map[base + i + (nx + 1) * (j + (ny + 1) * k)];
+            let mut side_tag = [default_tag; 6];
+            for (side, face) in hex_faces(&block.vertices).iter().enumerate() {
+                let mut key = [face[0], face[1], face[2], face[3]];
+                key.sort_unstable();
+                let (_, pi) = block_face_patch[&key];
+                if pi != usize::MAX {
+                    side_tag[side] = pi;
+                }
+            }
+            for k in 0..nz {
+                for j in 0..ny {
+                    for i in 0..nx {
+                        let v = [
+                            p(i, j, k),
+                            p(i + 1, j, k),
+                            p(i + 1, j + 1, k),
+                            p(i, j + 1, k),
+                            p(i, j, k + 1),
+                            p(i + 1, j, k + 1),
+                            p(i + 1, j + 1, k + 1),
+                            p(i, j + 1, k + 1),
+                        ];
+                        cells.push(hex_faces(&v).to_vec());
+                        // A cell face lies on a block side only at the block edge
+                        let on_side = [
+                            i == 0,
+                            i + 1 == nx,
+                            j == 0,
+                            j + 1 == ny,
+                            k == 0,
+                            k + 1 == nz,
+                        ];
+                        let mut tags = [default_tag; 6];
+                        for side in 0..6 {
+                            if on_side[side] {
+                                tags[side] = side_tag[side];
+                            }
+                        }
+                        cell_tags.push(tags);
+                    }
+                }
+            }
+        }
+
+        let assembled = assemble_cells(points, &cells, |c, lf| cell_tags[c][lf])?;
+
+        let mut counts = vec![0usize; self.patches.len() + 1];
+        for &tag in &assembled.boundary_tags {
+            counts[tag] += 1;
+        }
+        let mut start = assembled.mesh.n_internal_faces();
+        let mut patches = Vec::with_capacity(counts.len());
+        for (patch, &size) in self.patches.iter().zip(&counts) {
+            patches.push(PatchDef::new(&patch.name, &patch.patch_type, start, size));
+            start += size;
+        }
+        if counts[default_tag] > 0 {
+            patches.push(PatchDef::new(
+                DEFAULT_PATCH_NAME,
+                DEFAULT_PATCH_TYPE,
+                start,
+                counts[default_tag],
+            ));
+        }
+
+        Ok((assembled.mesh, patches))
+    }
+
+    /// Checks vertex references, cell counts, grading and block handedness.
+    fn validate(&self) -> Result<(), MeshError> {
+        let n_vertices = self.vertices.len();
+        for (b, block) in self.blocks.iter().enumerate() {
+            for &vertex in &block.vertices {
+                if vertex >= n_vertices {
+                    return Err(MeshError::BlockVertexOutOfRange {
+                        block: b,
+                        vertex,
+                        n_vertices,
+                    });
+                }
+            }
+            for d in 0..3 {
+                if block.n_cells[d] == 0 {
+                    return Err(MeshError::ZeroCellCount { direction: d });
+                }
+                let g = block.grading[d];
+                if !(g.is_finite() && g > 0.0) {
+                    return Err(MeshError::InvalidGrading {
+                        direction: d,
+                        grading: g,
+                    });
+                }
+            }
+            // Averaged edge vectors give the Jacobian at the block center
+            let c: Vec<Vector> = block.vertices.iter().map(|&v| self.vertices[v]).collect();
+            let axis = |d: usize| {
+                block_edges(d)
+                    .iter()
+                    .fold(Vector::zero(), |acc, &(a, b)| acc + (c[b] - c[a]))
+            };
+            if axis(0).cross(&axis(1)) * axis(2) <= 0.0 {
+                return Err(MeshError::InvertedBlock { block: b });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the four block edges (as local vertex pairs) running in local
+/// direction `d`.
+fn block_edges(d: usize) -> [(usize, usize); 4] {
+    match d {
+        0 => [(0, 1), (3, 2), (4, 5), (7, 6)],
+        1 => [(0, 3), (1, 2), (4, 7), (5, 6)],
+        _ => [(0, 4), (1, 5), (2, 6), (3, 7)],
+    }
+}
+
+/// Trilinear interpolation between the eight block corners.
+fn trilinear(c: &[Vector], u: f64, v: f64, w: f64) -> Vector {
+    let bottom = (c[0] * (1.0 - u) + c[1] * u) * (1.0 - v) + (c[3] * (1.0 - u) + c[2] * u) * v;
+    let top = (c[4] * (1.0 - u) + c[5] * u) * (1.0 - v) + (c[7] * (1.0 - u) + c[6] * u) * v;
+    bottom * (1.0 - w) + top * w
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Backward-facing step in 2D (one cell thick): an inlet channel block
+    /// above the step and two blocks downstream.
+    ///
+    /// ```text
+    ///  y=2  5 ---- 6 ---- 7
+    ///       | b0   | b2   |
+    ///  y=1  2 ---- 3 ---- 4
+    ///              | b1   |
+    ///  y=0         0 ---- 1
+    ///      x=0    x=1    x=3
+    /// ```
+    ///
+    /// Vertices `0..8` lie at z=0 and `8..16` at z=0.1.
+    fn backward_facing_step() -> BlockMesh {
+        let xy = [
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (0.0, 1.0),
+            (1.0, 1.0),
+            (3.0, 1.0),
+            (0.0, 2.0),
+            (1.0, 2.0),
+            (3.0, 2.0),
+        ];
+        let mut vertices = Vec::new();
+        for z in [0.0, 0.1] {
+            for &(x, y) in &xy {
+                vertices.push(Vector::new(x, y, z));
+            }
+        }
+        let n = xy.len();
+        let hex = |a: usize, b: usize, c: usize, d: usize| [a, b, c, d, a + n, b + n, c + n, d + n];
+        let blocks = vec![
+            HexBlock::new(hex(2, 3, 6, 5), [2, 2, 1]),
+            HexBlock::new(hex(0, 1, 4, 3), [4, 2, 1]).with_grading([2.0, 1.0, 1.0]),
+            HexBlock::new(hex(3, 4, 7, 6), [4, 2, 1]).with_grading([2.0, 1.0, 1.0]),
+        ];
+        let patches = vec![
+            BlockPatch::new("inlet", "patch", vec![[2, 5, 5 + n, 2 + n]]),
+            BlockPatch::new(
+                "outlet",
+                "patch",
+                vec![[1, 4, 4 + n, 1 + n], [4, 7, 7 + n, 4 + n]],
+            ),
+        ];
+        BlockMesh::new(vertices, blocks, patches)
+    }
+
+    #[test]
+    fn multi_block_merges_shared_faces() {
+        let (mesh, _) = backward_facing_step().build().unwrap();
+        assert_eq!(mesh.n_cells(), 4 + 8 + 8);
+        // Per-block internal faces + 2 faces between b0/b2 + 4 between b1/b2
+        let per_block = (2 + 2) + (3 * 2 + 4) + (3 * 2 + 4);
+        assert_eq!(mesh.n_internal_faces(), per_block + 2 + 4);
+        // Merged points: 2 layers of (3x3 + 5x3 + 5x3 - 3 - 5) points
+        assert_eq!(mesh.n_points(), 2 * (9 + 15 + 15 - 3 - 5));
+        let total: f64 = mesh.cell_volumes().iter().sum();
+        assert!((total - 0.5).abs() < 1e-12, "total volume {total}");
+    }
+
+    #[test]
+    fn multi_block_internal_faces_upper_triangular() {
+        let (mesh, _) = backward_facing_step().build().unwrap();
+        let owner = mesh.owner();
+        let cc = mesh.cell_centers();
+        for (f, &n) in mesh.neighbor().iter().enumerate() {
+            assert!(owner[f] < n);
+            if f > 0 {
+                assert!((owner[f - 1], mesh.neighbor()[f - 1]) < (owner[f], n));
+            }
+            assert!(mesh.face_areas()[f] * (cc[n] - cc[owner[f]]) > 0.0);
+        }
+    }
+
+    #[test]
+    fn multi_block_patches_grouped_in_order() {
+        let (mesh, patches) = backward_facing_step().build().unwrap();
+        let names: Vec<&str> = patches.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["inlet", "outlet", DEFAULT_PATCH_NAME]);
+        assert_eq!(patches[0].size, 2);
+        assert_eq!(patches[1].size, 4);
+        assert_eq!(patches[0].start, mesh.n_internal_faces());
+        assert_eq!(patches[2].start + patches[2].size, mesh.n_faces());
+        let fc = mesh.face_centers();
+        for f in patches[1].range() {
+            assert!((fc[f].x() - 3.0).abs() < 1e-12);
+            assert!(mesh.face_areas()[f].x() > 0.0);
+        }
+    }
+
+    #[test]
+    fn unknown_patch_face_returns_err() {
+        let mut bm = backward_facing_step();
+        bm.patches
+            .push(BlockPatch::new("bogus", "wall", vec![[0, 1, 2, 3]]));
+        assert!(matches!(
+            bm.build(),
+            Err(MeshError::UnknownPatchFace { .. })
+        ));
+    }
+
+    #[test]
+    fn duplicate_patch_face_returns_err() {
+        let mut bm = backward_facing_step();
+        let n = 8;
+        bm.patches
+            .push(BlockPatch::new("walls", "wall", vec![[5, 2, 2 + n, 5 + n]]));
+        assert!(matches!(
+            bm.build(),
+            Err(MeshError::DuplicatePatchFace { ref patch, ref other, .. })
+                if patch == "walls" && other == "inlet"
+        ));
+    }
+
+    #[test]
+    fn internal_patch_face_returns_err() {
+        let mut bm = backward_facing_step();
+        let n = 8;
+        // Shared by the first and third blocks
+        bm.patches.push(BlockPatch::new(
+            "baffle",
+            "wall",
+            vec![[3, 6, 6 + n, 3 + n]],
+        ));
+        assert!(matches!(
+            bm.build(),
+            Err(MeshError::InternalPatchFace { .. })
+        ));
+    }
+
+    #[test]
+    fn vertex_out_of_range_returns_err() {
+        let mut bm = backward_facing_step();
+        bm.blocks[1].vertices[0] = 100;
+        assert!(matches!(
+            bm.build(),
+            Err(MeshError::BlockVertexOutOfRange { block: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn inverted_block_returns_err() {
+        let mut bm = backward_facing_step();
+        let v = bm.blocks[0].vertices;
+        bm.blocks[0].vertices = [v[4], v[5], v[6], v[7], v[0], v[1], v[2], v[3]];
+        assert!(matches!(
+            bm.build(),
+            Err(MeshError::InvertedBlock { block: 0 })
+        ));
+    }
+}
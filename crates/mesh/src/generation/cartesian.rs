@@ -0,0 +1,391 @@
+use dugong_types::tensor::Vector;
+
+use super::graded_positions;
+use crate::error::MeshError;
+use crate::patch_def::PatchDef;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// Names of the six boundary patches, in the order they are emitted.
+const SIDE_NAMES: [&str; 6] = ["xMin", "xMax", "yMin", "yMax", "zMin", "zMax"];
+
+/// An axis-aligned box `[x0, x1] × [y0, y1] × [z0, z1]` split into
+/// `nx × ny × nz` hexahedral cells.
+///
+/// Cells are numbered `i + nx * (j + ny * k)` and points
+/// `i + (nx + 1) * (j + (ny + 1) * k)`. The generated mesh follows the
+/// OpenFOAM ordering conventions:
+///
+/// - Internal faces come first, sorted by owner and then by neighbor
+///   (upper-triangular order).
+/// - Boundary faces follow, grouped into the six patches `xMin`, `xMax`,
+///   `yMin`, `yMax`, `zMin`, `zMax` (all of type `"patch"`).
+///
+/// # Examples
+///
+/// ```
+/// use dugong_mesh::CartesianBox;
+/// use dugong_types::tensor::Vector;
+///
+/// let (mesh, patches) = CartesianBox::new(
+///     Vector::new(0.0, 0.0, 0.0),
+///     Vector::new(2.0, 1.0, 1.0),
+///     [4, 2, 2],
+/// )
+/// .with_grading([2.0, 1.0, 1.0])
+/// .build()
+/// .unwrap();
+/// assert_eq!(mesh.n_cells(), 16);
+/// assert_eq!(patches.len(), 6);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CartesianBox {
+    min: Vector,
+    max: Vector,
+    n_cells: [usize; 3],
+    grading: [f64; 3],
+}
+
+impl CartesianBox {
+    /// Creates a uniformly spaced box spanning `min..max` with
+    /// `n_cells = [nx, ny, nz]` cells per direction.
+    pub fn new(min: Vector, max: Vector, n_cells: [usize; 3]) -> Self {
+        Self {
+            min,
+            max,
+            n_cells,
+            grading: [1.0; 3],
+        }
+    }
+
+    /// Sets the per-direction expansion ratio (last cell width / first cell
+    /// width), as in OpenFOAM's `simpleGrading`.
+    pub fn with_grading(mut self, grading: [f64; 3]) -> Self {
+        self.grading = grading;
+        self
+    }
+
+    /// Generates the mesh and its six boundary patches.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if any cell count is zero, any `max` component is not
+    /// strictly greater than the corresponding `min` component, or any
+    /// grading is non-positive or non-finite.
+    pub fn build(&self) -> Result<(PrimitiveMesh, Vec<PatchDef>), MeshError> {
+        let lo = self.min.as_array();
+        let hi = self.max.as_array();
+        for d in 0..3 {
+            if self.n_cells[d] == 0 {
+                return Err(MeshError::ZeroCellCount { direction: d });
+            }
+            if !(lo[d].is_finite() && hi[d].is_finite() && hi[d] > lo[d]) {
+                return Err(MeshError::InvalidExtent {
+                    direction: d,
+                    min: lo[d],
+                    max: hi[d],
+                });
+            }
+            let g = self.grading[d];
+            if !(g.is_finite() && g > 0.0) {
+                return Err(MeshError::InvalidGrading {
+                    direction: d,
+                    grading: g,
+                });
+            }
+        }
+
+        let [nx, ny, nz] = self.n_cells;
+        let coords: Vec<Vec<f64>> = (0..3)
+            .map(|d| {
+                graded_positions(self.n_cells[d], self.grading[d])
+                    .into_iter()
+                    .map(|t| lo[d] + t * (hi[d] - lo[d]))
+                    .collect()
+            })
+            .collect();
+
+        // Points
+        let mut points = Vec::with_capacity((nx + 1) * (ny + 1) * (nz + 1));
+        for &z in &coords[2] {
+            for &y in &coords[1] {
+                for &x in &coords[0] {
+                    points.push(Vector::new(x, y, z));
+                }
+            }
+        }
+
+        let p = |i: usize, j: usize, k: usize| i + (nx + 1) * (j + (ny + 1) * k);
+        let c = |i: usize, j: usize, k: usize| i + nx * (j + ny * k);
+
+        let n_internal = (nx - 1) * ny * nz + nx * (ny - 1) * nz + nx * ny * (nz - 1);
+        let n_boundary = 2 * (ny * nz + nx * nz + nx * ny);
+        let mut faces = Vec::with_capacity(n_internal + n_boundary);
+        let mut owner = Vec::with_capacity(n_internal + n_boundary);
+        let mut neighbor = Vec::with_capacity(n_internal);
+
+        // Internal faces: visiting cells in ascending order and their +x, +y,
+        // +z neighbors (whose indices increase in that order) yields
+        // upper-triangular ordering.
+        for k in 0..nz {
+            for j in 0..ny {
+                for i in 0..nx {
+                    let o = c(i, j, k);
+                    if i + 1 < nx {
+                        faces.push(vec![
+                            p(i + 1, j, k),
+                            p(i + 1, j + 1, k),
+                            p(i + 1, j + 1, k + 1),
+                            p(i + 1, j, k + 1),
+                        ]);
+                        owner.push(o);
+                        neighbor.push(c(i + 1, j, k));
+                    }
+                    if j + 1 < ny {
+                        faces.push(vec![
+                            p(i, j + 1, k),
+                            p(i, j + 1, k + 1),
+                            p(i + 1, j + 1, k + 1),
+                            p(i + 1, j + 1, k),
+                        ]);
+                        owner.push(o);
+                        neighbor.push(c(i, j + 1, k));
+                    }
+                    if k + 1 < nz {
+                        faces.push(vec![
+                            p(i, j, k + 1),
+                            p(i + 1, j, k + 1),
+                            p(i + 1, j + 1, k + 1),
+                            p(i, j + 1, k + 1),
+                        ]);
+                        owner.push(o);
+                        neighbor.push(c(i, j, k + 1));
+                    }
+                }
+            }
+        }
+
+        // Boundary faces, one patch per side
+        let mut patches = Vec::with_capacity(6);
+        let mut push_patch = |side: usize, start: usize, end: usize| {
+            patches.push(PatchDef::new(SIDE_NAMES[side], "patch", start, end - start));
+        };
+
+        // xMin / xMax
+        let start = faces.len();
+        for k in 0..nz {
+            for j in 0..ny {
+                faces.push(vec![
+                    p(0, j, k),
+                    p(0, j, k + 1),
+                    p(0, j + 1, k + 1),
+                    p(0, j + 1, k),
+                ]);
+                owner.push(c(0, j, k));
+            }
+        }
+        push_patch(0, start, faces.len());
+        let start = faces.len();
+        for k in 0..nz {
+            for j in 0..ny {
+                faces.push(vec![
+                    p(nx, j, k),
+                    p(nx, j + 1, k),
+                    p(nx, j + 1, k + 1),
+                    p(nx, j, k + 1),
+                ]);
+                owner.push(c(nx - 1, j, k));
+            }
+        }
+        push_patch(1, start, faces.len());
+
+        // yMin / yMax
+        let start = faces.len();
+        for k in 0..nz {
+            for i in 0..nx {
+                faces.push(vec![
+                    p(i, 0, k),
+                    p(i + 1, 0, k),
+                    p(i + 1, 0, k + 1),
+                    p(i, 0, k + 1),
+                ]);
+                owner.push(c(i, 0, k));
+            }
+        }
+        push_patch(2, start, faces.len());
+        let start = faces.len();
+        for k in 0..nz {
+            for i in 0..nx {
+                faces.push(vec![
+                    p(i, ny, k),
+                    p(i, ny, k + 1),
+                    p(i + 1, ny, k + 1),
+                    p(i + 1, ny, k),
+                ]);
+                owner.push(c(i, ny - 1, k));
+            }
+        }
+        push_patch(3, start, faces.len());
+
+        // zMin / zMax
+        let start = faces.len();
+        for j in 0..ny {
+            for i in 0..nx {
+                faces.push(vec![
+                    p(i, j, 0),
+                    p(i, j + 1, 0),
+                    p(i + 1, j + 1, 0),
+                    p(i + 1, j, 0),
+                ]);
+                owner.push(c(i, j, 0));
+            }
+        }
+        push_patch(4, start, faces.len());
+        let start = faces.len();
+        for j in 0..ny {
+            for i in 0..nx {
+                faces.push(vec![
+                    p(i, j, nz),
+                    p(i + 1, j, nz),
+                    p(i + 1, j + 1, nz),
+                    p(i, j + 1, nz),
+                ]);
+                owner.push(c(i, j, nz - 1));
+            }
+        }
+        push_patch(5, start, faces.len());
+
+        let mesh = PrimitiveMesh::new(points, faces, owner, neighbor)?;
+        Ok((mesh, patches))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_box(n: [usize; 3]) -> CartesianBox {
+        CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), n)
+    }
+
+    #[test]
+    fn counts_match_structured_formulae() {
+        let (mesh, _) = unit_box([3, 4, 5]).build().unwrap();
+        assert_eq!(mesh.n_cells(), 60);
+        assert_eq!(mesh.n_points(), 4 * 5 * 6);
+        assert_eq!(mesh.n_internal_faces(), 2 * 4 * 5 + 3 * 3 * 5 + 3 * 4 * 4);
+        assert_eq!(mesh.n_faces() - mesh.n_internal_faces(), 2 * (20 + 15 + 12));
+    }
+
+    #[test]
+    fn internal_faces_are_upper_triangular() {
+        let (mesh, _) = unit_box([3, 3, 3]).build().unwrap();
+        let owner = mesh.owner();
+        let neighbor = mesh.neighbor();
+        for (f, &n) in neighbor.iter().enumerate() {
+            assert!(owner[f] < n, "face {f}: owner {} >= neighbor {n}", owner[f]);
+        }
+        for f in 1..neighbor.len() {
+            let prev = (owner[f - 1], neighbor[f - 1]);
+            let cur = (owner[f], neighbor[f]);
+            assert!(prev < cur, "faces {} and {f} out of order", f - 1);
+        }
+    }
+
+    #[test]
+    fn patches_cover_boundary_contiguously() {
+        let (mesh, patches) = unit_box([2, 3, 4]).build().unwrap();
+        let names: Vec<&str> = patches.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, SIDE_NAMES);
+        assert_eq!(patches[0].start, mesh.n_internal_faces());
+        for w in patches.windows(2) {
+            assert_eq!(w[0].start + w[0].size, w[1].start);
+        }
+        assert_eq!(patches[5].start + patches[5].size, mesh.n_faces());
+        assert_eq!(patches[0].size, 12);
+        assert_eq!(patches[2].size, 8);
+        assert_eq!(patches[4].size, 6);
+    }
+
+    #[test]
+    fn boundary_normals_point_outward() {
+        let (mesh, patches) = unit_box([2, 2, 2]).build().unwrap();
+        let expected = [
+            Vector::new(-1.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, -1.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, -1.0),
+            Vector::new(0.0, 0.0, 1.0),
+        ];
+        let areas = mesh.face_areas();
+        for (patch, dir) in patches.iter().zip(expected) {
+            for f in patch.range() {
+                let n = areas[f] / areas[f].mag();
+                assert!((n - dir).mag() < 1e-12, "{} face {f}", patch.name);
+            }
+        }
+    }
+
+    #[test]
+    fn internal_normals_point_owner_to_neighbor() {
+        let (mesh, _) = unit_box([2, 2, 2]).build().unwrap();
+        let cc = mesh.cell_centers();
+        for (f, &n) in mesh.neighbor().iter().enumerate() {
+            let d = cc[n] - cc[mesh.owner()[f]];
+            assert!(mesh.face_areas()[f] * d > 0.0, "face {f}");
+        }
+    }
+
+    #[test]
+    fn volumes_sum_to_box_volume() {
+        let (mesh, _) = CartesianBox::new(
+            Vector::new(-1.0, 0.0, 2.0),
+            Vector::new(1.0, 3.0, 2.5),
+            [4, 3, 2],
+        )
+        .with_grading([3.0, 0.5, 1.0])
+        .build()
+        .unwrap();
+        let total: f64 = mesh.cell_volumes().iter().sum();
+        assert!((total - 3.0).abs() < 1e-12, "total volume {total}");
+        assert!(mesh.cell_volumes().iter().all(|&v| v > 0.0));
+    }
+
+    #[test]
+    fn grading_sets_last_to_first_width_ratio() {
+        let (mesh, _) = unit_box([5, 1, 1])
+            .with_grading([4.0, 1.0, 1.0])
+            .build()
+            .unwrap();
+        let vols = mesh.cell_volumes();
+        assert!((vols[4] / vols[0] - 4.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn zero_cell_count_returns_err() {
+        let result = unit_box([2, 0, 2]).build();
+        assert!(matches!(
+            result,
+            Err(MeshError::ZeroCellCount { direction: 1 })
+        ));
+    }
+
+    #[test]
+    fn inverted_extent_returns_err() {
+        let result =
+            CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, -1.0), [1, 1, 1]).build();
+        assert!(matches!(
+            result,
+            Err(MeshError::InvalidExtent { direction: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn non_positive_grading_returns_err() {
+        let result = unit_box([2, 2, 2]).with_grading([1.0, 0.0, 1.0]).build();
+        assert!(matches!(
+            result,
+            Err(MeshError::InvalidGrading { direction: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use dugong_types::tensor::Vector;
+
+use super::DEFAULT_PATCH_NAME;
+use crate::assembly::{assemble_cells, hex_faces};
+use crate::error::MeshError;
+use crate::patch_def::PatchDef;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// Type of the patch collecting boundary faces not listed in any
+/// [`ShapePatch`].
+const DEFAULT_SHAPE_PATCH_TYPE: &str = "patch";
+
+/// A linear cell given by its vertices in OpenFOAM (and Gmsh) order.
+///
+/// - `Tet`: `(v1 - v0) × (v2 - v0) · (v3 - v0) > 0`.
+/// - `Pyramid`: base `0-3`, counter-clockwise seen from the apex `4`.
+/// - `Prism`: bottom triangle `0-2`, top triangle `3-5` with `i + 3` above
+///   `i`.
+/// - `Hex`: bottom `0-3`, top `4-7` with `i + 4` above `i`.
+///
+/// Cells whose vertex order yields a negative volume are turned inside out
+/// by [`ShapeMesh::build`], so either handedness is accepted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CellShape {
+    Tet([usize; 4]),
+    Pyramid([usize; 5]),
+    Prism([usize; 6]),
+    Hex([usize; 8]),
+}
+
+impl CellShape {
+    /// Returns the vertex indices of the cell.
+    pub fn vertices(&self) -> &[usize] {
+        match self {
+            CellShape::Tet(v) => v,
+            CellShape::Pyramid(v) => v,
+            CellShape::Prism(v) => v,
+            CellShape::Hex(v) => v,
+        }
+    }
+
+    /// Returns the faces of the cell, oriented outward for a positively
+    /// oriented vertex order.
+    pub fn faces(&self) -> Vec<Vec<usize>> {
+        match self {
+            CellShape::Tet(v) => vec![
+                vec![v[0], v[2], v[1]],
+                vec![v[0], v[1], v[3]],
+                vec![v[1], v[2], v[3]],
+                vec![v[0], v[3], v[2]],
+            ],
+            CellShape::Pyramid(v) => vec![
+                vec![v[0], v[3], v[2], v[1]],
+                vec![v[0], v[1], v[4]],
+                vec![v[1], v[2], v[4]],
+                vec![v[2], v[3], v[4]],
+                vec![v[3], v[0], v[4]],
+            ],
+            CellShape::Prism(v) => vec![
+                vec![v[0], v[2], v[1]],
+                vec![v[3], v[4], v[5]],
+                vec![v[0], v[1], v[4], v[3]],
+                vec![v[1], v[2], v[5], v[4]],
+                vec![v[2], v[0], v[3], v[5]],
+            ],
+            CellShape::Hex(v) => hex_faces(v).to_vec(),
+        }
+    }
+}
+
+/// A named boundary patch given by its faces.
+///
+/// Each entry of `faces` lists the vertices of one cell face, in any order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShapePatch {
+    /// Patch name.
+    pub name: String,
+    /// OpenFOAM-style patch type name.
+    pub patch_type: String,
+    /// Faces belonging to the patch.
+    pub faces: Vec<Vec<usize>>,
+}
+
+impl ShapePatch {
+    /// Creates a new patch.
+    pub fn new(
+        name: impl Into<String>,
+        patch_type: impl Into<String>,
+        faces: Vec<Vec<usize>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            patch_type: patch_type.into(),
+            faces,
+        }
+    }
+}
+
+/// An unstructured mesh described by points, cell shapes and boundary
+/// patches, as produced by external mesh generators.
+///
+/// Cells keep their order. Faces shared by two cells become internal faces;
+/// boundary faces are grouped into the listed patches in order, and faces not
+/// covered by any patch go to a final [`DEFAULT_PATCH_NAME`] patch of type
+/// `patch`, which is only emitted when non-empty. Patch faces that turn out to
+/// be internal (e.g. internal surfaces of a CAD model) are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShapeMesh {
+    /// Point coordinates.
+    pub points: Vec<Vector>,
+    /// Cells as shapes over `points`.
+    pub cells: Vec<CellShape>,
+    /// Named boundary patches.
+    pub patches: Vec<ShapePatch>,
+}
+
+impl ShapeMesh {
+    /// Creates a new mesh description.
+    pub fn new(points: Vec<Vector>, cells: Vec<CellShape>, patches: Vec<ShapePatch>) -> Self {
+        Self {
+            points,
+            cells,
+            patches,
+        }
+    }
+
+    /// Builds the mesh and the patch face ranges.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a cell vertex is out of range, a patch face is not a
+    /// face of any cell, or the cells do not form a valid face-based mesh
+    /// (e.g. a face shared by more than two cells).
+    pub fn build(&self) -> Result<(PrimitiveMesh, Vec<PatchDef>), MeshError> {
+        let n_points = self.points.len();
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for (c, shape) in self.cells.iter().enumerate() {
+            if let Some(&vertex) = shape.vertices().iter().find(|&&v| v >= n_points) {
+                return Err(MeshError::CellVertexOutOfRange {
+                    cell: c,
+                    vertex,
+                    n_points,
+                });
+            }
+            let mut faces = shape.faces();
+            if signed_volume(&self.points, &faces) < 0.0 {
+                for face in &mut faces {
+                    face.reverse();
+                }
+            }
+            cells.push(faces);
+        }
+
+        // Sorted vertex set -> patch index
+        let mut face_patch: HashMap<Vec<usize>, usize> = HashMap::new();
+        for faces in &cells {
+            for face in faces {
+                face_patch.entry(sorted(face)).or_insert(usize::MAX);
+            }
+        }
+        for (pi, patch) in self.patches.iter().enumerate() {
+            for face in &patch.faces {
+                match face_patch.get_mut(&sorted(face)) {
+                    Some(slot) => *slot = pi,
+                    None => {
+                        return Err(MeshError::UnknownBoundaryFace {
+                            patch: patch.name.clone(),
+                            face: face.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        let default_tag = self.patches.len();
+
+        let assembled = assemble_cells(self.points.clone(), &cells, |c, lf| {
+            match face_patch[&sorted(&cells[c][lf])] {
+                usize::MAX => default_tag,
+                pi => pi,
+            }
+        })?;
+
+        let mut counts = vec![0usize; self.patches.len() + 1];
+        for &tag in &assembled.boundary_tags {
+            counts[tag] += 1;
+        }
+        let mut start = assembled.mesh.n_internal_faces();
+        let mut patches = Vec::with_capacity(counts.len());
+        for (patch, &size) in self.patches.iter().zip(&counts) {
+            patches.push(PatchDef::new(&patch.name, &patch.patch_type, start, size));
+            start += size;
+        }
+        if counts[default_tag] > 0 {
+            patches.push(PatchDef::new(
+                DEFAULT_PATCH_NAME,
+                DEFAULT_SHAPE_PATCH_TYPE,
+                start,
+                counts[default_tag],
+            ));
+        }
+
+        Ok((assembled.mesh, patches))
+    }
+}
+
+fn sorted(face: &[usize]) -> Vec<usize> {
+    let mut key = face.to_vec();
+    key.sort_unstable();
+    key
+}
+
+/// Returns the volume enclosed by `faces`, positive if they point outward.
+fn signed_volume(points: &[Vector], faces: &[Vec<usize>]) -> f64 {
+    let mut six_v = 0.0;
+    for face in faces {
+        let a = points[face[0]];
+        for i in 1..face.len() - 1 {
+            let b = points[face[i]];
+            let c = points[face[i + 1]];
+            six_v += a * b.cross(&c);
+        }
+    }
+    six_v / 6.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points: unit cube corners `0-7` (OpenFOAM hex order), a second cube
+    /// layer `8-11` at x = 2, and an apex `12` at x = 2.5 beyond it.
+    fn mixed_points() -> Vec<Vector> {
+        vec![
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(1.0, 1.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(1.0, 0.0, 1.0),
+            Vector::new(1.0, 1.0, 1.0),
+            Vector::new(0.0, 1.0, 1.0),
+            Vector::new(2.0, 0.0, 0.0),
+            Vector::new(2.0, 1.0, 0.0),
+            Vector::new(2.0, 0.0, 1.0),
+            Vector::new(2.0, 1.0, 1.0),
+            Vector::new(2.5, 0.5, 0.5),
+        ]
+    }
+
+    /// Hex `[0, 1]³`, two prisms filling `[1, 2] × [0, 1]²` split along the
+    /// diagonal plane through points 1, 5, 9 and 11, and a pyramid on the x = 2
+    /// face.
+    fn mixed_mesh() -> ShapeMesh {
+        let cells = vec![
+            CellShape::Hex([0, 1, 2, 3, 4, 5, 6, 7]),
+            // Prisms: bottom triangles in the z = 0 plane
+            CellShape::Prism([1, 8, 9, 5, 10, 11]),
+            CellShape::Prism([1, 9, 2, 5, 11, 6]),
+            // Pyramid on the x = 2 face, apex outward
+            CellShape::Pyramid([8, 9, 11, 10, 12]),
+        ];
+        let patches = vec![ShapePatch::new("left", "wall", vec![vec![0, 4, 7, 3]])];
+        ShapeMesh::new(mixed_points(), cells, patches)
+    }
+
+    #[test]
+    fn mixed_shapes_share_faces() {
+        let (mesh, patches) = mixed_mesh().build().unwrap();
+        assert_eq!(mesh.n_cells(), 4);
+        // hex|prism, prism|prism, prism|pyramid
+        assert_eq!(mesh.n_internal_faces(), 3);
+        let volumes = mesh.cell_volumes();
+        let expected = [1.0, 0.5, 0.5, 1.0 / 6.0];
+        for (c, (&v, &e)) in volumes.iter().zip(&expected).enumerate() {
+            assert!((v - e).abs() < 1e-12, "cell {c}: volume {v}, expected {e}");
+        }
+        assert!(mesh.check_topology().is_ok());
+        let names: Vec<&str> = patches.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["left", DEFAULT_PATCH_NAME]);
+        assert_eq!(patches[0].size, 1);
+        assert_eq!(patches[1].patch_type, "patch");
+        assert_eq!(patches[1].start + patches[1].size, mesh.n_faces());
+    }
+
+    #[test]
+    fn inverted_cells_are_flipped() {
+        let cells = vec![
+            CellShape::Tet([0, 1, 3, 4]),
+            // Swapping two vertices gives a negative orientation
+            CellShape::Tet([2, 1, 3, 6]),
+        ];
+        let (mesh, _) = ShapeMesh::new(mixed_points(), cells, vec![])
+            .build()
+            .unwrap();
+        assert_eq!(mesh.n_internal_faces(), 0);
+        for &v in mesh.cell_volumes() {
+            assert!(v > 0.0, "volume {v}");
+        }
+    }
+
+    #[test]
+    fn unknown_patch_face_and_bad_vertex_are_rejected() {
+        let mut sm = mixed_mesh();
+        sm.patches
+            .push(ShapePatch::new("bogus", "wall", vec![vec![0, 1, 12]]));
+        assert!(matches!(
+            sm.build(),
+            Err(MeshError::UnknownBoundaryFace { .. })
+        ));
+
+        let mut sm = mixed_mesh();
+        sm.cells[3] = CellShape::Pyramid([8, 9, 11, 10, 99]);
+        assert!(matches!(
+            sm.build(),
+            Err(MeshError::CellVertexOutOfRange {
+                cell: 3,
+                vertex: 99,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn internal_patch_faces_are_ignored() {
+        let mut sm = mixed_mesh();
+        sm.patches
+            .push(ShapePatch::new("baffle", "wall", vec![vec![1, 2, 6, 5]]));
+        let (mesh, patches) = sm.build().unwrap();
+        assert_eq!(patches[1].name, "baffle");
+        assert_eq!(patches[1].size, 0);
+        assert_eq!(mesh.n_internal_faces(), 3);
+    }
+}
@@ -1,6 +1,8 @@
-use std::collections::BTreeSet;
-
 use dugong_types::tensor::Vector;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::compact_list::CompactList;
 
 /// Computes the centroid and area vector of a single face.
 ///
@@ -58,9 +60,10 @@ pub(crate) fn compute_face_geometry(points: &[Vector], face: &[usize]) -> (Vecto
 /// - All point indices in each face are within `points` bounds.
 /// - All `owner` elements are less than `n_cells`.
 /// - All `neighbor` elements are less than `n_cells`.
+#[cfg_attr(feature = "parallel", allow(dead_code))]
 pub(crate) fn compute_cell_geometry(
     points: &[Vector],
-    faces: &[Vec<usize>],
+    faces: &CompactList,
     owner: &[usize],
     neighbor: &[usize],
     n_cells: usize,
@@ -122,8 +125,108 @@ pub(crate) fn compute_cell_geometry(
     (cell_volumes, cell_centers)
 }
 
+/// Computes centers and area vectors of all faces on the rayon thread pool.
+///
+/// Each face is independent, so the result equals the serial loop over
+/// [`compute_face_geometry`].
+#[cfg(feature = "parallel")]
+pub(crate) fn compute_face_geometry_par(
+    points: &[Vector],
+    faces: &CompactList,
+) -> (Vec<Vector>, Vec<Vector>) {
+    (0..faces.len())
+        .into_par_iter()
+        .map(|f| compute_face_geometry(points, &faces[f]))
+        .unzip()
+}
+
+/// Parallel counterpart of [`compute_cell_geometry`].
+///
+/// Instead of scattering face contributions into the cells, every cell
+/// gathers its own faces from `cell_faces`, so no two threads write the same
+/// cell. `cell_faces` lists owned faces before neighbor faces, each in
+/// ascending order (see [`compute_cell_faces`]), which is exactly the order
+/// in which the serial owner and neighbor loops accumulate into a cell, so
+/// the results are bit-identical.
+///
+/// # Panics
+///
+/// Panics if any point index in `faces` or face index in `cell_faces` is out
+/// of bounds.
+#[cfg(feature = "parallel")]
+pub(crate) fn compute_cell_geometry_par(
+    points: &[Vector],
+    faces: &CompactList,
+    owner: &[usize],
+    cell_faces: &CompactList,
+) -> (Vec<f64>, Vec<Vector>) {
+    let face_geom: Vec<(Vector, Vector)> = (0..faces.len())
+        .into_par_iter()
+        .map(|f| compute_face_geometry(points, &faces[f]))
+        .collect();
+
+    (0..cell_faces.len())
+        .into_par_iter()
+        .map(|ci| {
+            let cell = &cell_faces[ci];
+            let mut c_ref = Vector::zero();
+            for &fi in cell {
+                c_ref += face_geom[fi].0;
+            }
+            if !cell.is_empty() {
+                c_ref /= cell.len() as f64;
+            }
+
+            let mut volume = 0.0_f64;
+            let mut center_weighted = Vector::zero();
+            for &fi in cell {
+                let (fc, fa) = face_geom[fi];
+                // Area vector points away from the owner
+                let fa = if owner[fi] == ci { fa } else { -fa };
+                let pyr_vol = fa * (fc - c_ref) / 3.0;
+                let pyr_center = c_ref * 0.75 + fc * 0.25;
+                volume += pyr_vol;
+                center_weighted += pyr_center * pyr_vol;
+            }
+
+            let center = if volume.abs() > 1e-30 {
+                center_weighted / volume
+            } else {
+                c_ref
+            };
+            (volume, center)
+        })
+        .unzip()
+}
+
+/// Groups `(key, value)` pairs by key, keeping the order of `pairs` within
+/// each key.
+///
+/// # Panics
+///
+/// Panics if any key is greater than or equal to `n_keys`.
+fn group_by_key(n_keys: usize, pairs: impl Iterator<Item = (usize, usize)> + Clone) -> CompactList {
+    let mut offsets = vec![0usize; n_keys + 1];
+    for (k, _) in pairs.clone() {
+        offsets[k + 1] += 1;
+    }
+    for k in 0..n_keys {
+        offsets[k + 1] += offsets[k];
+    }
+    let mut cursor = offsets[..n_keys].to_vec();
+    let mut values = vec![0usize; offsets[n_keys]];
+    for (k, v) in pairs {
+        values[cursor[k]] = v;
+        cursor[k] += 1;
+    }
+    CompactList::from_parts(offsets, values)
+}
+
 /// Builds the list of face indices adjacent to each cell.
 ///
+/// Each cell lists its owned faces first, then its neighbor faces, both in
+/// ascending order.
+///
 /// # Panics
 ///
 /// Panics if any of the following preconditions are violated:
@@ -133,15 +236,10 @@ pub(crate) fn compute_cell_faces(
     owner: &[usize],
     neighbor: &[usize],
     n_cells: usize,
-) -> Vec<Vec<usize>> {
-    let mut result = vec![Vec::new(); n_cells];
-    for (fi, &o) in owner.iter().enumerate() {
-        result[o].push(fi);
-    }
-    for (fi, &n) in neighbor.iter().enumerate() {
-        result[n].push(fi);
-    }
-    result
+) -> CompactList {
+    let owned = owner.iter().enumerate().map(|(fi, &o)| (o, fi));
+    let neighbored = neighbor.iter().enumerate().map(|(fi, &n)| (n, fi));
+    group_by_key(n_cells, owned.chain(neighbored))
 }
 
 /// Derives the list of neighboring cells for each cell.
@@ -154,19 +252,17 @@ pub(crate) fn compute_cell_faces(
 /// Panics if any element of `owner[0..neighbor.len()]` or `neighbor` is
 /// greater than or equal to `n_cells`.
 pub(crate) fn compute_cell_cells(
-    cell_faces: &[Vec<usize>],
+    cell_faces: &CompactList,
     owner: &[usize],
     neighbor: &[usize],
     n_cells: usize,
-) -> Vec<Vec<usize>> {
-    let mut result = vec![Vec::new(); n_cells];
-    for (fi, &n) in neighbor.iter().enumerate() {
-        let o = owner[fi];
-        result[o].push(n);
-        result[n].push(o);
-    }
+) -> CompactList {
+    let pairs = neighbor
+        .iter()
+        .enumerate()
+        .flat_map(|(fi, &n)| [(owner[fi], n), (n, owner[fi])]);
     let _ = cell_faces; // signature kept per design
-    result
+    group_by_key(n_cells, pairs)
 }
 
 /// Collects the point indices belonging to each cell, with duplicates removed.
@@ -177,23 +273,102 @@ pub(crate) fn compute_cell_cells(
 /// # Panics
 ///
 /// Panics if any face index in `cell_faces` is not a valid index into `faces`.
-pub(crate) fn compute_cell_points(
-    cell_faces: &[Vec<usize>],
-    faces: &[Vec<usize>],
-) -> Vec<Vec<usize>> {
-    let mut result = Vec::with_capacity(cell_faces.len());
+pub(crate) fn compute_cell_points(cell_faces: &CompactList, faces: &CompactList) -> CompactList {
+    let mut result = CompactList::with_capacity(cell_faces.len(), 0);
+    let mut pts = Vec::new();
     for cell_face_indices in cell_faces {
-        let mut pts = BTreeSet::new();
+        pts.clear();
         for &fi in cell_face_indices {
-            for &pi in &faces[fi] {
-                pts.insert(pi);
-            }
+            pts.extend_from_slice(&faces[fi]);
+        }
+        pts.sort_unstable();
+        pts.dedup();
+        result.push(&pts);
+    }
+    result
+}
+
+/// Inverts a cell-to-point (or face-to-point) list into the cells (faces)
+/// using each point, in ascending order.
+///
+/// # Panics
+///
+/// Panics if any point index is greater than or equal to `n_points`.
+pub(crate) fn compute_point_lists(lists: &CompactList, n_points: usize) -> CompactList {
+    let pairs = lists
+        .iter()
+        .enumerate()
+        .flat_map(|(i, list)| list.iter().map(move |&p| (p, i)));
+    group_by_key(n_points, pairs)
+}
+
+/// Collects the unique edges of all faces as `[lower, higher]` point pairs,
+/// sorted lexicographically.
+pub(crate) fn compute_edges(faces: &CompactList) -> Vec<[usize; 2]> {
+    let mut edges: Vec<[usize; 2]> = faces
+        .iter()
+        .flat_map(|face| {
+            (0..face.len()).map(move |i| {
+                let (a, b) = (face[i], face[(i + 1) % face.len()]);
+                [a.min(b), a.max(b)]
+            })
+        })
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+/// Returns the edge index of each side of each face: entry `i` of face `f`
+/// is the edge from `faces[f][i]` to the next point of the face.
+///
+/// # Panics
+///
+/// Panics if a face side is missing from `edges`.
+pub(crate) fn compute_face_edges(faces: &CompactList, edges: &[[usize; 2]]) -> CompactList {
+    let mut result = CompactList::with_capacity(faces.len(), faces.values().len());
+    let mut face_edges = Vec::new();
+    for face in faces {
+        face_edges.clear();
+        for i in 0..face.len() {
+            let (a, b) = (face[i], face[(i + 1) % face.len()]);
+            let edge = edges
+                .binary_search(&[a.min(b), a.max(b)])
+                .expect("face side is an edge");
+            face_edges.push(edge);
         }
-        result.push(pts.into_iter().collect());
+        result.push(&face_edges);
     }
     result
 }
 
+/// Collects the edge indices of each cell from its faces, sorted and with
+/// duplicates removed.
+///
+/// # Panics
+///
+/// Panics if any face index in `cell_faces` is not a valid index into
+/// `face_edges`.
+pub(crate) fn compute_cell_edges(
+    cell_faces: &CompactList,
+    face_edges: &CompactList,
+) -> CompactList {
+    // Same gathering as the cell points, over face edges instead of points
+    compute_cell_points(cell_faces, face_edges)
+}
+
+/// Returns the points joined to each point by an edge, in ascending order.
+///
+/// # Panics
+///
+/// Panics if any edge point is greater than or equal to `n_points`.
+pub(crate) fn compute_point_points(edges: &[[usize; 2]], n_points: usize) -> CompactList {
+    // Edges are sorted, so each point sees its lower neighbors (as the
+    // second point of an edge) in ascending order before its higher ones
+    let pairs = edges.iter().flat_map(|&[a, b]| [(a, b), (b, a)]);
+    group_by_key(n_points, pairs)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,15 +401,15 @@ mod tests {
 
     /// 6 faces of a unit cube. Vertices are listed counter-clockwise when
     /// viewed from the owner side, so the right-hand rule gives an outward normal.
-    fn cube_faces() -> Vec<Vec<usize>> {
-        vec![
+    fn cube_faces() -> CompactList {
+        CompactList::from(vec![
             vec![0, 3, 2, 1], // f0: z- (normal -z)
             vec![4, 5, 6, 7], // f1: z+ (normal +z)
             vec![0, 1, 5, 4], // f2: y- (normal -y)
             vec![3, 7, 6, 2], // f3: y+ (normal +y)
             vec![0, 4, 7, 3], // f4: x- (normal -x)
             vec![1, 2, 6, 5], // f5: x+ (normal +x)
-        ]
+        ])
     }
 
     // ===== compute_face_geometry =====
@@ -333,7 +508,7 @@ mod tests {
             Vector::new(2.0, 0.0, 1.0), // 10
             Vector::new(2.0, 1.0, 1.0), // 11
         ];
-        let faces = vec![
+        let faces = CompactList::from(vec![
             vec![1, 2, 6, 5],   // f0:  internal (owner=0→neighbor=1, normal +x)
             vec![0, 3, 2, 1],   // f1:  cell0 z- (normal -z)
             vec![4, 5, 6, 7],   // f2:  cell0 z+ (normal +z)
@@ -345,24 +520,57 @@ mod tests {
             vec![2, 6, 11, 9],  // f8:  cell1 y+ (normal +y)
             vec![1, 2, 9, 8],   // f9:  cell1 z- (normal -z)
             vec![5, 10, 11, 6], // f10: cell1 z+ (normal +z)
-        ];
+        ]);
         let owner = vec![0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1];
         let neighbor = vec![1];
         let (vols, centers) = compute_cell_geometry(&pts, &faces, &owner, &neighbor, 2);
-        for i in 0..2 {
-            assert!(
-                (vols[i] - 1.0).abs() < 1e-10,
-                "cell {i} volume error, got {}",
-                vols[i]
-            );
+        for (i, &v) in vols.iter().enumerate() {
+            assert!((v - 1.0).abs() < 1e-10, "cell {i} volume error, got {v}");
         }
         let expected = [Vector::new(0.5, 0.5, 0.5), Vector::new(1.5, 0.5, 0.5)];
-        for i in 0..2 {
-            let diff = (centers[i] - expected[i]).mag();
+        for (i, (c, e)) in centers.iter().zip(expected.iter()).enumerate() {
+            let diff = (*c - *e).mag();
             assert!(diff < 1e-10, "cell {i} center error {diff}");
         }
     }
 
+    // ===== compute_cell_geometry_par =====
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_cell_geometry_is_bit_identical() {
+        use crate::generation::CartesianBox;
+
+        let (mesh, _) = CartesianBox::new(Vector::zero(), Vector::new(1.0, 2.0, 1.0), [7, 5, 4])
+            .build()
+            .unwrap();
+        // Perturb the points so the faces are warped and sums do not cancel
+        let points: Vec<Vector> = mesh
+            .points()
+            .iter()
+            .map(|&p| p + Vector::new((p.y() * 7.1).sin(), (p.z() * 5.3).cos(), p.x()) * 0.01)
+            .collect();
+        let (faces, owner, neighbor) = (mesh.faces(), mesh.owner(), mesh.neighbor());
+        let n_cells = mesh.n_cells();
+
+        let bits = |v: &Vector| v.as_array().map(f64::to_bits);
+        let (centers, areas) = compute_face_geometry_par(&points, faces);
+        for (f, face) in faces.iter().enumerate() {
+            let (fc, fa) = compute_face_geometry(&points, face);
+            assert_eq!(bits(&centers[f]), bits(&fc));
+            assert_eq!(bits(&areas[f]), bits(&fa));
+        }
+
+        let (volumes, centers) = compute_cell_geometry(&points, faces, owner, neighbor, n_cells);
+        let cell_faces = compute_cell_faces(owner, neighbor, n_cells);
+        let (volumes_par, centers_par) =
+            compute_cell_geometry_par(&points, faces, owner, &cell_faces);
+        for c in 0..n_cells {
+            assert_eq!(volumes[c].to_bits(), volumes_par[c].to_bits());
+            assert_eq!(bits(&centers[c]), bits(&centers_par[c]));
+        }
+    }
+
     // ===== compute_cell_faces =====
 
     #[test]
@@ -390,7 +598,7 @@ mod tests {
 
     #[test]
     fn cell_cells_no_internal_faces() {
-        let cf = vec![vec![0, 1, 2, 3, 4, 5]];
+        let cf = CompactList::from(vec![vec![0, 1, 2, 3, 4, 5]]);
         let owner = vec![0; 6];
         let neighbor: Vec<usize> = vec![];
         let cc = compute_cell_cells(&cf, &owner, &neighbor, 1);
@@ -400,7 +608,7 @@ mod tests {
 
     #[test]
     fn cell_cells_two_cells_symmetric() {
-        let cf = vec![vec![0, 1, 2, 3, 4, 5], vec![0, 6, 7, 8, 9, 10]];
+        let cf = CompactList::from(vec![vec![0, 1, 2, 3, 4, 5], vec![0, 6, 7, 8, 9, 10]]);
         let owner = vec![0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1];
         let neighbor = vec![1];
         let cc = compute_cell_cells(&cf, &owner, &neighbor, 2);
@@ -413,7 +621,7 @@ mod tests {
     #[test]
     fn cell_points_single_cube() {
         let faces = cube_faces();
-        let cf = vec![vec![0, 1, 2, 3, 4, 5]];
+        let cf = CompactList::from(vec![vec![0, 1, 2, 3, 4, 5]]);
         let cp = compute_cell_points(&cf, &faces);
         assert_eq!(cp.len(), 1);
         assert_eq!(cp[0], vec![0, 1, 2, 3, 4, 5, 6, 7]);
@@ -422,9 +630,9 @@ mod tests {
     #[test]
     fn cell_points_no_duplicates() {
         let faces = cube_faces();
-        let cf = vec![vec![0, 1, 2, 3, 4, 5]];
+        let cf = CompactList::from(vec![vec![0, 1, 2, 3, 4, 5]]);
         let cp = compute_cell_points(&cf, &faces);
-        let mut sorted = cp[0].clone();
+        let mut sorted = cp[0].to_vec();
         sorted.sort();
         sorted.dedup();
         assert_eq!(cp[0].len(), sorted.len());
@@ -432,11 +640,11 @@ mod tests {
 
     #[test]
     fn cell_points_sorted() {
-        // BTreeSet guarantees sorted output
+        // Sorting and deduplication guarantee sorted output
         let faces = cube_faces();
-        let cf = vec![vec![0, 1, 2, 3, 4, 5]];
+        let cf = CompactList::from(vec![vec![0, 1, 2, 3, 4, 5]]);
         let cp = compute_cell_points(&cf, &faces);
-        let mut sorted = cp[0].clone();
+        let mut sorted = cp[0].to_vec();
         sorted.sort();
         assert_eq!(cp[0], sorted);
     }
@@ -0,0 +1,111 @@
+/// Lower/upper (LDU) matrix addressing of a mesh.
+///
+/// Each internal face couples cell `lower[f]` to cell `upper[f]`, with
+/// `lower[f] < upper[f]`. Faces are expected in upper-triangular order, so
+/// the faces owned by cell `c` are `owner_start[c]..owner_start[c + 1]`.
+/// `losort` lists the faces ordered by `upper`, and the faces whose upper
+/// cell is `c` are `losort[losort_start[c]..losort_start[c + 1]]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LduAddressing {
+    /// Lower (owner) cell of each internal face.
+    pub lower: Vec<usize>,
+    /// Upper (neighbor) cell of each internal face.
+    pub upper: Vec<usize>,
+    /// Internal face indices sorted by upper cell, then by face index.
+    pub losort: Vec<usize>,
+    /// Start of each cell's faces in `lower`; length `n_cells + 1`.
+    pub owner_start: Vec<usize>,
+    /// Start of each cell's faces in `losort`; length `n_cells + 1`.
+    pub losort_start: Vec<usize>,
+}
+
+impl LduAddressing {
+    /// Builds the addressing from the lower and upper cell of each internal
+    /// face.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lower` and `upper` differ in length or contain a cell index
+    /// `>= n_cells`.
+    pub fn new(n_cells: usize, lower: Vec<usize>, upper: Vec<usize>) -> Self {
+        assert_eq!(lower.len(), upper.len(), "lower/upper length mismatch");
+
+        let owner_start = start_offsets(n_cells, &lower);
+        let losort_start = start_offsets(n_cells, &upper);
+
+        // Counting sort by upper cell keeps face order within each cell
+        let mut losort = vec![0; upper.len()];
+        let mut next = losort_start.clone();
+        for (f, &u) in upper.iter().enumerate() {
+            losort[next[u]] = f;
+            next[u] += 1;
+        }
+
+        Self {
+            lower,
+            upper,
+            losort,
+            owner_start,
+            losort_start,
+        }
+    }
+
+    /// Returns the number of cells.
+    pub fn n_cells(&self) -> usize {
+        self.owner_start.len() - 1
+    }
+
+    /// Returns the number of internal faces (off-diagonal coefficient pairs).
+    pub fn n_faces(&self) -> usize {
+        self.lower.len()
+    }
+}
+
+/// Returns the cumulative count of `cells` per cell index, with a leading 0.
+fn start_offsets(n_cells: usize, cells: &[usize]) -> Vec<usize> {
+    let mut start = vec![0; n_cells + 1];
+    for &c in cells {
+        start[c + 1] += 1;
+    }
+    for c in 0..n_cells {
+        start[c + 1] += start[c];
+    }
+    start
+}
+
+/// A mesh that provides LDU addressing for matrix assembly.
+pub trait LduMesh {
+    /// Returns the LDU addressing.
+    fn ldu_addressing(&self) -> &LduAddressing;
+
+    /// Returns the number of cells (matrix rows).
+    fn n_cells(&self) -> usize;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addressing_of_four_cell_ring() {
+        // Cells 0-1-3-2 around a 2 x 2 grid, faces in upper-triangular order
+        let ldu = LduAddressing::new(4, vec![0, 0, 1, 2], vec![1, 2, 3, 3]);
+        assert_eq!(ldu.n_cells(), 4);
+        assert_eq!(ldu.n_faces(), 4);
+        assert_eq!(ldu.owner_start, vec![0, 2, 3, 4, 4]);
+        assert_eq!(ldu.losort, vec![0, 1, 2, 3]);
+        assert_eq!(ldu.losort_start, vec![0, 0, 1, 2, 4]);
+    }
+
+    #[test]
+    fn losort_orders_faces_by_upper_cell() {
+        let ldu = LduAddressing::new(3, vec![0, 0, 1], vec![2, 1, 2]);
+        assert_eq!(ldu.losort, vec![1, 0, 2]);
+        assert_eq!(ldu.losort_start, vec![0, 0, 1, 3]);
+        for c in 0..3 {
+            for &f in &ldu.losort[ldu.losort_start[c]..ldu.losort_start[c + 1]] {
+                assert_eq!(ldu.upper[f], c);
+            }
+        }
+    }
+}
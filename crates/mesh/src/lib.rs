@@ -2,9 +2,54 @@
 //!
 //! Provides finite volume mesh representation with cells, faces, and points.
 
+mod affine;
+mod assembly;
+pub mod check;
+pub mod compact_list;
+pub mod decomposition;
+mod dual;
 mod error;
+mod fv_mesh;
+pub mod generation;
 mod geometry;
+mod ldu_addressing;
+mod merge;
+pub mod motion;
+mod patch_def;
+pub mod patches;
+mod poly_mesh;
 mod primitive_mesh;
+mod quality;
+pub mod refinement;
+pub mod renumber;
+mod search;
+pub mod subset;
+pub mod surface;
+mod transform;
+pub mod wall_distance;
+mod zones;
 
+pub use check::{GeometryReport, TopologyReport};
+pub use compact_list::CompactList;
+pub use decomposition::{Decomposition, DecompositionMethod, Subdomain};
 pub use error::MeshError;
+pub use fv_mesh::FvMesh;
+pub use generation::{
+    BlockMesh, BlockPatch, CartesianBox, CellShape, HexBlock, ShapeMesh, ShapePatch,
+};
+pub use ldu_addressing::{LduAddressing, LduMesh};
+pub use motion::MeshMover;
+pub use patch_def::{PatchCoupling, PatchDef};
+pub use patches::{
+    CoupledPatch, CyclicMatch, CyclicPolyPatch, EmptyPolyPatch, GenericPolyPatch, PolyPatch,
+    ProcessorPolyPatch, SymmetryPolyPatch, WallPolyPatch, WedgePolyPatch,
+};
+pub use poly_mesh::PolyMesh;
 pub use primitive_mesh::PrimitiveMesh;
+pub use refinement::HexRefinement;
+pub use renumber::{RenumberMethod, Renumbering};
+pub use subset::SubsetMesh;
+pub use surface::BoundarySurface;
+pub use transform::Transform;
+pub use wall_distance::WallDistanceMethod;
+pub use zones::{FaceZone, Zone};
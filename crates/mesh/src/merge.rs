@@ -0,0 +1,383 @@
+//! Merging meshes and stitching coincident boundary faces.
+
+use std::collections::HashMap;
+
+use crate::assembly::merge_points;
+use crate::error::MeshError;
+use crate::patch_def::PatchDef;
+use crate::patches::check_cyclic_pairs;
+use crate::primitive_mesh::PrimitiveMesh;
+
+impl PrimitiveMesh {
+    /// Combines this mesh with `other` and stitches their common boundary.
+    ///
+    /// `patches` and `other_patches` describe the boundary of each mesh as in
+    /// [`PolyMesh`](crate::PolyMesh). Cells of `other` are numbered after
+    /// those of `self`. Patches with the same name are combined into one, in
+    /// the position of the first occurrence, with the faces of `self` first;
+    /// the remaining patches of `other` are appended. Coupling data is kept,
+    /// so cyclic pairs stay in matched order. The combined mesh is then passed to
+    /// [`stitch`](Self::stitch), so boundary faces of the two meshes whose
+    /// points coincide within `tolerance` become internal faces.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if either patch list does not partition the boundary of
+    /// its mesh, if two patches share a name but not a type or coupling, or
+    /// if [`stitch`](Self::stitch) fails.
+    pub fn merge(
+        &self,
+        patches: &[PatchDef],
+        other: &PrimitiveMesh,
+        other_patches: &[PatchDef],
+        tolerance: f64,
+    ) -> Result<(PrimitiveMesh, Vec<PatchDef>), MeshError> {
+        check_patches(self, patches)?;
+        check_patches(other, other_patches)?;
+
+        let point_offset = self.n_points();
+        let cell_offset = self.n_cells();
+        let face_offset = self.n_faces();
+        let mut points = self.points().to_vec();
+        points.extend_from_slice(other.points());
+        let other_face = |f: usize| -> Vec<usize> {
+            other.faces()[f].iter().map(|&p| p + point_offset).collect()
+        };
+
+        let mut faces = Vec::with_capacity(self.n_faces() + other.n_faces());
+        let mut owner = Vec::with_capacity(faces.capacity());
+        let mut neighbor = Vec::new();
+        for f in 0..self.n_internal_faces() {
+            faces.push(self.faces()[f].to_vec());
+            owner.push(self.owner()[f]);
+            neighbor.push(self.neighbor()[f]);
+        }
+        for f in 0..other.n_internal_faces() {
+            faces.push(other_face(f));
+            owner.push(other.owner()[f] + cell_offset);
+            neighbor.push(other.neighbor()[f] + cell_offset);
+        }
+
+        // Group the boundary faces of both meshes by patch name
+        let mut groups: Vec<(PatchDef, Vec<usize>)> = Vec::new();
+        let sources = [(patches, 0), (other_patches, face_offset)];
+        for (defs, offset) in sources {
+            for def in defs {
+                let face_ids = def.range().map(|f| f + offset);
+                match groups.iter_mut().find(|(g, _)| g.name == def.name) {
+                    Some((group, members)) => {
+                        if group.patch_type != def.patch_type {
+                            return Err(MeshError::PatchTypeMismatch {
+                                patch: def.name.clone(),
+                                patch_type: group.patch_type.clone(),
+                                other_type: def.patch_type.clone(),
+                            });
+                        }
+                        if group.coupling != def.coupling {
+                            return Err(MeshError::PatchCouplingMismatch {
+                                patch: def.name.clone(),
+                            });
+                        }
+                        members.extend(face_ids);
+                    }
+                    None => groups.push((def.clone(), face_ids.collect())),
+                }
+            }
+        }
+
+        let mut merged_patches = Vec::with_capacity(groups.len());
+        for (def, members) in groups {
+            merged_patches.push(PatchDef {
+                start: faces.len(),
+                size: members.len(),
+                ..def
+            });
+            for f in members {
+                if f < face_offset {
+                    faces.push(self.faces()[f].to_vec());
+                    owner.push(self.owner()[f]);
+                } else {
+                    faces.push(other_face(f - face_offset));
+                    owner.push(other.owner()[f - face_offset] + cell_offset);
+                }
+            }
+        }
+
+        let combined = PrimitiveMesh::new(points, faces, owner, neighbor)?;
+        combined.stitch(&merged_patches, tolerance)
+    }
+
+    /// Turns coincident pairs of boundary faces into internal faces.
+    ///
+    /// Boundary points closer than `tolerance` are merged first, keeping the
+    /// lowest-numbered point of each cluster; points away from the boundary
+    /// are never merged. Two boundary faces that then share the same point
+    /// set are replaced by one internal face, taken from the side of the
+    /// lower-numbered cell so that it points out of its owner. The internal
+    /// faces are re-sorted into upper-triangular order and the remaining
+    /// boundary faces keep their patch, coupling and relative order. Patches
+    /// that lose all their faces are kept with size zero. Cell numbering is
+    /// unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `patches` does not partition the boundary, if more
+    /// than two boundary faces coincide, if a cell would be stitched to
+    /// itself, or if a cyclic face would be stitched without its partner.
+    pub fn stitch(
+        &self,
+        patches: &[PatchDef],
+        tolerance: f64,
+    ) -> Result<(PrimitiveMesh, Vec<PatchDef>), MeshError> {
+        check_patches(self, patches)?;
+        let n_internal = self.n_internal_faces();
+        let boundary = n_internal..self.n_faces();
+
+        // Merge boundary points only
+        let mut on_boundary = vec![false; self.n_points()];
+        for face in self.faces().iter().skip(n_internal) {
+            for &p in face {
+                on_boundary[p] = true;
+            }
+        }
+        let boundary_points: Vec<usize> =
+            (0..self.n_points()).filter(|&p| on_boundary[p]).collect();
+        let coords: Vec<_> = boundary_points.iter().map(|&p| self.points()[p]).collect();
+        let (_, cluster) = merge_points(&coords, tolerance);
+
+        let mut point_map = vec![0; self.n_points()];
+        let mut representative: Vec<Option<usize>> = vec![None; coords.len()];
+        let mut points = Vec::with_capacity(self.n_points());
+        let mut next_boundary = 0;
+        for p in 0..self.n_points() {
+            if on_boundary[p] {
+                let c = cluster[next_boundary];
+                next_boundary += 1;
+                if let Some(q) = representative[c] {
+                    point_map[p] = q;
+                    continue;
+                }
+                representative[c] = Some(points.len());
+            }
+            point_map[p] = points.len();
+            points.push(self.points()[p]);
+        }
+        let map_face =
+            |f: usize| -> Vec<usize> { self.faces()[f].iter().map(|&p| point_map[p]).collect() };
+
+        // Pair boundary faces with identical point sets
+        let mut seen: HashMap<Vec<usize>, usize> = HashMap::new();
+        let mut partner: Vec<Option<usize>> = vec![None; self.n_faces()];
+        for f in boundary.clone() {
+            let mut key = map_face(f);
+            key.sort_unstable();
+            match seen.get(&key) {
+                None => {
+                    seen.insert(key, f);
+                }
+                Some(&g) => {
+                    if partner[g].is_some() || self.owner()[g] == self.owner()[f] {
+                        return Err(MeshError::NonManifoldFace { points: key });
+                    }
+                    partner[g] = Some(f);
+                    partner[f] = Some(g);
+                }
+            }
+        }
+
+        // A cyclic face may only be stitched together with its partner, so
+        // the remaining faces of a pair stay in matched order
+        check_cyclic_pairs(patches, |f| usize::from(partner[f].is_some()))?;
+
+        // (owner, neighbor, source face)
+        let mut internal: Vec<(usize, usize, usize)> = (0..n_internal)
+            .map(|f| (self.owner()[f], self.neighbor()[f], f))
+            .collect();
+        for f in boundary.clone() {
+            if let Some(g) = partner[f] {
+                let (a, b) = (self.owner()[f], self.owner()[g]);
+                if a < b {
+                    internal.push((a, b, f));
+                }
+            }
+        }
+        internal.sort_by_key(|&(o, n, _)| (o, n));
+
+        let n_faces = self.n_faces() - (internal.len() - n_internal);
+        let mut faces = Vec::with_capacity(n_faces);
+        let mut owner = Vec::with_capacity(n_faces);
+        let mut neighbor = Vec::with_capacity(internal.len());
+        for &(o, n, f) in &internal {
+            faces.push(map_face(f));
+            owner.push(o);
+            neighbor.push(n);
+        }
+        let mut stitched_patches = Vec::with_capacity(patches.len());
+        for def in patches {
+            let start = faces.len();
+            for f in def.range().filter(|&f| partner[f].is_none()) {
+                faces.push(map_face(f));
+                owner.push(self.owner()[f]);
+            }
+            stitched_patches.push(PatchDef {
+                start,
+                size: faces.len() - start,
+                ..def.clone()
+            });
+        }
+
+        let mesh = PrimitiveMesh::new(points, faces, owner, neighbor)?;
+        Ok((mesh, stitched_patches))
+    }
+}
+
+/// Checks that `patches` cover the boundary faces of `mesh` contiguously and
+/// in order.
+pub(crate) fn check_patches(mesh: &PrimitiveMesh, patches: &[PatchDef]) -> Result<(), MeshError> {
+    let mut expected = mesh.n_internal_faces();
+    for def in patches {
+        if def.start != expected {
+            return Err(MeshError::PatchStartMismatch {
+                patch: def.name.clone(),
+                start: def.start,
+                expected,
+            });
+        }
+        expected += def.size;
+    }
+    if expected != mesh.n_faces() {
+        return Err(MeshError::PatchCoverageMismatch {
+            covered: expected,
+            n_faces: mesh.n_faces(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use dugong_types::tensor::Vector;
+
+    use super::*;
+    use crate::generation::CartesianBox;
+
+    fn boxed(x0: f64, x1: f64, nx: usize) -> (PrimitiveMesh, Vec<PatchDef>) {
+        CartesianBox::new(
+            Vector::new(x0, 0.0, 0.0),
+            Vector::new(x1, 1.0, 1.0),
+            [nx, 2, 1],
+        )
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn merge_two_boxes_stitches_interface() {
+        let (left, left_patches) = boxed(0.0, 1.0, 2);
+        let (right, right_patches) = boxed(1.0, 2.0, 2);
+        let (mesh, patches) = left
+            .merge(&left_patches, &right, &right_patches, 1e-9)
+            .unwrap();
+
+        // Same as a single 4 x 2 x 1 box
+        let (whole, whole_patches) = boxed(0.0, 2.0, 4);
+        assert_eq!(mesh.n_cells(), 8);
+        assert_eq!(mesh.n_points(), whole.n_points());
+        assert_eq!(mesh.n_internal_faces(), whole.n_internal_faces());
+        assert_eq!(mesh.n_faces(), whole.n_faces());
+        assert!(mesh.check_topology().is_ok());
+        assert!(mesh.check_geometry().is_ok());
+
+        let sizes: Vec<_> = patches.iter().map(|p| (p.name.as_str(), p.size)).collect();
+        let whole_sizes: Vec<_> = whole_patches
+            .iter()
+            .map(|p| (p.name.as_str(), p.size))
+            .collect();
+        assert_eq!(sizes[0], ("xMin", 2));
+        assert_eq!(sizes[1], ("xMax", 2));
+        assert_eq!(&sizes[2..], &whole_sizes[2..]);
+        assert!(crate::PolyMesh::from_patch_defs(mesh, &patches).is_ok());
+    }
+
+    #[test]
+    fn stitch_within_tolerance_only() {
+        let (left, left_patches) = boxed(0.0, 1.0, 1);
+        let (right, right_patches) = boxed(1.0 + 1e-6, 2.0, 1);
+
+        let (loose, _) = left
+            .merge(&left_patches, &right, &right_patches, 1e-9)
+            .unwrap();
+        assert_eq!(loose.n_internal_faces(), 2);
+        assert_eq!(loose.n_points(), 24);
+
+        let (tight, _) = left
+            .merge(&left_patches, &right, &right_patches, 1e-5)
+            .unwrap();
+        assert_eq!(tight.n_internal_faces(), 4);
+        assert_eq!(tight.n_points(), 18);
+        // Internal faces stay upper-triangular and point out of the owner
+        let centers = tight.cell_centers();
+        for f in 0..tight.n_internal_faces() {
+            let (o, n) = (tight.owner()[f], tight.neighbor()[f]);
+            assert!(o < n);
+            assert!(tight.face_areas()[f] * (centers[n] - centers[o]) > 0.0);
+        }
+    }
+
+    #[test]
+    fn merged_cyclic_pairs_stay_matched() {
+        use crate::patch_def::PatchCoupling;
+        use crate::transform::Transform;
+
+        // Both boxes are periodic in y
+        let periodic = |x0, x1| {
+            let (mesh, mut patches) = boxed(x0, x1, 2);
+            for (p, neighbor_patch, dy) in [(2, "yMax", -1.0), (3, "yMin", 1.0)] {
+                patches[p].patch_type = "cyclic".to_string();
+                patches[p].coupling = Some(PatchCoupling::Cyclic {
+                    neighbor_patch: neighbor_patch.to_string(),
+                    transform: Transform::Translation {
+                        separation: Vector::new(0.0, dy, 0.0),
+                    },
+                });
+            }
+            (mesh, patches)
+        };
+        let (left, left_patches) = periodic(0.0, 1.0);
+        let (right, right_patches) = periodic(1.0, 2.0);
+        let (mesh, patches) = left
+            .merge(&left_patches, &right, &right_patches, 1e-9)
+            .unwrap();
+        assert_eq!(patches[2].coupling, left_patches[2].coupling);
+        assert_eq!(patches[2].size, 4);
+
+        let poly = crate::PolyMesh::from_patch_defs(mesh, &patches).unwrap();
+        let y_min = poly.patches()[2].as_coupled().unwrap();
+        for (i, &c) in y_min.face_cells().iter().enumerate() {
+            // The cell across the periodic boundary lies one row up, seen
+            // one cell height below
+            let expected = poly.cell_centers()[c] - Vector::new(0.0, 0.5, 0.0);
+            assert!((y_min.neighbor_cell_centers()[i] - expected).mag() < 1e-12);
+        }
+        assert_eq!(poly.patch_defs(), patches);
+
+        // Different couplings under one name cannot be combined
+        let mut other_patches = right_patches.clone();
+        other_patches[2].coupling = None;
+        assert!(matches!(
+            left.merge(&left_patches, &right, &other_patches, 1e-9),
+            Err(MeshError::PatchCouplingMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn mismatched_patch_types_are_rejected() {
+        let (left, left_patches) = boxed(0.0, 1.0, 1);
+        let (right, mut right_patches) = boxed(1.0, 2.0, 1);
+        right_patches[2].patch_type = "wall".to_string();
+        let Err(err) = left.merge(&left_patches, &right, &right_patches, 1e-9) else {
+            panic!("expected a patch type mismatch");
+        };
+        assert!(matches!(err, MeshError::PatchTypeMismatch { .. }));
+    }
+}
@@ -0,0 +1,72 @@
+use std::ops::Range;
+
+use crate::transform::Transform;
+
+/// A named, contiguous range of boundary faces.
+///
+/// `PatchDef` is the plain-data description of a boundary patch as produced by
+/// mesh generators and readers, before it is turned into a typed patch.
+/// Faces `start..start + size` of the owning mesh belong to the patch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatchDef {
+    /// Patch name (e.g. `"inlet"`).
+    pub name: String,
+    /// OpenFOAM-style patch type name (e.g. `"patch"`, `"wall"`, `"empty"`).
+    pub patch_type: String,
+    /// Index of the first face of the patch.
+    pub start: usize,
+    /// Number of faces in the patch.
+    pub size: usize,
+    /// Coupling data of a `"cyclic"` or `"processor"` patch, `None` for
+    /// other types.
+    pub coupling: Option<PatchCoupling>,
+}
+
+/// The data a coupled patch needs beyond its face range.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchCoupling {
+    /// One half of a periodic pair. Face `i` is coupled to face `i` of
+    /// `neighbor_patch`.
+    Cyclic {
+        /// Name of the other half.
+        neighbor_patch: String,
+        /// Maps neighbor-side positions into this patch's frame.
+        transform: Transform,
+    },
+    /// The boundary between the sub-meshes of two processes.
+    Processor {
+        /// Rank owning this side.
+        my_rank: i32,
+        /// Rank on the other side.
+        neighbor_rank: i32,
+    },
+}
+
+impl PatchDef {
+    /// Creates a new patch description without coupling data.
+    pub fn new(
+        name: impl Into<String>,
+        patch_type: impl Into<String>,
+        start: usize,
+        size: usize,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            patch_type: patch_type.into(),
+            start,
+            size,
+            coupling: None,
+        }
+    }
+
+    /// Sets the coupling data.
+    pub fn with_coupling(mut self, coupling: PatchCoupling) -> Self {
+        self.coupling = Some(coupling);
+        self
+    }
+
+    /// Returns the face index range `start..start + size`.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.start + self.size
+    }
+}
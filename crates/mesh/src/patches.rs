@@ -0,0 +1,227 @@
+//! Typed boundary patches.
+//!
+//! A patch is a contiguous range of boundary faces with a name and a type.
+//! Behavior that differs between patch types is expressed through the
+//! [`PolyPatch`] trait; patches that couple their faces to cells elsewhere
+//! (cyclic and processor boundaries) additionally implement
+//! [`CoupledPatch`].
+
+use std::any::Any;
+use std::ops::Range;
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::patch_def::{PatchCoupling, PatchDef};
+use crate::primitive_mesh::PrimitiveMesh;
+use crate::transform::Transform;
+
+mod basic;
+mod cyclic;
+mod processor;
+
+pub use basic::{
+    EmptyPolyPatch, GenericPolyPatch, SymmetryPolyPatch, WallPolyPatch, WedgePolyPatch,
+};
+pub(crate) use cyclic::check_cyclic_pairs;
+pub use cyclic::{CyclicMatch, CyclicPolyPatch};
+pub use processor::ProcessorPolyPatch;
+
+/// A named, typed, contiguous range of boundary faces.
+pub trait PolyPatch: Send + Sync {
+    /// Returns the patch name.
+    fn name(&self) -> &str;
+
+    /// Returns the index of the first face of the patch.
+    fn start(&self) -> usize;
+
+    /// Returns the number of faces in the patch.
+    fn size(&self) -> usize;
+
+    /// Returns the OpenFOAM-style type name (e.g. `"wall"`, `"cyclic"`).
+    fn patch_type(&self) -> &str;
+
+    /// Returns the face index range `start..start + size`.
+    fn range(&self) -> Range<usize> {
+        self.start()..self.start() + self.size()
+    }
+
+    /// Returns the patch as a [`CoupledPatch`] if it is one.
+    fn as_coupled(&self) -> Option<&dyn CoupledPatch> {
+        None
+    }
+
+    /// Returns the patch as a mutable [`CoupledPatch`] if it is one.
+    fn as_coupled_mut(&mut self) -> Option<&mut dyn CoupledPatch> {
+        None
+    }
+
+    /// Returns the patch as [`Any`] for downcasting to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Hook called once the patch is attached to a mesh, so the patch can
+    /// derive data from the mesh topology.
+    fn init_geometry(&mut self, _mesh: &PrimitiveMesh) {}
+
+    /// Hook called after the mesh points have moved.
+    fn move_points(&mut self, _new_points: &[Vector]) {}
+
+    /// Returns the plain-data description of the patch.
+    fn to_patch_def(&self) -> PatchDef {
+        PatchDef::new(self.name(), self.patch_type(), self.start(), self.size())
+    }
+}
+
+/// A patch whose faces are coupled to cells across an interface.
+pub trait CoupledPatch: PolyPatch {
+    /// Returns the owner cell of each patch face.
+    fn face_cells(&self) -> &[usize];
+
+    /// Returns the center of the cell on the other side of each patch face,
+    /// expressed in this patch's frame.
+    fn neighbor_cell_centers(&self) -> &[Vector];
+
+    /// Replaces the neighbor cell centers (e.g. after a parallel exchange).
+    fn set_neighbor_cell_centers(&mut self, centers: Vec<Vector>);
+
+    /// Returns the rank of the neighboring process, or `None` for couplings
+    /// within the same mesh.
+    fn neighbor_rank(&self) -> Option<i32>;
+
+    /// Returns the transform from the neighbor side into this patch's frame.
+    fn transform(&self) -> &Transform;
+}
+
+/// Builds a typed patch from its plain-data description.
+///
+/// `"wall"`, `"empty"`, `"symmetry"` and `"wedge"` map to their dedicated
+/// types; any other non-coupled type becomes a [`GenericPolyPatch`] that keeps
+/// the type name. `"cyclic"` and `"processor"` patches are built from the
+/// matching [`PatchCoupling`].
+///
+/// # Errors
+///
+/// Returns `Err` for a coupled type without the matching coupling data.
+pub fn patch_from_def(def: &PatchDef) -> Result<Box<dyn PolyPatch>, MeshError> {
+    let PatchDef {
+        name,
+        patch_type,
+        start,
+        size,
+        coupling,
+    } = def.clone();
+    Ok(match (patch_type.as_str(), coupling) {
+        (WallPolyPatch::TYPE_NAME, _) => Box::new(WallPolyPatch::new(name, start, size)),
+        (EmptyPolyPatch::TYPE_NAME, _) => Box::new(EmptyPolyPatch::new(name, start, size)),
+        (SymmetryPolyPatch::TYPE_NAME, _) => Box::new(SymmetryPolyPatch::new(name, start, size)),
+        (WedgePolyPatch::TYPE_NAME, _) => Box::new(WedgePolyPatch::new(name, start, size)),
+        (
+            CyclicPolyPatch::TYPE_NAME,
+            Some(PatchCoupling::Cyclic {
+                neighbor_patch,
+                transform,
+            }),
+        ) => Box::new(CyclicPolyPatch::new(
+            name,
+            start,
+            size,
+            neighbor_patch,
+            transform,
+        )),
+        (
+            ProcessorPolyPatch::TYPE_NAME,
+            Some(PatchCoupling::Processor {
+                my_rank,
+                neighbor_rank,
+            }),
+        ) => Box::new(ProcessorPolyPatch::new(
+            name,
+            start,
+            size,
+            my_rank,
+            neighbor_rank,
+        )),
+        (CyclicPolyPatch::TYPE_NAME | ProcessorPolyPatch::TYPE_NAME, _) => {
+            return Err(MeshError::UnsupportedPatchType {
+                patch: name,
+                patch_type,
+            });
+        }
+        _ => Box::new(GenericPolyPatch::new(name, patch_type, start, size)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poly_patch_is_object_safe() {
+        let patches: Vec<Box<dyn PolyPatch>> = vec![
+            Box::new(WallPolyPatch::new("walls", 10, 4)),
+            Box::new(EmptyPolyPatch::new("frontAndBack", 14, 8)),
+            Box::new(CyclicPolyPatch::new(
+                "left",
+                22,
+                2,
+                "right",
+                Transform::Identity,
+            )),
+        ];
+        assert_eq!(patches[0].patch_type(), "wall");
+        assert_eq!(patches[1].range(), 14..22);
+        assert!(patches[0].as_coupled().is_none());
+        assert!(patches[2].as_coupled().is_some());
+        let cyclic = patches[2].as_any().downcast_ref::<CyclicPolyPatch>();
+        assert_eq!(cyclic.unwrap().neighbor_patch_name(), "right");
+    }
+
+    #[test]
+    fn patch_from_def_maps_types() {
+        let wall = patch_from_def(&PatchDef::new("w", "wall", 0, 3)).unwrap();
+        assert!(wall.as_any().is::<WallPolyPatch>());
+        let inlet = patch_from_def(&PatchDef::new("inlet", "patch", 3, 2)).unwrap();
+        assert!(inlet.as_any().is::<GenericPolyPatch>());
+        assert_eq!(inlet.to_patch_def(), PatchDef::new("inlet", "patch", 3, 2));
+        let cyclic = patch_from_def(&PatchDef::new("c", "cyclic", 5, 1));
+        assert!(matches!(
+            cyclic,
+            Err(MeshError::UnsupportedPatchType { .. })
+        ));
+    }
+
+    #[test]
+    fn coupled_patches_round_trip_through_defs() {
+        let shift = Transform::Translation {
+            separation: Vector::new(1.0, 0.0, 0.0),
+        };
+        let defs = [
+            PatchDef::new("left", "cyclic", 4, 2).with_coupling(PatchCoupling::Cyclic {
+                neighbor_patch: "right".to_string(),
+                transform: shift.clone(),
+            }),
+            PatchDef::new("procBoundary1to0", "processor", 6, 3).with_coupling(
+                PatchCoupling::Processor {
+                    my_rank: 1,
+                    neighbor_rank: 0,
+                },
+            ),
+        ];
+        for def in &defs {
+            let patch = patch_from_def(def).unwrap();
+            assert_eq!(&patch.to_patch_def(), def);
+        }
+        let left = patch_from_def(&defs[0]).unwrap();
+        let left = left.as_any().downcast_ref::<CyclicPolyPatch>().unwrap();
+        assert_eq!(left.neighbor_patch_name(), "right");
+        assert_eq!(left.transform(), &shift);
+
+        // The coupling must match the type
+        let mut mismatched = defs[1].clone();
+        mismatched.patch_type = "cyclic".to_string();
+        assert!(matches!(
+            patch_from_def(&mismatched),
+            Err(MeshError::UnsupportedPatchType { .. })
+        ));
+    }
+}
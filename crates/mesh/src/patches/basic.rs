@@ -0,0 +1,127 @@
+use std::any::Any;
+
+use crate::patches::PolyPatch;
+
+/// Defines a non-coupled patch type that carries only its name and face
+/// range.
+macro_rules! basic_patch {
+    ($(#[$doc:meta])* $ty:ident, $type_name:literal) => {
+        $(#[$doc])*
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct $ty {
+            name: String,
+            start: usize,
+            size: usize,
+        }
+
+        impl $ty {
+            /// OpenFOAM type name of this patch.
+            pub const TYPE_NAME: &'static str = $type_name;
+
+            /// Creates a patch covering faces `start..start + size`.
+            pub fn new(name: impl Into<String>, start: usize, size: usize) -> Self {
+                Self {
+                    name: name.into(),
+                    start,
+                    size,
+                }
+            }
+        }
+
+        impl PolyPatch for $ty {
+            fn name(&self) -> &str {
+                &self.name
+            }
+
+            fn start(&self) -> usize {
+                self.start
+            }
+
+            fn size(&self) -> usize {
+                self.size
+            }
+
+            fn patch_type(&self) -> &str {
+                Self::TYPE_NAME
+            }
+
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+        }
+    };
+}
+
+basic_patch!(
+    /// A solid wall.
+    WallPolyPatch,
+    "wall"
+);
+
+basic_patch!(
+    /// The unresolved direction of a 2-D or 1-D case; no flux crosses it.
+    EmptyPolyPatch,
+    "empty"
+);
+
+basic_patch!(
+    /// A plane of symmetry.
+    SymmetryPolyPatch,
+    "symmetry"
+);
+
+basic_patch!(
+    /// One side of an axisymmetric wedge.
+    WedgePolyPatch,
+    "wedge"
+);
+
+/// A non-coupled patch without special geometric treatment (OpenFOAM
+/// `patch`), also used for types this crate does not know.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenericPolyPatch {
+    name: String,
+    patch_type: String,
+    start: usize,
+    size: usize,
+}
+
+impl GenericPolyPatch {
+    /// Creates a patch of type `patch_type` covering faces
+    /// `start..start + size`.
+    pub fn new(
+        name: impl Into<String>,
+        patch_type: impl Into<String>,
+        start: usize,
+        size: usize,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            patch_type: patch_type.into(),
+            start,
+            size,
+        }
+    }
+}
+
+impl PolyPatch for GenericPolyPatch {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn patch_type(&self) -> &str {
+        &self.patch_type
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,482 @@
+use std::any::Any;
+use std::collections::HashMap;
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::patch_def::{PatchCoupling, PatchDef};
+use crate::patches::{CoupledPatch, PolyPatch};
+use crate::primitive_mesh::PrimitiveMesh;
+use crate::transform::Transform;
+
+/// One half of a periodic pair of patches within the same mesh.
+///
+/// Face `i` of this patch is coupled to face `i` of the neighbor patch;
+/// [`match_pair`](Self::match_pair) reorders the faces of a mesh so that
+/// this holds. The transform maps neighbor-side positions into this patch's
+/// frame; the neighbor patch is expected to hold the inverse transform.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CyclicPolyPatch {
+    name: String,
+    start: usize,
+    size: usize,
+    neighbor_patch: String,
+    transform: Transform,
+    face_cells: Vec<usize>,
+    neighbor_cell_centers: Vec<Vector>,
+}
+
+/// A mesh whose cyclic pair faces are stored in matched order, as returned
+/// by [`CyclicPolyPatch::match_pair`].
+pub struct CyclicMatch {
+    /// The mesh with the faces of the neighbor patch reordered.
+    pub mesh: PrimitiveMesh,
+    /// `face_map[new] == old`. Only faces of the neighbor patch move.
+    pub face_map: Vec<usize>,
+    /// The half on the first patch.
+    pub patch: CyclicPolyPatch,
+    /// The half on the neighbor patch.
+    pub neighbor: CyclicPolyPatch,
+}
+
+impl CyclicPolyPatch {
+    /// OpenFOAM type name of this patch.
+    pub const TYPE_NAME: &'static str = "cyclic";
+
+    /// Default face-matching tolerance of [`match_pair`](Self::match_pair),
+    /// relative to the face size.
+    pub const DEFAULT_MATCH_TOLERANCE: f64 = 1e-4;
+
+    /// Creates a cyclic patch covering faces `start..start + size`, coupled
+    /// to the patch named `neighbor_patch`.
+    ///
+    /// Face cells and neighbor cell centers are filled in when the patch is
+    /// attached to a [`PolyMesh`](crate::PolyMesh).
+    pub fn new(
+        name: impl Into<String>,
+        start: usize,
+        size: usize,
+        neighbor_patch: impl Into<String>,
+        transform: Transform,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            start,
+            size,
+            neighbor_patch: neighbor_patch.into(),
+            transform,
+            face_cells: Vec::new(),
+            neighbor_cell_centers: Vec::new(),
+        }
+    }
+
+    /// Creates both halves of a periodic pair by matching the faces of
+    /// `patch` and `neighbor` geometrically, and reorders the faces of
+    /// `neighbor` so that face `i` of each half is the partner of face `i`
+    /// of the other.
+    ///
+    /// `transform` maps `neighbor` positions into the frame of `patch`; the
+    /// neighbor half gets its inverse. Each face of `patch` is paired with
+    /// the `neighbor` face whose transformed center lies within
+    /// `tolerance × √|Sf|` of its own center and whose transformed area
+    /// vector is opposite to its own within the same relative tolerance.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `tolerance` is not positive and finite, the patches
+    /// differ in size, or a face of `patch` has no partner or shares its
+    /// partner with another face.
+    pub fn match_pair(
+        mesh: &PrimitiveMesh,
+        patch: &PatchDef,
+        neighbor: &PatchDef,
+        transform: Transform,
+        tolerance: f64,
+    ) -> Result<CyclicMatch, MeshError> {
+        if !(tolerance > 0.0 && tolerance.is_finite()) {
+            return Err(MeshError::InvalidMatchTolerance { tolerance });
+        }
+        if patch.size != neighbor.size {
+            return Err(MeshError::CyclicSizeMismatch {
+                patch: patch.name.clone(),
+                size: patch.size,
+                neighbor_size: neighbor.size,
+            });
+        }
+        let order = match_faces(mesh, patch, neighbor, &transform, tolerance)?;
+        let mut face_map: Vec<usize> = (0..mesh.n_faces()).collect();
+        for (i, &j) in order.iter().enumerate() {
+            face_map[neighbor.start + i] = neighbor.start + j;
+        }
+        let reordered = PrimitiveMesh::from_compact(
+            mesh.points().to_vec(),
+            face_map.iter().map(|&f| &mesh.faces()[f]).collect(),
+            face_map.iter().map(|&f| mesh.owner()[f]).collect(),
+            mesh.neighbor().to_vec(),
+        )
+        .expect("reordering boundary faces preserves validity");
+
+        Ok(CyclicMatch {
+            mesh: reordered,
+            face_map,
+            patch: Self::new(
+                patch.name.clone(),
+                patch.start,
+                patch.size,
+                neighbor.name.clone(),
+                transform.clone(),
+            ),
+            neighbor: Self::new(
+                neighbor.name.clone(),
+                neighbor.start,
+                neighbor.size,
+                patch.name.clone(),
+                transform.inverse(),
+            ),
+        })
+    }
+
+    /// Returns the name of the coupled patch.
+    pub fn neighbor_patch_name(&self) -> &str {
+        &self.neighbor_patch
+    }
+}
+
+/// Checks that every face of each cyclic patch in `patches` goes to the same
+/// part as its partner, `part(f)` being the part of face `f`. Pairs whose
+/// neighbor patch is missing or of another size are left to
+/// [`PolyMesh::new`](crate::PolyMesh::new) to reject.
+pub(crate) fn check_cyclic_pairs(
+    patches: &[PatchDef],
+    part: impl Fn(usize) -> usize,
+) -> Result<(), MeshError> {
+    for def in patches {
+        let Some(PatchCoupling::Cyclic { neighbor_patch, .. }) = &def.coupling else {
+            continue;
+        };
+        let Some(neighbor) = patches.iter().find(|p| &p.name == neighbor_patch) else {
+            continue;
+        };
+        if neighbor.size != def.size {
+            continue;
+        }
+        if let Some(i) = (0..def.size).find(|&i| part(def.start + i) != part(neighbor.start + i)) {
+            return Err(MeshError::SplitCyclicPair {
+                patch: def.name.clone(),
+                face: def.start + i,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns, for each face of `patch`, the matching face within `neighbor`.
+fn match_faces(
+    mesh: &PrimitiveMesh,
+    patch: &PatchDef,
+    neighbor: &PatchDef,
+    transform: &Transform,
+    tolerance: f64,
+) -> Result<Vec<usize>, MeshError> {
+    let centers = mesh.face_centers();
+    let areas = mesh.face_areas();
+    let radius: Vec<f64> = patch
+        .range()
+        .map(|f| tolerance * areas[f].mag().sqrt())
+        .collect();
+    // Bucket width no smaller than any search radius, so that a partner is
+    // always within the neighboring buckets
+    let width = radius.iter().copied().fold(f64::MIN_POSITIVE, f64::max);
+    let bucket = |p: Vector| p.as_array().map(|x| (x / width).floor() as i64);
+
+    let moved: Vec<(Vector, Vector)> = neighbor
+        .range()
+        .map(|f| {
+            (
+                transform.transform_position(centers[f]),
+                transform.transform_vector(areas[f]),
+            )
+        })
+        .collect();
+    let mut buckets: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    for (j, &(c, _)) in moved.iter().enumerate() {
+        buckets.entry(bucket(c)).or_default().push(j);
+    }
+
+    let mut order = Vec::with_capacity(patch.size);
+    let mut taken = vec![false; neighbor.size];
+    for (i, f) in patch.range().enumerate() {
+        let key = bucket(centers[f]);
+        let mut best: Option<(f64, usize)> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    // Keys saturate for points far out relative to the width
+                    let (Some(x), Some(y), Some(z)) = (
+                        key[0].checked_add(dx),
+                        key[1].checked_add(dy),
+                        key[2].checked_add(dz),
+                    ) else {
+                        continue;
+                    };
+                    let Some(js) = buckets.get(&[x, y, z]) else {
+                        continue;
+                    };
+                    for &j in js {
+                        let (c, a) = moved[j];
+                        let distance = (c - centers[f]).mag();
+                        let opposed = (a + areas[f]).mag() <= tolerance * areas[f].mag();
+                        if distance <= radius[i]
+                            && opposed
+                            && best.is_none_or(|(d, _)| distance < d)
+                        {
+                            best = Some((distance, j));
+                        }
+                    }
+                }
+            }
+        }
+        match best {
+            Some((_, j)) if !taken[j] => {
+                taken[j] = true;
+                order.push(j);
+            }
+            _ => {
+                return Err(MeshError::UnmatchedCyclicFace {
+                    patch: patch.name.clone(),
+                    face: f,
+                });
+            }
+        }
+    }
+    Ok(order)
+}
+
+impl PolyPatch for CyclicPolyPatch {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn patch_type(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    fn as_coupled(&self) -> Option<&dyn CoupledPatch> {
+        Some(self)
+    }
+
+    fn as_coupled_mut(&mut self) -> Option<&mut dyn CoupledPatch> {
+        Some(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn init_geometry(&mut self, mesh: &PrimitiveMesh) {
+        self.face_cells = mesh.owner()[self.range()].to_vec();
+    }
+
+    fn to_patch_def(&self) -> PatchDef {
+        PatchDef::new(&self.name, Self::TYPE_NAME, self.start, self.size).with_coupling(
+            PatchCoupling::Cyclic {
+                neighbor_patch: self.neighbor_patch.clone(),
+                transform: self.transform.clone(),
+            },
+        )
+    }
+}
+
+impl CoupledPatch for CyclicPolyPatch {
+    fn face_cells(&self) -> &[usize] {
+        &self.face_cells
+    }
+
+    fn neighbor_cell_centers(&self) -> &[Vector] {
+        &self.neighbor_cell_centers
+    }
+
+    fn set_neighbor_cell_centers(&mut self, centers: Vec<Vector>) {
+        self.neighbor_cell_centers = centers;
+    }
+
+    fn neighbor_rank(&self) -> Option<i32> {
+        None
+    }
+
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patches::patch_from_def;
+    use crate::{CartesianBox, PolyMesh};
+
+    /// A 3 x 3 x 1 box over the unit cube.
+    fn box_mesh() -> (PrimitiveMesh, Vec<PatchDef>) {
+        CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [3, 3, 1])
+            .build()
+            .unwrap()
+    }
+
+    /// Returns `mesh` with the faces of `def` stored in reverse order.
+    fn reverse_patch_faces(mesh: &PrimitiveMesh, def: &PatchDef) -> PrimitiveMesh {
+        let mut faces = mesh.faces().to_nested();
+        let mut owner = mesh.owner().to_vec();
+        faces[def.range()].reverse();
+        owner[def.range()].reverse();
+        PrimitiveMesh::new(
+            mesh.points().to_vec(),
+            faces,
+            owner,
+            mesh.neighbor().to_vec(),
+        )
+        .unwrap()
+    }
+
+    /// Attaches the matched pair replacing patches 0 and `other`, with the
+    /// remaining patches kept as they are.
+    fn attach(matched: CyclicMatch, defs: &[PatchDef], other: usize) -> PolyMesh {
+        let mut pair = [Some(matched.patch), Some(matched.neighbor)];
+        let patches = defs
+            .iter()
+            .enumerate()
+            .map(|(p, def)| match p {
+                0 => Box::new(pair[0].take().unwrap()) as Box<dyn PolyPatch>,
+                p if p == other => Box::new(pair[1].take().unwrap()),
+                _ => patch_from_def(def).unwrap(),
+            })
+            .collect();
+        PolyMesh::new(matched.mesh, patches).unwrap()
+    }
+
+    #[test]
+    fn translation_reorders_permuted_faces() {
+        let (mesh, defs) = box_mesh();
+        let mesh = reverse_patch_faces(&mesh, &defs[1]);
+        let shift = Transform::Translation {
+            separation: Vector::new(-1.0, 0.0, 0.0),
+        };
+        let matched = CyclicPolyPatch::match_pair(
+            &mesh,
+            &defs[0],
+            &defs[1],
+            shift,
+            CyclicPolyPatch::DEFAULT_MATCH_TOLERANCE,
+        )
+        .unwrap();
+        // The reversed faces are put back in matched order
+        let x_max = defs[1].start;
+        assert_eq!(
+            matched.face_map[x_max..x_max + 3],
+            [x_max + 2, x_max + 1, x_max]
+        );
+        assert!(
+            matched
+                .face_map
+                .iter()
+                .enumerate()
+                .all(|(new, &old)| defs[1].range().contains(&new) || new == old)
+        );
+        for (new, &old) in matched.face_map.iter().enumerate() {
+            assert_eq!(matched.mesh.faces()[new], mesh.faces()[old]);
+            assert_eq!(matched.mesh.owner()[new], mesh.owner()[old]);
+        }
+        assert_eq!(matched.neighbor.neighbor_patch_name(), "xMin");
+
+        let poly = attach(matched, &defs, 1);
+        let x_min = poly.patches()[0].as_coupled().unwrap();
+        for (i, &c) in x_min.face_cells().iter().enumerate() {
+            // The partner cell appears one cell width to the left, at the
+            // same y
+            let expected = poly.cell_centers()[c] - Vector::new(1.0 / 3.0, 0.0, 0.0);
+            assert!((x_min.neighbor_cell_centers()[i] - expected).mag() < 1e-12);
+        }
+
+        // The plain descriptions rebuild the same coupling
+        let copy = PrimitiveMesh::new(
+            poly.points().to_vec(),
+            poly.faces().to_nested(),
+            poly.owner().to_vec(),
+            poly.neighbor().to_vec(),
+        )
+        .unwrap();
+        let rebuilt = PolyMesh::from_patch_defs(copy, &poly.patch_defs()).unwrap();
+        let coupled = rebuilt.patches()[0].as_coupled().unwrap();
+        assert_eq!(
+            coupled.neighbor_cell_centers(),
+            x_min.neighbor_cell_centers()
+        );
+    }
+
+    #[test]
+    fn rotation_matches_sector_sides() {
+        // The x = 0 and y = 0 sides of the box are a quarter turn apart
+        let (mesh, defs) = box_mesh();
+        let turn = Transform::rotation(
+            Vector::new(0.0, 0.0, 1.0),
+            std::f64::consts::FRAC_PI_2,
+            Vector::zero(),
+        );
+        let matched = CyclicPolyPatch::match_pair(&mesh, &defs[0], &defs[2], turn, 1e-6).unwrap();
+        let poly = attach(matched, &defs, 2);
+        let x_min = poly.patches()[0].as_coupled().unwrap();
+        for (i, &c) in x_min.face_cells().iter().enumerate() {
+            let center = poly.cell_centers()[c];
+            let expected = Vector::new(-center.x(), center.y(), center.z());
+            assert!((x_min.neighbor_cell_centers()[i] - expected).mag() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn mismatched_sides_are_rejected() {
+        let (mesh, defs) = box_mesh();
+        let short = Transform::Translation {
+            separation: Vector::new(-0.9, 0.0, 0.0),
+        };
+        let result = CyclicPolyPatch::match_pair(&mesh, &defs[0], &defs[1], short, 1e-4);
+        assert!(matches!(
+            result,
+            Err(MeshError::UnmatchedCyclicFace { ref patch, .. }) if patch == "xMin"
+        ));
+
+        let (mesh, defs) = CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [3, 1, 1])
+            .build()
+            .unwrap();
+        let mut bottom = defs[4].clone();
+        bottom.size = 2;
+        let result =
+            CyclicPolyPatch::match_pair(&mesh, &bottom, &defs[5], Transform::Identity, 1e-4);
+        assert!(matches!(result, Err(MeshError::CyclicSizeMismatch { .. })));
+    }
+
+    #[test]
+    fn tolerance_must_be_positive_and_finite() {
+        let (mesh, defs) = CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [1, 1, 1])
+            .build()
+            .unwrap();
+        let shift = Transform::Translation {
+            separation: Vector::new(-1.0, 0.0, 0.0),
+        };
+        for tolerance in [0.0, -1e-4, f64::NAN, f64::INFINITY] {
+            let result =
+                CyclicPolyPatch::match_pair(&mesh, &defs[0], &defs[1], shift.clone(), tolerance);
+            assert!(matches!(
+                result,
+                Err(MeshError::InvalidMatchTolerance { .. })
+            ));
+        }
+        assert!(CyclicPolyPatch::match_pair(&mesh, &defs[0], &defs[1], shift, 1e-4).is_ok());
+    }
+}
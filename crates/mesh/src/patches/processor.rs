@@ -0,0 +1,194 @@
+use std::any::Any;
+
+use dugong_types::tensor::Vector;
+
+use crate::patch_def::{PatchCoupling, PatchDef};
+use crate::patches::{CoupledPatch, PolyPatch};
+use crate::primitive_mesh::PrimitiveMesh;
+use crate::transform::Transform;
+
+/// Boundary between the sub-meshes of two processes.
+///
+/// Face `i` of this patch is the same face as face `i` of the matching
+/// patch on `neighbor_rank`, oriented outward from the local cell. The
+/// neighbor cell centers must be supplied from the other side, either by a
+/// parallel exchange or, after a serial decomposition, directly from the
+/// global mesh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessorPolyPatch {
+    name: String,
+    start: usize,
+    size: usize,
+    my_rank: i32,
+    neighbor_rank: i32,
+    transform: Transform,
+    face_cells: Vec<usize>,
+    neighbor_cell_centers: Vec<Vector>,
+}
+
+impl ProcessorPolyPatch {
+    /// OpenFOAM type name of this patch.
+    pub const TYPE_NAME: &'static str = "processor";
+
+    /// Creates a processor patch covering faces `start..start + size` of
+    /// the sub-mesh of `my_rank`, coupled to `neighbor_rank`.
+    pub fn new(
+        name: impl Into<String>,
+        start: usize,
+        size: usize,
+        my_rank: i32,
+        neighbor_rank: i32,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            start,
+            size,
+            my_rank,
+            neighbor_rank,
+            transform: Transform::Identity,
+            face_cells: Vec::new(),
+            neighbor_cell_centers: Vec::new(),
+        }
+    }
+
+    /// Returns the OpenFOAM name of the patch from `my_rank` to
+    /// `neighbor_rank` (e.g. `procBoundary0to1`).
+    pub fn default_name(my_rank: i32, neighbor_rank: i32) -> String {
+        format!("procBoundary{my_rank}to{neighbor_rank}")
+    }
+
+    /// Returns the rank owning this side of the boundary.
+    pub fn my_rank(&self) -> i32 {
+        self.my_rank
+    }
+
+    /// Returns `true` on the lower-ranked side of the boundary.
+    pub fn is_owner(&self) -> bool {
+        self.my_rank < self.neighbor_rank
+    }
+}
+
+impl PolyPatch for ProcessorPolyPatch {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn patch_type(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    fn as_coupled(&self) -> Option<&dyn CoupledPatch> {
+        Some(self)
+    }
+
+    fn as_coupled_mut(&mut self) -> Option<&mut dyn CoupledPatch> {
+        Some(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn init_geometry(&mut self, mesh: &PrimitiveMesh) {
+        self.face_cells = mesh.owner()[self.range()].to_vec();
+    }
+
+    fn to_patch_def(&self) -> PatchDef {
+        PatchDef::new(&self.name, Self::TYPE_NAME, self.start, self.size).with_coupling(
+            PatchCoupling::Processor {
+                my_rank: self.my_rank,
+                neighbor_rank: self.neighbor_rank,
+            },
+        )
+    }
+}
+
+impl CoupledPatch for ProcessorPolyPatch {
+    fn face_cells(&self) -> &[usize] {
+        &self.face_cells
+    }
+
+    fn neighbor_cell_centers(&self) -> &[Vector] {
+        &self.neighbor_cell_centers
+    }
+
+    fn set_neighbor_cell_centers(&mut self, centers: Vec<Vector>) {
+        self.neighbor_cell_centers = centers;
+    }
+
+    fn neighbor_rank(&self) -> Option<i32> {
+        Some(self.neighbor_rank)
+    }
+
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patches::patch_from_def;
+    use crate::{CartesianBox, PolyMesh};
+
+    #[test]
+    fn processor_patch_takes_its_cells_from_the_mesh() {
+        let (mesh, defs) = CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [2, 2, 1])
+            .build()
+            .unwrap();
+        let x_max = &defs[1];
+        let name = ProcessorPolyPatch::default_name(0, 1);
+        assert_eq!(name, "procBoundary0to1");
+        // Set before attaching, as after a serial decomposition
+        let centers = vec![Vector::new(1.25, 0.25, 0.5), Vector::new(1.25, 0.75, 0.5)];
+        let patches = defs
+            .iter()
+            .map(|def| match def.name.as_str() {
+                "xMax" => {
+                    let mut patch = ProcessorPolyPatch::new(&name, def.start, def.size, 0, 1);
+                    patch.set_neighbor_cell_centers(centers.clone());
+                    Box::new(patch) as Box<dyn PolyPatch>
+                }
+                _ => patch_from_def(def).unwrap(),
+            })
+            .collect();
+        let poly = PolyMesh::new(mesh, patches).unwrap();
+
+        let patch = poly.patches()[1]
+            .as_any()
+            .downcast_ref::<ProcessorPolyPatch>()
+            .unwrap();
+        assert_eq!(patch.patch_type(), "processor");
+        assert_eq!(
+            patch.to_patch_def().coupling,
+            Some(PatchCoupling::Processor {
+                my_rank: 0,
+                neighbor_rank: 1
+            })
+        );
+        assert_eq!(patch.my_rank(), 0);
+        assert_eq!(patch.neighbor_rank(), Some(1));
+        assert!(patch.is_owner());
+        assert_eq!(patch.transform(), &Transform::Identity);
+        assert_eq!(patch.face_cells(), &poly.owner()[x_max.range()]);
+        assert_eq!(patch.face_cells(), [1, 3]);
+        assert_eq!(patch.neighbor_cell_centers(), centers);
+    }
+
+    #[test]
+    fn higher_rank_side_is_not_owner() {
+        let patch = ProcessorPolyPatch::new("procBoundary3to1", 0, 0, 3, 1);
+        assert!(!patch.is_owner());
+        assert_eq!(patch.neighbor_rank(), Some(1));
+        assert!(patch.face_cells().is_empty());
+        assert!(patch.neighbor_cell_centers().is_empty());
+    }
+}
@@ -0,0 +1,461 @@
+use std::collections::HashSet;
+use std::ops::Deref;
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::patch_def::PatchDef;
+use crate::patches::{CoupledPatch, CyclicPolyPatch, PolyPatch, patch_from_def};
+use crate::primitive_mesh::PrimitiveMesh;
+use crate::zones::{FaceZone, Zone};
+
+/// A [`PrimitiveMesh`] with typed boundary patches and zones.
+///
+/// The patches partition the boundary faces: sorted by `start`, they are
+/// contiguous and cover exactly `n_internal_faces()..n_faces()`. Patch and
+/// zone names are unique within their kind.
+///
+/// `PolyMesh` dereferences to its [`PrimitiveMesh`], so all topology and
+/// geometry accessors are available directly.
+pub struct PolyMesh {
+    primitive: PrimitiveMesh,
+    patches: Vec<Box<dyn PolyPatch>>,
+    cell_zones: Vec<Zone>,
+    face_zones: Vec<FaceZone>,
+    point_zones: Vec<Zone>,
+    old_points: Option<Vec<Vector>>,
+}
+
+impl PolyMesh {
+    /// Attaches `patches` to `primitive` after validating the boundary
+    /// partition.
+    ///
+    /// Patches must be given in face order. Each patch's
+    /// [`init_geometry`](PolyPatch::init_geometry) hook is called, and cyclic
+    /// patches receive the transformed cell centers of their neighbor patch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if:
+    /// - A patch name is repeated
+    /// - A patch does not start where the previous one ended (the first at
+    ///   `n_internal_faces()`)
+    /// - The patches do not end at `n_faces()`
+    /// - A cyclic patch names a neighbor that is missing, not cyclic, or of a
+    ///   different size
+    pub fn new(
+        primitive: PrimitiveMesh,
+        mut patches: Vec<Box<dyn PolyPatch>>,
+    ) -> Result<Self, MeshError> {
+        let mut names = HashSet::new();
+        let mut expected = primitive.n_internal_faces();
+        for patch in &patches {
+            if !names.insert(patch.name()) {
+                return Err(MeshError::DuplicatePatchName {
+                    name: patch.name().to_string(),
+                });
+            }
+            if patch.start() != expected {
+                return Err(MeshError::PatchStartMismatch {
+                    patch: patch.name().to_string(),
+                    start: patch.start(),
+                    expected,
+                });
+            }
+            expected += patch.size();
+        }
+        if expected != primitive.n_faces() {
+            return Err(MeshError::PatchCoverageMismatch {
+                covered: expected,
+                n_faces: primitive.n_faces(),
+            });
+        }
+
+        for patch in &mut patches {
+            patch.init_geometry(&primitive);
+        }
+
+        let mut mesh = Self {
+            primitive,
+            patches,
+            cell_zones: Vec::new(),
+            face_zones: Vec::new(),
+            point_zones: Vec::new(),
+            old_points: None,
+        };
+        mesh.update_cyclic_neighbor_centers()?;
+        Ok(mesh)
+    }
+
+    /// Builds typed patches from plain patch descriptions via
+    /// [`patch_from_def`] and attaches them with [`PolyMesh::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a description has a coupled type without coupling
+    /// data or the patches fail [`PolyMesh::new`] validation.
+    pub fn from_patch_defs(primitive: PrimitiveMesh, defs: &[PatchDef]) -> Result<Self, MeshError> {
+        let patches = defs
+            .iter()
+            .map(patch_from_def)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(primitive, patches)
+    }
+
+    /// Returns the underlying primitive mesh.
+    pub fn primitive(&self) -> &PrimitiveMesh {
+        &self.primitive
+    }
+
+    /// Returns all patches in face order.
+    pub fn patches(&self) -> &[Box<dyn PolyPatch>] {
+        &self.patches
+    }
+
+    /// Returns the plain descriptions of all patches in face order.
+    pub fn patch_defs(&self) -> Vec<PatchDef> {
+        self.patches.iter().map(|p| p.to_patch_def()).collect()
+    }
+
+    /// Returns the index of the patch named `name`.
+    pub fn find_patch(&self, name: &str) -> Option<usize> {
+        self.patches.iter().position(|p| p.name() == name)
+    }
+
+    /// Returns the patch named `name`.
+    pub fn patch_by_name(&self, name: &str) -> Option<&dyn PolyPatch> {
+        self.find_patch(name).map(|i| self.patches[i].as_ref())
+    }
+
+    /// Returns the index of the patch containing `face`, or `None` for
+    /// internal or out-of-range faces.
+    pub fn which_patch(&self, face: usize) -> Option<usize> {
+        if face < self.primitive.n_internal_faces() {
+            return None;
+        }
+        // Patches are contiguous and sorted, so the first patch ending past
+        // `face` contains it
+        let i = self
+            .patches
+            .partition_point(|p| p.start() + p.size() <= face);
+        (i < self.patches.len()).then_some(i)
+    }
+
+    /// Returns all cell zones.
+    pub fn cell_zones(&self) -> &[Zone] {
+        &self.cell_zones
+    }
+
+    /// Returns all face zones.
+    pub fn face_zones(&self) -> &[FaceZone] {
+        &self.face_zones
+    }
+
+    /// Returns all point zones.
+    pub fn point_zones(&self) -> &[Zone] {
+        &self.point_zones
+    }
+
+    /// Returns the cell zone named `name`.
+    pub fn cell_zone(&self, name: &str) -> Option<&Zone> {
+        self.cell_zones.iter().find(|z| z.name == name)
+    }
+
+    /// Returns the face zone named `name`.
+    pub fn face_zone(&self, name: &str) -> Option<&FaceZone> {
+        self.face_zones.iter().find(|z| z.name == name)
+    }
+
+    /// Returns the point zone named `name`.
+    pub fn point_zone(&self, name: &str) -> Option<&Zone> {
+        self.point_zones.iter().find(|z| z.name == name)
+    }
+
+    /// Adds a cell zone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the name is taken or an index is `>= n_cells()`.
+    pub fn add_cell_zone(&mut self, zone: Zone) -> Result<(), MeshError> {
+        let taken = self.cell_zone(&zone.name).is_some();
+        check_zone(&zone.name, &zone.indices, taken, self.primitive.n_cells())?;
+        self.cell_zones.push(zone);
+        Ok(())
+    }
+
+    /// Adds a face zone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the name is taken or an index is `>= n_faces()`.
+    pub fn add_face_zone(&mut self, zone: FaceZone) -> Result<(), MeshError> {
+        let taken = self.face_zone(&zone.name).is_some();
+        check_zone(&zone.name, &zone.indices, taken, self.primitive.n_faces())?;
+        self.face_zones.push(zone);
+        Ok(())
+    }
+
+    /// Adds a point zone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the name is taken or an index is `>= n_points()`.
+    pub fn add_point_zone(&mut self, zone: Zone) -> Result<(), MeshError> {
+        let taken = self.point_zone(&zone.name).is_some();
+        check_zone(&zone.name, &zone.indices, taken, self.primitive.n_points())?;
+        self.point_zones.push(zone);
+        Ok(())
+    }
+
+    /// Returns the points before the last [`move_points`](Self::move_points),
+    /// or `None` if the mesh has not moved.
+    pub fn old_points(&self) -> Option<&[Vector]> {
+        self.old_points.as_deref()
+    }
+
+    /// Moves the points to `new_points`, keeping topology, patches and zones.
+    ///
+    /// The previous points are kept as [`old_points`](Self::old_points), the
+    /// patches are re-initialized on the moved geometry and their
+    /// [`move_points`](PolyPatch::move_points) hook is called. Returns the
+    /// volume swept by each face (see [`PrimitiveMesh::swept_volumes`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `new_points` does not have `n_points()` entries.
+    pub fn move_points(&mut self, new_points: Vec<Vector>) -> Result<Vec<f64>, MeshError> {
+        let moved = self.primitive.with_points(new_points)?;
+        let old = std::mem::replace(&mut self.primitive, moved);
+        let swept = self.primitive.swept_volumes(old.points())?;
+        for patch in &mut self.patches {
+            patch.init_geometry(&self.primitive);
+            patch.move_points(self.primitive.points());
+        }
+        self.update_cyclic_neighbor_centers()?;
+        self.old_points = Some(old.points().to_vec());
+        Ok(swept)
+    }
+
+    /// Sets the neighbor cell centers of every cyclic patch from the owner
+    /// cells of its neighbor patch, mapped through the patch transform.
+    fn update_cyclic_neighbor_centers(&mut self) -> Result<(), MeshError> {
+        let owner = self.primitive.owner();
+        let cell_centers = self.primitive.cell_centers();
+        let mut updates: Vec<(usize, Vec<Vector>)> = Vec::new();
+        for (i, patch) in self.patches.iter().enumerate() {
+            let Some(cyclic) = patch.as_any().downcast_ref::<CyclicPolyPatch>() else {
+                continue;
+            };
+            let neighbor = self
+                .patches
+                .iter()
+                .find(|p| p.name() == cyclic.neighbor_patch_name())
+                .filter(|p| p.as_any().is::<CyclicPolyPatch>())
+                .ok_or_else(|| MeshError::UnknownNeighborPatch {
+                    patch: cyclic.name().to_string(),
+                    neighbor: cyclic.neighbor_patch_name().to_string(),
+                })?;
+            if neighbor.size() != cyclic.size() {
+                return Err(MeshError::CyclicSizeMismatch {
+                    patch: cyclic.name().to_string(),
+                    size: cyclic.size(),
+                    neighbor_size: neighbor.size(),
+                });
+            }
+            let transform = cyclic.transform();
+            let centers = (0..cyclic.size())
+                .map(|i| {
+                    let c = owner[neighbor.start() + i];
+                    transform.transform_position(cell_centers[c])
+                })
+                .collect();
+            updates.push((i, centers));
+        }
+        for (i, centers) in updates {
+            if let Some(coupled) = self.patches[i].as_coupled_mut() {
+                coupled.set_neighbor_cell_centers(centers);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deref for PolyMesh {
+    type Target = PrimitiveMesh;
+
+    fn deref(&self) -> &PrimitiveMesh {
+        &self.primitive
+    }
+}
+
+/// Validates a zone's name uniqueness and index range.
+fn check_zone(name: &str, indices: &[usize], taken: bool, len: usize) -> Result<(), MeshError> {
+    if taken {
+        return Err(MeshError::DuplicateZoneName {
+            name: name.to_string(),
+        });
+    }
+    if let Some(&index) = indices.iter().find(|&&i| i >= len) {
+        return Err(MeshError::ZoneIndexOutOfRange {
+            zone: name.to_string(),
+            index,
+            len,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CartesianBox;
+    use crate::patches::WallPolyPatch;
+    use crate::transform::Transform;
+
+    /// A 2 x 1 x 1 box over the unit cube with its six patches.
+    fn box_mesh() -> (PrimitiveMesh, Vec<PatchDef>) {
+        CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [2, 1, 1])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn from_patch_defs_keeps_names_and_ranges() {
+        let (primitive, defs) = box_mesh();
+        let mesh = PolyMesh::from_patch_defs(primitive, &defs).unwrap();
+        assert_eq!(mesh.patches().len(), 6);
+        assert_eq!(mesh.patch_defs(), defs);
+        assert_eq!(mesh.find_patch("yMin"), Some(2));
+        assert_eq!(mesh.patch_by_name("xMax").unwrap().size(), 1);
+        // Delegation to the primitive mesh
+        assert_eq!(mesh.n_cells(), 2);
+        assert_eq!(mesh.primitive().n_internal_faces(), 1);
+    }
+
+    #[test]
+    fn which_patch_finds_owning_patch() {
+        let (primitive, defs) = box_mesh();
+        let mesh = PolyMesh::from_patch_defs(primitive, &defs).unwrap();
+        assert_eq!(mesh.which_patch(0), None);
+        for (i, def) in defs.iter().enumerate() {
+            for f in def.range() {
+                assert_eq!(mesh.which_patch(f), Some(i), "face {f}");
+            }
+        }
+        assert_eq!(mesh.which_patch(mesh.n_faces()), None);
+    }
+
+    #[test]
+    fn rejects_gap_between_patches() {
+        let (primitive, mut defs) = box_mesh();
+        defs[1].start += 1;
+        let result = PolyMesh::from_patch_defs(primitive, &defs);
+        assert!(matches!(
+            result,
+            Err(MeshError::PatchStartMismatch { ref patch, .. }) if patch == "xMax"
+        ));
+    }
+
+    #[test]
+    fn rejects_incomplete_coverage_and_duplicate_names() {
+        let (primitive, mut defs) = box_mesh();
+        defs.pop();
+        let result = PolyMesh::from_patch_defs(primitive, &defs);
+        assert!(matches!(
+            result,
+            Err(MeshError::PatchCoverageMismatch { .. })
+        ));
+
+        let (primitive, mut defs) = box_mesh();
+        defs[1].name = "xMin".into();
+        let result = PolyMesh::from_patch_defs(primitive, &defs);
+        assert!(matches!(result, Err(MeshError::DuplicatePatchName { .. })));
+    }
+
+    fn periodic_x_patches(defs: &[PatchDef]) -> Vec<Box<dyn PolyPatch>> {
+        let separation = Vector::new(1.0, 0.0, 0.0);
+        let mut patches: Vec<Box<dyn PolyPatch>> = vec![
+            Box::new(CyclicPolyPatch::new(
+                "xMin",
+                defs[0].start,
+                defs[0].size,
+                "xMax",
+                Transform::Translation {
+                    separation: -separation,
+                },
+            )),
+            Box::new(CyclicPolyPatch::new(
+                "xMax",
+                defs[1].start,
+                defs[1].size,
+                "xMin",
+                Transform::Translation { separation },
+            )),
+        ];
+        for def in &defs[2..] {
+            patches.push(Box::new(WallPolyPatch::new(
+                def.name.clone(),
+                def.start,
+                def.size,
+            )));
+        }
+        patches
+    }
+
+    #[test]
+    fn cyclic_patches_get_face_cells_and_neighbor_centers() {
+        let (primitive, defs) = box_mesh();
+        let mesh = PolyMesh::new(primitive, periodic_x_patches(&defs)).unwrap();
+        let x_min = mesh.patches()[0].as_coupled().unwrap();
+        assert_eq!(x_min.face_cells(), &[0]);
+        assert_eq!(x_min.neighbor_rank(), None);
+        // Cell 1 (center x = 0.75) seen across the periodic boundary
+        let c = x_min.neighbor_cell_centers()[0];
+        assert!((c - Vector::new(-0.25, 0.5, 0.5)).mag() < 1e-12);
+        let x_max = mesh.patches()[1].as_coupled().unwrap();
+        assert_eq!(x_max.face_cells(), &[1]);
+        let c = x_max.neighbor_cell_centers()[0];
+        assert!((c - Vector::new(1.25, 0.5, 0.5)).mag() < 1e-12);
+        assert_eq!(mesh.patches()[2].patch_type(), "wall");
+    }
+
+    #[test]
+    fn cyclic_with_missing_neighbor_is_rejected() {
+        let (primitive, defs) = box_mesh();
+        let mut patches = periodic_x_patches(&defs);
+        patches[1] = Box::new(WallPolyPatch::new("xMax", defs[1].start, defs[1].size));
+        let result = PolyMesh::new(primitive, patches);
+        assert!(matches!(
+            result,
+            Err(MeshError::UnknownNeighborPatch { ref neighbor, .. }) if neighbor == "xMax"
+        ));
+    }
+
+    #[test]
+    fn zones_are_validated_and_found_by_name() {
+        let (primitive, defs) = box_mesh();
+        let mut mesh = PolyMesh::from_patch_defs(primitive, &defs).unwrap();
+        mesh.add_cell_zone(Zone::new("left", vec![0])).unwrap();
+        mesh.add_face_zone(FaceZone::unflipped("mid", vec![0]))
+            .unwrap();
+        mesh.add_point_zone(Zone::new("corner", vec![0, 11]))
+            .unwrap();
+        assert_eq!(mesh.cell_zone("left").unwrap().indices, vec![0]);
+        assert_eq!(mesh.face_zones().len(), 1);
+        assert_eq!(mesh.point_zone("corner").unwrap().len(), 2);
+        assert!(mesh.cell_zone("right").is_none());
+
+        let dup = mesh.add_cell_zone(Zone::new("left", vec![1]));
+        assert!(matches!(dup, Err(MeshError::DuplicateZoneName { .. })));
+        let bad = mesh.add_cell_zone(Zone::new("right", vec![2]));
+        assert!(matches!(
+            bad,
+            Err(MeshError::ZoneIndexOutOfRange {
+                index: 2,
+                len: 2,
+                ..
+            })
+        ));
+        assert_eq!(mesh.cell_zones().len(), 1);
+    }
+}
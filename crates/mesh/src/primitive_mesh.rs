@@ -2,15 +2,21 @@ use std::sync::OnceLock;
 
 use dugong_types::tensor::Vector;
 
+use crate::compact_list::CompactList;
 use crate::error::MeshError;
+use crate::generation::CartesianBox;
 use crate::geometry;
+use crate::quality;
+use crate::search::{self, BoxTree};
 
 /// The topology engine for polyhedral meshes.
 ///
 /// Stores the minimal set of mesh data — point coordinates, face-vertex
 /// connectivity, and owner/neighbor cell indices — and lazily derives
-/// geometry (cell volumes, cell centers, face area vectors, face centers)
-/// and connectivity (cell-cells, cell-faces, cell-points) on first access.
+/// geometry (cell volumes, cell centers, face area vectors, face centers),
+/// connectivity (cell, point, face and edge adjacency), quality metrics
+/// (non-orthogonality, skewness, aspect ratio, ...) and the point-location
+/// index on first access.
 ///
 /// # Mesh topology conventions (OpenFOAM-compatible)
 ///
@@ -28,7 +34,7 @@ use crate::geometry;
 /// the struct is `Send + Sync` without `unsafe`.
 pub struct PrimitiveMesh {
     points: Vec<Vector>,
-    faces: Vec<Vec<usize>>,
+    faces: CompactList,
     owner: Vec<usize>,
     neighbor: Vec<usize>,
     n_cells: usize,
@@ -38,9 +44,23 @@ pub struct PrimitiveMesh {
     face_centers: OnceLock<Vec<Vector>>,
     face_areas: OnceLock<Vec<Vector>>,
 
-    cell_cells: OnceLock<Vec<Vec<usize>>>,
-    cell_faces: OnceLock<Vec<Vec<usize>>>,
-    cell_points: OnceLock<Vec<Vec<usize>>>,
+    cell_cells: OnceLock<CompactList>,
+    cell_faces: OnceLock<CompactList>,
+    cell_points: OnceLock<CompactList>,
+    cell_edges: OnceLock<CompactList>,
+    point_cells: OnceLock<CompactList>,
+    point_faces: OnceLock<CompactList>,
+    point_points: OnceLock<CompactList>,
+    edges: OnceLock<Vec<[usize; 2]>>,
+    face_edges: OnceLock<CompactList>,
+
+    face_non_orthogonality: OnceLock<Vec<f64>>,
+    face_skewness: OnceLock<Vec<f64>>,
+    cell_aspect_ratio: OnceLock<Vec<f64>>,
+    cell_determinant: OnceLock<Vec<f64>>,
+    cell_volume_ratio: OnceLock<Vec<f64>>,
+
+    cell_tree: OnceLock<BoxTree>,
 }
 
 impl PrimitiveMesh {
@@ -70,6 +90,23 @@ impl PrimitiveMesh {
         faces: Vec<Vec<usize>>,
         owner: Vec<usize>,
         neighbor: Vec<usize>,
+    ) -> Result<Self, MeshError> {
+        Self::from_compact(points, CompactList::from(faces), owner, neighbor)
+    }
+
+    /// Constructs a new `PrimitiveMesh` from faces already in compact form.
+    ///
+    /// Same as [`new`](Self::new), without the intermediate nested face
+    /// lists.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` under the same conditions as [`new`](Self::new).
+    pub fn from_compact(
+        points: Vec<Vector>,
+        faces: CompactList,
+        owner: Vec<usize>,
+        neighbor: Vec<usize>,
     ) -> Result<Self, MeshError> {
         // owner length check
         if owner.len() != faces.len() {
@@ -120,9 +157,37 @@ impl PrimitiveMesh {
             cell_cells: OnceLock::new(),
             cell_faces: OnceLock::new(),
             cell_points: OnceLock::new(),
+            cell_edges: OnceLock::new(),
+            point_cells: OnceLock::new(),
+            point_faces: OnceLock::new(),
+            point_points: OnceLock::new(),
+            edges: OnceLock::new(),
+            face_edges: OnceLock::new(),
+            face_non_orthogonality: OnceLock::new(),
+            face_skewness: OnceLock::new(),
+            cell_aspect_ratio: OnceLock::new(),
+            cell_determinant: OnceLock::new(),
+            cell_volume_ratio: OnceLock::new(),
+            cell_tree: OnceLock::new(),
         })
     }
 
+    /// Creates a uniform `nx × ny × nz` hexahedral mesh of the unit cube
+    /// `[0, 1]³`.
+    ///
+    /// This is a shorthand for [`CartesianBox`](crate::CartesianBox) with the
+    /// patch information discarded; see there for the cell, point and face
+    /// ordering.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if any of `nx`, `ny`, `nz` is zero.
+    pub fn unit_cube(nx: usize, ny: usize, nz: usize) -> Result<Self, MeshError> {
+        let (mesh, _) =
+            CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [nx, ny, nz]).build()?;
+        Ok(mesh)
+    }
+
     // Basic accessors
 
     /// Returns the vertex coordinates.
@@ -131,9 +196,10 @@ impl PrimitiveMesh {
     }
 
     /// Returns the face definitions. Each face is a list of point indices
-    /// forming a polygon. Internal faces occupy `faces[0..n_internal_faces()]`,
-    /// boundary faces occupy the remainder.
-    pub fn faces(&self) -> &[Vec<usize>] {
+    /// forming a polygon, stored compactly and returned as a slice. Internal
+    /// faces occupy `faces[0..n_internal_faces()]`, boundary faces occupy the
+    /// remainder.
+    pub fn faces(&self) -> &CompactList {
         &self.faces
     }
 
@@ -188,13 +254,19 @@ impl PrimitiveMesh {
     /// `points` bounds by `new()`.
     fn ensure_face_geometry(&self) {
         self.face_centers.get_or_init(|| {
-            let mut centers = Vec::with_capacity(self.faces.len());
-            let mut areas = Vec::with_capacity(self.faces.len());
-            for f in &self.faces {
-                let (fc, fa) = geometry::compute_face_geometry(&self.points, f);
-                centers.push(fc);
-                areas.push(fa);
-            }
+            #[cfg(feature = "parallel")]
+            let (centers, areas) = geometry::compute_face_geometry_par(&self.points, &self.faces);
+            #[cfg(not(feature = "parallel"))]
+            let (centers, areas) = {
+                let mut centers = Vec::with_capacity(self.faces.len());
+                let mut areas = Vec::with_capacity(self.faces.len());
+                for f in &self.faces {
+                    let (fc, fa) = geometry::compute_face_geometry(&self.points, f);
+                    centers.push(fc);
+                    areas.push(fa);
+                }
+                (centers, areas)
+            };
             let _ = self.face_areas.set(areas);
             centers
         });
@@ -230,6 +302,14 @@ impl PrimitiveMesh {
     /// - All `neighbor` elements are less than `n_cells`.
     fn ensure_cell_geometry(&self) {
         self.cell_volumes.get_or_init(|| {
+            #[cfg(feature = "parallel")]
+            let (volumes, centers) = geometry::compute_cell_geometry_par(
+                &self.points,
+                &self.faces,
+                &self.owner,
+                self.ensure_cell_faces(),
+            );
+            #[cfg(not(feature = "parallel"))]
             let (volumes, centers) = geometry::compute_cell_geometry(
                 &self.points,
                 &self.faces,
@@ -271,7 +351,7 @@ impl PrimitiveMesh {
     /// Validated by `new()`:
     /// - All `owner` elements are less than `n_cells`.
     /// - All `neighbor` elements are less than `n_cells`.
-    fn ensure_cell_faces(&self) -> &[Vec<usize>] {
+    fn ensure_cell_faces(&self) -> &CompactList {
         self.cell_faces
             .get_or_init(|| geometry::compute_cell_faces(&self.owner, &self.neighbor, self.n_cells))
     }
@@ -281,8 +361,8 @@ impl PrimitiveMesh {
     ///
     /// `cell_faces()[c]` contains the indices (into `faces()`) of all faces
     /// that border cell `c`, including both internal and boundary faces.
-    /// The returned slice has length `n_cells()`.
-    pub fn cell_faces(&self) -> &[Vec<usize>] {
+    /// The returned list has length `n_cells()`.
+    pub fn cell_faces(&self) -> &CompactList {
         self.ensure_cell_faces()
     }
 
@@ -291,14 +371,14 @@ impl PrimitiveMesh {
     ///
     /// `cell_cells()[c]` contains the indices of all cells that share an
     /// internal face with cell `c`. Boundary faces do not contribute neighbors.
-    /// The returned slice has length `n_cells()`.
+    /// The returned list has length `n_cells()`.
     ///
     /// # Safety (logical preconditions)
     ///
     /// Validated by `new()`:
     /// - All `owner` elements are less than `n_cells`.
     /// - All `neighbor` elements are less than `n_cells`.
-    pub fn cell_cells(&self) -> &[Vec<usize>] {
+    pub fn cell_cells(&self) -> &CompactList {
         self.cell_cells.get_or_init(|| {
             let cf = self.ensure_cell_faces();
             geometry::compute_cell_cells(cf, &self.owner, &self.neighbor, self.n_cells)
@@ -310,7 +390,7 @@ impl PrimitiveMesh {
     ///
     /// `cell_points()[c]` contains all vertex indices that form cell `c`,
     /// collected from its adjacent faces with duplicates removed and sorted
-    /// in ascending order. The returned slice has length `n_cells()`.
+    /// in ascending order. The returned list has length `n_cells()`.
     ///
     /// # Safety (logical preconditions)
     ///
@@ -319,12 +399,159 @@ impl PrimitiveMesh {
     /// - All `neighbor` elements are less than `n_cells` (for `ensure_cell_faces`).
     /// - Face indices returned by `cell_faces` are derived from `owner`/`neighbor`
     ///   and therefore within `faces` bounds.
-    pub fn cell_points(&self) -> &[Vec<usize>] {
+    pub fn cell_points(&self) -> &CompactList {
         self.cell_points.get_or_init(|| {
             let cf = self.ensure_cell_faces();
             geometry::compute_cell_points(cf, &self.faces)
         })
     }
+
+    /// Returns the cell indices sharing each point. Lazily computed on first
+    /// access.
+    ///
+    /// `point_cells()[p]` lists, in ascending order, every cell that has
+    /// point `p` as a vertex. The returned list has length `n_points()`.
+    pub fn point_cells(&self) -> &CompactList {
+        self.point_cells
+            .get_or_init(|| geometry::compute_point_lists(self.cell_points(), self.points.len()))
+    }
+
+    /// Returns the face indices sharing each point. Lazily computed on first
+    /// access.
+    ///
+    /// `point_faces()[p]` lists, in ascending order, every face that has
+    /// point `p` as a vertex. The returned list has length `n_points()`.
+    pub fn point_faces(&self) -> &CompactList {
+        self.point_faces
+            .get_or_init(|| geometry::compute_point_lists(&self.faces, self.points.len()))
+    }
+
+    /// Returns the unique edges of the mesh. Lazily computed on first access.
+    ///
+    /// Each edge is a `[lower, higher]` pair of point indices joined by a
+    /// side of at least one face. Edges are sorted lexicographically, so
+    /// the index of a known edge can be found by binary search.
+    pub fn edges(&self) -> &[[usize; 2]] {
+        self.edges
+            .get_or_init(|| geometry::compute_edges(&self.faces))
+    }
+
+    /// Returns the edge indices of each face. Lazily computed on first
+    /// access.
+    ///
+    /// `face_edges()[f][i]` is the index (into `edges()`) of the side from
+    /// `faces()[f][i]` to the next point of the face, so the edges follow
+    /// the face orientation. The returned list has length `n_faces()`.
+    pub fn face_edges(&self) -> &CompactList {
+        self.face_edges
+            .get_or_init(|| geometry::compute_face_edges(&self.faces, self.edges()))
+    }
+
+    /// Returns the edge indices of each cell. Lazily computed on first
+    /// access.
+    ///
+    /// `cell_edges()[c]` lists, in ascending order and without duplicates,
+    /// the edges of all faces of cell `c`. The returned list has length
+    /// `n_cells()`.
+    pub fn cell_edges(&self) -> &CompactList {
+        self.cell_edges.get_or_init(|| {
+            geometry::compute_cell_edges(self.ensure_cell_faces(), self.face_edges())
+        })
+    }
+
+    /// Returns the points joined to each point by an edge. Lazily computed
+    /// on first access.
+    ///
+    /// `point_points()[p]` lists the other ends of the edges of point `p` in
+    /// ascending order. The returned list has length `n_points()`.
+    pub fn point_points(&self) -> &CompactList {
+        self.point_points
+            .get_or_init(|| geometry::compute_point_points(self.edges(), self.points.len()))
+    }
+
+    // Lazy quality accessors
+
+    /// Returns the non-orthogonality angle of each face in degrees. Lazily
+    /// computed on first access.
+    ///
+    /// For an internal face this is the angle between the face area vector
+    /// and the owner-to-neighbor center vector; for a boundary face the
+    /// owner-to-face-center vector is used. The returned slice has length
+    /// `n_faces()`.
+    pub fn face_non_orthogonality(&self) -> &[f64] {
+        self.face_non_orthogonality.get_or_init(|| {
+            quality::compute_face_non_orthogonality(
+                self.face_areas(),
+                self.face_centers(),
+                self.cell_centers(),
+                &self.owner,
+                &self.neighbor,
+            )
+        })
+    }
+
+    /// Returns the skewness of each face. Lazily computed on first access.
+    ///
+    /// Skewness is the distance between the face center and the point where
+    /// the owner–neighbor center line crosses the face, relative to the
+    /// owner–neighbor distance (OpenFOAM definition). The returned slice has
+    /// length `n_faces()`.
+    pub fn face_skewness(&self) -> &[f64] {
+        self.face_skewness.get_or_init(|| {
+            quality::compute_face_skewness(
+                self.face_areas(),
+                self.face_centers(),
+                self.cell_centers(),
+                &self.owner,
+                &self.neighbor,
+            )
+        })
+    }
+
+    /// Returns the aspect ratio of each cell. Lazily computed on first access.
+    ///
+    /// A cube has aspect ratio 1. The returned slice has length `n_cells()`.
+    pub fn cell_aspect_ratio(&self) -> &[f64] {
+        self.cell_aspect_ratio.get_or_init(|| {
+            quality::compute_cell_aspect_ratio(
+                self.face_areas(),
+                self.cell_faces(),
+                self.cell_volumes(),
+            )
+        })
+    }
+
+    /// Returns the normalized face-normal determinant of each cell. Lazily
+    /// computed on first access.
+    ///
+    /// A hexahedron with parallel opposite faces gives 1; values near zero
+    /// indicate cells whose faces barely span three dimensions. The returned
+    /// slice has length `n_cells()`.
+    pub fn cell_determinant(&self) -> &[f64] {
+        self.cell_determinant
+            .get_or_init(|| quality::compute_cell_determinant(self.face_areas(), self.cell_faces()))
+    }
+
+    /// Returns, for each cell, the smallest volume ratio
+    /// `min(V_a, V_b) / max(V_a, V_b)` over its internal faces. Lazily
+    /// computed on first access.
+    ///
+    /// Cells without internal faces have ratio 1. The returned slice has
+    /// length `n_cells()`.
+    pub fn cell_volume_ratio(&self) -> &[f64] {
+        self.cell_volume_ratio.get_or_init(|| {
+            quality::compute_cell_volume_ratio(self.cell_volumes(), &self.owner, &self.neighbor)
+        })
+    }
+
+    // Lazy search index
+
+    /// Returns the bounding volume hierarchy over the cells used by point
+    /// location. Lazily built on first access.
+    pub(crate) fn cell_tree(&self) -> &BoxTree {
+        self.cell_tree
+            .get_or_init(|| BoxTree::new(&search::cell_bounding_boxes(self)))
+    }
 }
 
 #[cfg(test)]
@@ -513,7 +740,7 @@ mod tests {
         let areas = mesh.face_areas();
         let mut sum = Vector::zero();
         for a in areas {
-            sum = sum + *a;
+            sum += *a;
         }
         let mag = sum.mag();
         assert!(mag < 1e-12, "face area vector sum magnitude {mag} >= 1e-12");
@@ -544,7 +771,7 @@ mod tests {
         let cp = mesh.cell_points();
         assert_eq!(cp.len(), 1);
         let pts = &cp[0];
-        let mut sorted = pts.clone();
+        let mut sorted = pts.to_vec();
         sorted.sort();
         sorted.dedup();
         assert_eq!(
@@ -555,12 +782,129 @@ mod tests {
         assert_eq!(pts.len(), 8, "unit cube should have 8 points");
     }
 
+    #[test]
+    fn test_point_connectivity_two_cells() {
+        let mesh = make_two_cell_mesh();
+        // Points 1, 2, 5, 6 lie on the shared face
+        assert_eq!(mesh.point_cells()[0], [0]);
+        assert_eq!(mesh.point_cells()[1], [0, 1]);
+        assert_eq!(mesh.point_cells()[8], [1]);
+        assert_eq!(mesh.point_faces().len(), mesh.n_points());
+        for (p, faces) in mesh.point_faces().iter().enumerate() {
+            assert!(faces.iter().all(|&f| mesh.faces()[f].contains(&p)));
+            // Three faces meet at a corner, five on the shared face
+            let expected = if mesh.point_cells()[p].len() == 2 {
+                5
+            } else {
+                3
+            };
+            assert_eq!(faces.len(), expected, "point {p}");
+        }
+        assert_eq!(mesh.point_points()[0], [1, 3, 4]);
+        assert_eq!(mesh.point_points()[1], [0, 2, 5, 8]);
+    }
+
+    #[test]
+    fn test_edge_connectivity_two_cells() {
+        let mesh = make_two_cell_mesh();
+        let edges = mesh.edges();
+        // 12 edges per cube, 4 of them shared
+        assert_eq!(edges.len(), 20);
+        assert!(edges.windows(2).all(|w| w[0] < w[1]));
+        assert!(edges.iter().all(|e| e[0] < e[1]));
+
+        for (f, face) in mesh.faces().iter().enumerate() {
+            let face_edges = &mesh.face_edges()[f];
+            assert_eq!(face_edges.len(), face.len());
+            for (i, &e) in face_edges.iter().enumerate() {
+                let (a, b) = (face[i], face[(i + 1) % face.len()]);
+                assert_eq!(edges[e], [a.min(b), a.max(b)]);
+            }
+        }
+        assert_eq!(mesh.cell_edges()[0].len(), 12);
+        assert_eq!(mesh.cell_edges()[1].len(), 12);
+        let shared = mesh.cell_edges()[0]
+            .iter()
+            .filter(|e| mesh.cell_edges()[1].contains(e))
+            .count();
+        assert_eq!(shared, 4);
+    }
+
     #[test]
     fn test_primitive_mesh_is_send_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<PrimitiveMesh>();
     }
 
+    // ===== unit_cube generator tests =====
+
+    #[test]
+    fn test_unit_cube_volumes_and_centers() {
+        let mesh = PrimitiveMesh::unit_cube(2, 2, 2).unwrap();
+        assert_eq!(mesh.n_cells(), 8);
+        for (i, &v) in mesh.cell_volumes().iter().enumerate() {
+            assert!((v - 0.125).abs() < 1e-12, "cell {i} volume {v}");
+        }
+        let c7 = mesh.cell_centers()[7];
+        assert!((c7 - Vector::new(0.75, 0.75, 0.75)).mag() < 1e-12);
+    }
+
+    #[test]
+    fn test_unit_cube_cells_are_closed() {
+        let mesh = PrimitiveMesh::unit_cube(3, 2, 2).unwrap();
+        let areas = mesh.face_areas();
+        let mut sums = vec![Vector::zero(); mesh.n_cells()];
+        for (f, &o) in mesh.owner().iter().enumerate() {
+            sums[o] += areas[f];
+        }
+        for (f, &n) in mesh.neighbor().iter().enumerate() {
+            sums[n] -= areas[f];
+        }
+        for (c, s) in sums.iter().enumerate() {
+            assert!(s.mag() < 1e-12, "cell {c} is not closed");
+        }
+    }
+
+    #[test]
+    fn test_unit_cube_zero_count_returns_err() {
+        assert!(matches!(
+            PrimitiveMesh::unit_cube(0, 1, 1),
+            Err(MeshError::ZeroCellCount { direction: 0 })
+        ));
+    }
+
+    // ===== Quality metric accessor tests =====
+
+    #[test]
+    fn test_quality_metrics_have_expected_lengths() {
+        let mesh = make_two_cell_mesh();
+        assert_eq!(mesh.face_non_orthogonality().len(), mesh.n_faces());
+        assert_eq!(mesh.face_skewness().len(), mesh.n_faces());
+        assert_eq!(mesh.cell_aspect_ratio().len(), mesh.n_cells());
+        assert_eq!(mesh.cell_determinant().len(), mesh.n_cells());
+        assert_eq!(mesh.cell_volume_ratio().len(), mesh.n_cells());
+    }
+
+    #[test]
+    fn test_quality_metrics_orthogonal_two_cells() {
+        let mesh = make_two_cell_mesh();
+        assert!(mesh.face_non_orthogonality()[0] < 1e-6);
+        assert!(mesh.face_skewness()[0] < 1e-12);
+        for c in 0..2 {
+            assert!((mesh.cell_aspect_ratio()[c] - 1.0).abs() < 1e-12);
+            assert!((mesh.cell_determinant()[c] - 1.0).abs() < 1e-12);
+            assert!((mesh.cell_volume_ratio()[c] - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_quality_metrics_cached_on_second_call() {
+        let mesh = make_two_cell_mesh();
+        let ptr1 = mesh.face_skewness().as_ptr();
+        let ptr2 = mesh.face_skewness().as_ptr();
+        assert_eq!(ptr1, ptr2, "face_skewness should return same pointer");
+    }
+
     // ===== Two-cell geometry tests =====
 
     #[test]
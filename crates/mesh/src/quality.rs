@@ -0,0 +1,288 @@
+use dugong_types::tensor::{Tensor, Vector};
+
+use crate::compact_list::CompactList;
+
+/// Guards divisions by vanishing lengths, areas and volumes.
+const VSMALL: f64 = 1e-300;
+
+/// Computes the non-orthogonality angle (degrees) of every face.
+///
+/// For an internal face the angle is measured between the face area vector
+/// and the vector joining the owner and neighbor cell centers. For a boundary
+/// face the vector from the owner center to the face center is used instead.
+///
+/// # Panics
+///
+/// Panics if the slices are inconsistent with `owner`/`neighbor`.
+pub(crate) fn compute_face_non_orthogonality(
+    face_areas: &[Vector],
+    face_centers: &[Vector],
+    cell_centers: &[Vector],
+    owner: &[usize],
+    neighbor: &[usize],
+) -> Vec<f64> {
+    let n_internal = neighbor.len();
+    face_areas
+        .iter()
+        .enumerate()
+        .map(|(f, &sf)| {
+            let d = if f < n_internal {
+                cell_centers[neighbor[f]] - cell_centers[owner[f]]
+            } else {
+                face_centers[f] - cell_centers[owner[f]]
+            };
+            let cos = (sf * d) / (sf.mag() * d.mag() + VSMALL);
+            cos.clamp(-1.0, 1.0).acos().to_degrees()
+        })
+        .collect()
+}
+
+/// Computes the skewness of every face, following OpenFOAM's definition.
+///
+/// For an internal face, skewness is the distance between the face center and
+/// the point where the owner–neighbor center line crosses the face, divided
+/// by the owner–neighbor distance. For a boundary face the owner center is
+/// projected onto the face plane, and the distance is divided by twice the
+/// owner-to-plane distance.
+///
+/// # Panics
+///
+/// Panics if the slices are inconsistent with `owner`/`neighbor`.
+pub(crate) fn compute_face_skewness(
+    face_areas: &[Vector],
+    face_centers: &[Vector],
+    cell_centers: &[Vector],
+    owner: &[usize],
+    neighbor: &[usize],
+) -> Vec<f64> {
+    let n_internal = neighbor.len();
+    face_areas
+        .iter()
+        .enumerate()
+        .map(|(f, &sf)| {
+            let n = sf / (sf.mag() + VSMALL);
+            let co = cell_centers[owner[f]];
+            let cf = face_centers[f];
+            if f < n_internal {
+                let cn = cell_centers[neighbor[f]];
+                let d_own = ((cf - co) * n).abs();
+                let d_nei = ((cn - cf) * n).abs();
+                let intersection = (co * d_nei + cn * d_own) / (d_own + d_nei + VSMALL);
+                (cf - intersection).mag() / ((cn - co).mag() + VSMALL)
+            } else {
+                let intersection = co + n * ((cf - co) * n);
+                (cf - intersection).mag() / (2.0 * (intersection - co).mag() + VSMALL)
+            }
+        })
+        .collect()
+}
+
+/// Computes the aspect ratio of every cell, following OpenFOAM's definition.
+///
+/// The aspect ratio is the larger of the ratio between the largest and the
+/// smallest Cartesian component of the summed face area magnitudes, and the
+/// ratio between the actual surface area and that of a cube of equal volume.
+///
+/// # Panics
+///
+/// Panics if any face index in `cell_faces` is out of range for
+/// `face_areas`, or `cell_faces` and `cell_volumes` differ in length.
+pub(crate) fn compute_cell_aspect_ratio(
+    face_areas: &[Vector],
+    cell_faces: &CompactList,
+    cell_volumes: &[f64],
+) -> Vec<f64> {
+    cell_faces
+        .iter()
+        .zip(cell_volumes)
+        .map(|(faces, &vol)| {
+            let mut cmpt = [0.0_f64; 3];
+            let mut sum_mag = 0.0;
+            for &f in faces {
+                let a = face_areas[f].as_array();
+                for d in 0..3 {
+                    cmpt[d] += a[d].abs();
+                }
+                sum_mag += face_areas[f].mag();
+            }
+            let max_cmpt = cmpt.iter().copied().fold(0.0, f64::max);
+            let min_cmpt = cmpt.iter().copied().fold(f64::INFINITY, f64::min);
+            let cartesian = max_cmpt / (min_cmpt + VSMALL);
+            let hydraulic = sum_mag / (6.0 * vol.abs().powf(2.0 / 3.0) + VSMALL);
+            cartesian.max(hydraulic)
+        })
+        .collect()
+}
+
+/// Computes the determinant of the normalized face-normal tensor of every
+/// cell.
+///
+/// For each cell the tensor `Σ n ⊗ n` over its unit face normals is formed,
+/// and the absolute value of its determinant is divided by 8 so that a
+/// hexahedron with parallel opposite faces gives 1. Values near zero indicate
+/// cells whose faces barely span three dimensions.
+///
+/// # Panics
+///
+/// Panics if any face index in `cell_faces` is out of range for
+/// `face_areas`.
+pub(crate) fn compute_cell_determinant(
+    face_areas: &[Vector],
+    cell_faces: &CompactList,
+) -> Vec<f64> {
+    cell_faces
+        .iter()
+        .map(|faces| {
+            let mut sum = Tensor::zero();
+            for &f in faces {
+                let n = face_areas[f] / (face_areas[f].mag() + VSMALL);
+                sum += n.outer(&n);
+            }
+            sum.det().abs() / 8.0
+        })
+        .collect()
+}
+
+/// Computes the smallest volume ratio between every cell and its face
+/// neighbors.
+///
+/// For each internal face the ratio `min(V_o, V_n) / max(V_o, V_n)` is formed;
+/// each cell receives the minimum over its internal faces, or 1 if it has
+/// none.
+///
+/// # Panics
+///
+/// Panics if any `owner`/`neighbor` entry is out of range for `cell_volumes`.
+pub(crate) fn compute_cell_volume_ratio(
+    cell_volumes: &[f64],
+    owner: &[usize],
+    neighbor: &[usize],
+) -> Vec<f64> {
+    let mut ratio = vec![1.0_f64; cell_volumes.len()];
+    for (f, &n) in neighbor.iter().enumerate() {
+        let o = owner[f];
+        let (vo, vn) = (cell_volumes[o].abs(), cell_volumes[n].abs());
+        let r = vo.min(vn) / (vo.max(vn) + VSMALL);
+        ratio[o] = ratio[o].min(r);
+        ratio[n] = ratio[n].min(r);
+    }
+    ratio
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PrimitiveMesh;
+
+    #[test]
+    fn orthogonal_mesh_has_zero_non_orthogonality_and_skewness() {
+        let mesh = PrimitiveMesh::unit_cube(3, 2, 2).unwrap();
+        let args = (
+            mesh.face_areas(),
+            mesh.face_centers(),
+            mesh.cell_centers(),
+            mesh.owner(),
+            mesh.neighbor(),
+        );
+        let non_orth = compute_face_non_orthogonality(args.0, args.1, args.2, args.3, args.4);
+        let skew = compute_face_skewness(args.0, args.1, args.2, args.3, args.4);
+        assert_eq!(non_orth.len(), mesh.n_faces());
+        assert!(non_orth.iter().all(|&a| a < 1e-6), "{non_orth:?}");
+        assert!(skew.iter().all(|&s| s < 1e-10), "{skew:?}");
+    }
+
+    #[test]
+    fn sheared_internal_face_is_non_orthogonal() {
+        // A unit cube below a parallelepiped whose top is shifted by 2 in x,
+        // so the center line is at 45 degrees to the shared face normal (+z).
+        let points = vec![
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(1.0, 1.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(1.0, 0.0, 1.0),
+            Vector::new(1.0, 1.0, 1.0),
+            Vector::new(0.0, 1.0, 1.0),
+            Vector::new(2.0, 0.0, 2.0),
+            Vector::new(3.0, 0.0, 2.0),
+            Vector::new(3.0, 1.0, 2.0),
+            Vector::new(2.0, 1.0, 2.0),
+        ];
+        let faces = vec![
+            vec![4, 5, 6, 7],   // internal, +z
+            vec![0, 3, 2, 1],   // cell 0 z-
+            vec![0, 1, 5, 4],   // cell 0 y-
+            vec![3, 7, 6, 2],   // cell 0 y+
+            vec![0, 4, 7, 3],   // cell 0 x-
+            vec![1, 2, 6, 5],   // cell 0 x+
+            vec![8, 9, 10, 11], // cell 1 z+
+            vec![4, 5, 9, 8],   // cell 1 y-
+            vec![7, 11, 10, 6], // cell 1 y+
+            vec![4, 8, 11, 7],  // cell 1 x-
+            vec![5, 6, 10, 9],  // cell 1 x+
+        ];
+        let owner = vec![0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1];
+        let mesh = PrimitiveMesh::new(points, faces, owner, vec![1]).unwrap();
+        let non_orth = compute_face_non_orthogonality(
+            mesh.face_areas(),
+            mesh.face_centers(),
+            mesh.cell_centers(),
+            mesh.owner(),
+            mesh.neighbor(),
+        );
+        assert!((non_orth[0] - 45.0).abs() < 1e-10, "angle {}", non_orth[0]);
+        let skew = compute_face_skewness(
+            mesh.face_areas(),
+            mesh.face_centers(),
+            mesh.cell_centers(),
+            mesh.owner(),
+            mesh.neighbor(),
+        );
+        // Center line crosses the face at x = 1.0; the face center is at 0.5
+        let expected = 0.5 / 2.0_f64.sqrt();
+        assert!((skew[0] - expected).abs() < 1e-10, "skewness {}", skew[0]);
+    }
+
+    #[test]
+    fn aspect_ratio_of_stretched_box() {
+        let unit = PrimitiveMesh::unit_cube(1, 1, 1).unwrap();
+        let ar =
+            compute_cell_aspect_ratio(unit.face_areas(), unit.cell_faces(), unit.cell_volumes());
+        assert!((ar[0] - 1.0).abs() < 1e-12);
+
+        // 4 x 1 x 1 cells in the unit cube: each cell is 0.25 x 1 x 1
+        let slab = PrimitiveMesh::unit_cube(4, 1, 1).unwrap();
+        let ar =
+            compute_cell_aspect_ratio(slab.face_areas(), slab.cell_faces(), slab.cell_volumes());
+        // Component areas: x = 2, y = z = 0.5 -> ratio 4
+        assert!((ar[0] - 4.0).abs() < 1e-12, "aspect ratio {}", ar[0]);
+    }
+
+    #[test]
+    fn determinant_of_hexahedron_is_one() {
+        let mesh = PrimitiveMesh::unit_cube(2, 1, 3).unwrap();
+        let det = compute_cell_determinant(mesh.face_areas(), mesh.cell_faces());
+        for (c, &d) in det.iter().enumerate() {
+            assert!((d - 1.0).abs() < 1e-12, "cell {c} determinant {d}");
+        }
+    }
+
+    #[test]
+    fn volume_ratio_of_graded_cells() {
+        let (mesh, _) =
+            crate::CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [3, 1, 1])
+                .with_grading([4.0, 1.0, 1.0])
+                .build()
+                .unwrap();
+        let ratio = compute_cell_volume_ratio(mesh.cell_volumes(), mesh.owner(), mesh.neighbor());
+        // Consecutive widths grow by a factor of 2
+        for (c, &r) in ratio.iter().enumerate() {
+            assert!((r - 0.5).abs() < 1e-10, "cell {c} ratio {r}");
+        }
+        let single = PrimitiveMesh::unit_cube(1, 1, 1).unwrap();
+        let ratio =
+            compute_cell_volume_ratio(single.cell_volumes(), single.owner(), single.neighbor());
+        assert_eq!(ratio, vec![1.0]);
+    }
+}
@@ -0,0 +1,690 @@
+//! Isotropic 2×2×2 refinement of hexahedral cells.
+//!
+//! [`PrimitiveMesh::refine_hex`] splits each selected hexahedron into eight
+//! children by adding a point at the middle of every edge, at the center of
+//! every face and at the center of the cell. Refinement is conforming: a
+//! face between a refined and an unrefined cell is split into four faces on
+//! both sides, and every face that shares a split edge gets the edge
+//! midpoint inserted, so unrefined neighbors become polyhedra with no
+//! hanging nodes. The selection criterion (e.g. a gradient magnitude above
+//! a threshold) is left to the caller.
+//!
+//! Refinement can be repeated with [`HexRefinement::refine`]. As in
+//! OpenFOAM's `hexRef8`, every cell and point carries a refinement level,
+//! and a cell whose faces were split or gained midpoints is still a
+//! hexahedron through its eight anchor points. Its edges and faces are
+//! split at the midpoints and centers already there. Cells sharing a face
+//! may differ by at most one level.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use dugong_types::tensor::Vector;
+
+use crate::assembly::{assemble_cells, relabel_patches};
+use crate::error::MeshError;
+use crate::merge::check_patches;
+use crate::patch_def::PatchDef;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// A refined mesh and the maps relating it to the original.
+pub struct HexRefinement {
+    /// The refined mesh.
+    pub mesh: PrimitiveMesh,
+    /// Boundary patches of the refined mesh, in the original order.
+    pub patches: Vec<PatchDef>,
+    /// `cell_map[new] == parent`, the original cell each new cell lies in.
+    pub cell_map: Vec<usize>,
+    /// `cell_offsets[old]..cell_offsets[old + 1]` are the new cells of
+    /// original cell `old`: eight for a refined cell, one otherwise.
+    pub cell_offsets: Vec<usize>,
+    /// Refinement level of each new cell: how many times a cell of the
+    /// unrefined mesh was split to make it.
+    pub cell_level: Vec<usize>,
+    /// Refinement level of each point: zero for points of the unrefined
+    /// mesh, one more than the level of the cell whose refinement added it
+    /// otherwise. The anchor points of a cell, those with a level not above
+    /// the cell's, are its eight corners.
+    pub point_level: Vec<usize>,
+}
+
+impl HexRefinement {
+    /// Returns the new cells covering original cell `old`.
+    pub fn children(&self, old: usize) -> Range<usize> {
+        self.cell_offsets[old]..self.cell_offsets[old + 1]
+    }
+
+    /// Copies per-cell values of the original mesh to the new cells, each
+    /// child taking the value of its parent.
+    pub fn map_cell_values<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        self.cell_map
+            .iter()
+            .map(|&old| values[old].clone())
+            .collect()
+    }
+
+    /// Splits each of `cells` of the refined mesh into eight hexahedra.
+    ///
+    /// This is [`PrimitiveMesh::refine_hex`] on [`mesh`](Self::mesh) and
+    /// [`patches`](Self::patches), using the levels tracked so far; the maps
+    /// of the result relate it to `self.mesh`.
+    ///
+    /// # Errors
+    ///
+    /// As [`PrimitiveMesh::refine_hex`].
+    pub fn refine(&self, cells: &[usize]) -> Result<HexRefinement, MeshError> {
+        refine(
+            &self.mesh,
+            cells,
+            &self.patches,
+            &self.cell_level,
+            &self.point_level,
+        )
+    }
+}
+
+impl PrimitiveMesh {
+    /// Splits each of `cells` into eight hexahedra.
+    ///
+    /// `patches` describes the boundary of this mesh as in
+    /// [`PolyMesh`](crate::PolyMesh); boundary faces of refined cells are
+    /// split within their patch. Original points keep their indices and the
+    /// new points follow them. The children of a cell are numbered
+    /// consecutively in place of their parent, ordered by the original
+    /// corner they contain. Faces are renumbered, so face fields must be
+    /// recomputed rather than mapped.
+    ///
+    /// The mesh is taken to be unrefined; use [`HexRefinement::refine`] to
+    /// refine the result again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a cell index is `>= n_cells()`, a selected cell is
+    /// not a hexahedron, two face neighbors would end up more than one level
+    /// apart, or `patches` does not partition the boundary.
+    pub fn refine_hex(
+        &self,
+        cells: &[usize],
+        patches: &[PatchDef],
+    ) -> Result<HexRefinement, MeshError> {
+        refine(
+            self,
+            cells,
+            patches,
+            &vec![0; self.n_cells()],
+            &vec![0; self.n_points()],
+        )
+    }
+}
+
+/// Refines `cells` of `mesh`, whose cells and points are at the given
+/// levels.
+fn refine(
+    mesh: &PrimitiveMesh,
+    cells: &[usize],
+    patches: &[PatchDef],
+    cell_level: &[usize],
+    point_level: &[usize],
+) -> Result<HexRefinement, MeshError> {
+    check_patches(mesh, patches)?;
+    let mut refine = vec![false; mesh.n_cells()];
+    for &cell in cells {
+        if cell >= mesh.n_cells() {
+            return Err(MeshError::CellIndexOutOfRange {
+                cell,
+                n_cells: mesh.n_cells(),
+            });
+        }
+        refine[cell] = true;
+    }
+
+    let owner = mesh.owner();
+    let neighbor = mesh.neighbor();
+    let faces = mesh.faces();
+
+    // A face between levels L and L + 1 is a quarter of a face of the
+    // coarser cell; anything finer is not supported
+    let new_level = |c: usize| cell_level[c] + usize::from(refine[c]);
+    for (f, &n) in neighbor.iter().enumerate() {
+        if new_level(owner[f]).abs_diff(new_level(n)) > 1 {
+            return Err(MeshError::UnbalancedRefinement {
+                cell: owner[f],
+                neighbor: n,
+            });
+        }
+    }
+
+    // Edge midpoints, face centers and cell centers of refined cells
+    let mut new = NewPoints {
+        points: mesh.points().to_vec(),
+        level: point_level.to_vec(),
+        edge_mid: HashMap::new(),
+        face_mid: HashMap::new(),
+    };
+    let mut hexes: HashMap<usize, (Vec<CoarseFace>, usize)> = HashMap::new();
+    for c in (0..mesh.n_cells()).filter(|&c| refine[c]) {
+        let level = cell_level[c];
+        let hex =
+            coarse_faces(mesh, c, level, &mut new).ok_or(MeshError::NotHexahedron { cell: c })?;
+        let center = new.push(
+            average(&new.points, &anchors(mesh, c, level, &new.level)),
+            level,
+        );
+        hexes.insert(c, (hex, center));
+    }
+
+    let patch_of = |f: usize| {
+        patches
+            .iter()
+            .position(|p| p.range().contains(&f))
+            .unwrap_or(usize::MAX)
+    };
+
+    // Outward faces of every new cell and the patch of each boundary one
+    let mut new_cells: Vec<Vec<Vec<usize>>> = Vec::new();
+    let mut tags: Vec<Vec<usize>> = Vec::new();
+    let mut cell_map = Vec::new();
+    let mut cell_offsets = vec![0];
+    let mut new_cell_level = Vec::new();
+    for (c, &level) in cell_level.iter().enumerate() {
+        let outward = |mut face: Vec<usize>, f: usize| {
+            if owner[f] != c {
+                face.reverse();
+            }
+            face
+        };
+        let tag = |f: usize| {
+            if f < neighbor.len() {
+                usize::MAX
+            } else {
+                patch_of(f)
+            }
+        };
+        let Some((hex, cc)) = hexes.get(&c) else {
+            let mut cell_faces = Vec::new();
+            let mut cell_tags = Vec::new();
+            for &f in &mesh.cell_faces()[c] {
+                for q in new.split_face(&faces[f], f) {
+                    cell_faces.push(outward(q, f));
+                    cell_tags.push(tag(f));
+                }
+            }
+            new_cells.push(cell_faces);
+            tags.push(cell_tags);
+            cell_map.push(c);
+            cell_offsets.push(new_cells.len());
+            new_cell_level.push(level);
+            continue;
+        };
+
+        for v in anchors(mesh, c, level, point_level) {
+            let mut child_faces = Vec::with_capacity(6);
+            let mut child_tags = Vec::with_capacity(6);
+            for (k, side) in hex.iter().enumerate() {
+                let Some(i) = side.corners.iter().position(|&p| p == v) else {
+                    continue;
+                };
+                // The quarter of the side holding `v`, which an earlier
+                // refinement of the neighbor may have left as a face of
+                // its own
+                if let [f] = side.faces[..] {
+                    let q = new
+                        .split_face(&faces[f], f)
+                        .into_iter()
+                        .find(|q| q.contains(&v))
+                        .ok_or(MeshError::NotHexahedron { cell: c })?;
+                    child_faces.push(outward(q, f));
+                    child_tags.push(tag(f));
+                } else {
+                    let f = side.faces[i];
+                    for q in new.split_face(&faces[f], f) {
+                        child_faces.push(outward(q, f));
+                        child_tags.push(tag(f));
+                    }
+                }
+
+                // Internal face across the edge leaving `v` along the
+                // outward loop, which visits each edge of `v` once
+                let w = side.corners[(i + 1) % 4];
+                let Some(other) = hex
+                    .iter()
+                    .enumerate()
+                    .find(|&(l, g)| l != k && g.corners.contains(&v) && g.corners.contains(&w))
+                    .map(|(_, g)| g)
+                else {
+                    continue;
+                };
+                let mut inner = vec![side.mids[i], side.center, *cc, other.center];
+                let p = &new.points;
+                let normal = (p[inner[2]] - p[inner[0]]).cross(&(p[inner[3]] - p[inner[1]]));
+                if normal * (p[w] - p[v]) < 0.0 {
+                    inner.reverse();
+                }
+                child_faces.push(new.insert_mids(&inner));
+                child_tags.push(usize::MAX);
+            }
+            new_cells.push(child_faces);
+            tags.push(child_tags);
+            cell_map.push(c);
+            new_cell_level.push(level + 1);
+        }
+        cell_offsets.push(new_cells.len());
+    }
+
+    let assembled = assemble_cells(new.points, &new_cells, |c, lf| tags[c][lf])?;
+    let new_patches = relabel_patches(patches, &assembled);
+
+    Ok(HexRefinement {
+        mesh: assembled.mesh,
+        patches: new_patches,
+        cell_map,
+        cell_offsets,
+        cell_level: new_cell_level,
+        point_level: new.level,
+    })
+}
+
+/// Points of the refined mesh with their levels, and the points added on
+/// edges and faces of the original mesh.
+struct NewPoints {
+    points: Vec<Vector>,
+    level: Vec<usize>,
+    /// Midpoint added on an original edge.
+    edge_mid: HashMap<(usize, usize), usize>,
+    /// Center added on an original face and the level of the cell that
+    /// split it.
+    face_mid: HashMap<usize, (usize, usize)>,
+}
+
+impl NewPoints {
+    /// Adds a point created by refining a cell at `level`.
+    fn push(&mut self, point: Vector, level: usize) -> usize {
+        self.points.push(point);
+        self.level.push(level + 1);
+        self.points.len() - 1
+    }
+
+    /// Returns the midpoint of original edge `a`-`b`, adding it if needed.
+    fn edge_mid(&mut self, a: usize, b: usize, level: usize) -> usize {
+        if let Some(&m) = self.edge_mid.get(&edge_key(a, b)) {
+            return m;
+        }
+        let m = self.push((self.points[a] + self.points[b]) * 0.5, level);
+        self.edge_mid.insert(edge_key(a, b), m);
+        m
+    }
+
+    /// Returns the center of original face `f`, adding it if needed.
+    fn face_mid(&mut self, f: usize, center: Vector, level: usize) -> usize {
+        if let Some(&(m, _)) = self.face_mid.get(&f) {
+            return m;
+        }
+        let m = self.push(center, level);
+        self.face_mid.insert(f, (m, level));
+        m
+    }
+
+    /// Returns `face` with the midpoints of its split edges inserted.
+    fn insert_mids(&self, face: &[usize]) -> Vec<usize> {
+        let mut out = Vec::with_capacity(face.len());
+        for (i, &a) in face.iter().enumerate() {
+            out.push(a);
+            if let Some(&m) = self.edge_mid.get(&edge_key(a, face[(i + 1) % face.len()])) {
+                out.push(m);
+            }
+        }
+        out
+    }
+
+    /// Returns the faces replacing original face `f` with points `face`, in
+    /// its orientation: its quarters if a refined cell split it, itself
+    /// with new edge midpoints otherwise.
+    ///
+    /// Quarter `i` holds the `i`-th anchor of the face. It runs from the
+    /// anchor along the face to the next edge midpoint, through the center,
+    /// and back from the previous edge midpoint, keeping any points that
+    /// finer neighbors left on the way.
+    fn split_face(&self, face: &[usize], f: usize) -> Vec<Vec<usize>> {
+        let face = self.insert_mids(face);
+        let Some(&(center, level)) = self.face_mid.get(&f) else {
+            return vec![face];
+        };
+        let n = face.len();
+        let is_mid = |j: usize| self.level[face[j % n]] == level + 1;
+        (0..n)
+            .filter(|&i| self.level[face[i]] <= level)
+            .map(|i| {
+                let next = (i + 1..i + n).find(|&j| is_mid(j)).unwrap_or(i);
+                let prev = (i + 1..i + n).rev().find(|&j| is_mid(j)).unwrap_or(i);
+                let mut quarter: Vec<usize> = (i..=next).map(|j| face[j % n]).collect();
+                quarter.push(center);
+                quarter.extend((prev..i + n).map(|j| face[j % n]));
+                quarter
+            })
+            .collect()
+    }
+}
+
+/// A side of a refined hexahedron at the cell's own level: one original
+/// face, or four quarters left by an earlier refinement of the neighbor.
+struct CoarseFace {
+    /// Anchor points in outward order.
+    corners: [usize; 4],
+    /// `mids[i]` halves the edge from `corners[i]` to the next corner.
+    mids: [usize; 4],
+    center: usize,
+    /// The original face, or the quarter holding each corner.
+    faces: Vec<usize>,
+}
+
+/// Returns the six sides of cell `c` at `level`, adding the edge midpoints
+/// and face centers it still lacks, or `None` if the cell is not a
+/// hexahedron.
+///
+/// As in OpenFOAM's `hexRef8`, the corners of a cell are its anchor points,
+/// those with a level not above the cell's. Points between them come from
+/// refined neighbors: the midpoint of an edge or the center of a side is
+/// the one point at `level + 1` there, the others are finer.
+fn coarse_faces(
+    mesh: &PrimitiveMesh,
+    c: usize,
+    level: usize,
+    new: &mut NewPoints,
+) -> Option<Vec<CoarseFace>> {
+    let anchors = anchors(mesh, c, level, &new.level);
+    if anchors.len() != 8 {
+        return None;
+    }
+
+    let mut sides = Vec::with_capacity(6);
+    // Quarters by center: anchor, next midpoint, previous midpoint, face
+    let mut quarters: Vec<(usize, Vec<[usize; 4]>)> = Vec::new();
+    for &f in &mesh.cell_faces()[c] {
+        let mut face = mesh.faces()[f].to_vec();
+        if mesh.owner()[f] != c {
+            face.reverse();
+        }
+        let n = face.len();
+        let corners: Vec<usize> = (0..n).filter(|&i| new.level[face[i]] <= level).collect();
+        match corners[..] {
+            [_, _, _, _] => {
+                let mut mids = [0; 4];
+                for k in 0..4 {
+                    let (i, j) = (corners[k], corners[(k + 1) % 4]);
+                    let end = if j > i { j } else { j + n };
+                    let mut on_level = (i + 1..end)
+                        .map(|t| face[t % n])
+                        .filter(|&p| new.level[p] == level + 1);
+                    mids[k] = match (end - i, on_level.next(), on_level.next()) {
+                        (1, _, _) => new.edge_mid(face[i], face[j], level),
+                        (_, Some(m), None) => m,
+                        _ => return None,
+                    };
+                }
+                let corners = [0, 1, 2, 3].map(|k| face[corners[k]]);
+                let center = new.face_mid(f, average(&new.points, &corners), level);
+                sides.push(CoarseFace {
+                    corners,
+                    mids,
+                    center,
+                    faces: vec![f],
+                });
+            }
+            [i] => {
+                let on_level: Vec<usize> = (1..n)
+                    .map(|t| face[(i + t) % n])
+                    .filter(|&p| new.level[p] == level + 1)
+                    .collect();
+                let [next, center, prev] = on_level[..] else {
+                    return None;
+                };
+                let quarter = [face[i], next, prev, f];
+                match quarters.iter_mut().find(|(m, _)| *m == center) {
+                    Some((_, group)) => group.push(quarter),
+                    None => quarters.push((center, vec![quarter])),
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    // Chain the quarters of each side through their shared midpoints
+    for (center, group) in quarters {
+        if group.len() != 4 {
+            return None;
+        }
+        let (mut corners, mut mids, mut faces) = ([0; 4], [0; 4], Vec::with_capacity(4));
+        let mut quarter = group[0];
+        for k in 0..4 {
+            corners[k] = quarter[0];
+            mids[k] = quarter[1];
+            faces.push(quarter[3]);
+            quarter = *group.iter().find(|q| q[2] == mids[k])?;
+        }
+        if quarter != group[0] {
+            return None;
+        }
+        sides.push(CoarseFace {
+            corners,
+            mids,
+            center,
+            faces,
+        });
+    }
+
+    let is_hex = sides.len() == 6
+        && anchors
+            .iter()
+            .all(|a| sides.iter().filter(|s| s.corners.contains(a)).count() == 3);
+    is_hex.then_some(sides)
+}
+
+/// Returns the anchor points of cell `c` at `level`, in cell point order.
+fn anchors(mesh: &PrimitiveMesh, c: usize, level: usize, point_level: &[usize]) -> Vec<usize> {
+    mesh.cell_points()[c]
+        .iter()
+        .copied()
+        .filter(|&p| point_level[p] <= level)
+        .collect()
+}
+
+/// Returns the average position of `ids`.
+///
+/// New face and cell centers are the averages of their corners, which do not
+/// depend on the points that refined neighbors added.
+fn average(points: &[Vector], ids: &[usize]) -> Vector {
+    ids.iter().fold(Vector::zero(), |sum, &p| sum + points[p]) / ids.len() as f64
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::{CartesianBox, CellShape, ShapeMesh};
+
+    fn unit_box(n: usize) -> (PrimitiveMesh, Vec<PatchDef>) {
+        CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [n, n, n])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn refining_one_corner_cell_is_conforming() {
+        let (mesh, patches) = unit_box(2);
+        let refined = mesh.refine_hex(&[0], &patches).unwrap();
+        let m = &refined.mesh;
+
+        assert_eq!(m.n_cells(), 15);
+        assert_eq!(refined.children(0), 0..8);
+        assert_eq!(refined.children(1), 8..9);
+        assert!(m.check_topology().is_ok());
+        let report = m.check_geometry();
+        assert!(report.is_ok(), "{:?}", report.errors);
+        assert!((report.total_volume - 1.0).abs() < 1e-12);
+        for c in refined.children(0) {
+            assert!((m.cell_volumes()[c] - 1.0 / 64.0).abs() < 1e-12);
+        }
+
+        // The neighbor across x sees four faces where it had one, plus an
+        // edge midpoint on each of the four faces around the split one
+        let neighbor = refined.children(1).start;
+        assert_eq!(m.cell_faces()[neighbor].len(), 9);
+        let n_points: usize = m.cell_faces()[neighbor]
+            .iter()
+            .map(|&f| m.faces()[f].len())
+            .sum();
+        assert_eq!(n_points, 4 * 9 + 4);
+
+        // xMin had four faces; the refined cell's one became four
+        let sizes: Vec<_> = refined.patches.iter().map(|p| p.size).collect();
+        assert_eq!(sizes, vec![7, 4, 7, 4, 7, 4]);
+        assert!(crate::PolyMesh::from_patch_defs(refined.mesh, &refined.patches).is_ok());
+    }
+
+    #[test]
+    fn refining_everything_matches_a_finer_box() {
+        let (mesh, patches) = unit_box(2);
+        let all: Vec<usize> = (0..mesh.n_cells()).collect();
+        let refined = mesh.refine_hex(&all, &patches).unwrap();
+        let (fine, fine_patches) = unit_box(4);
+
+        assert_eq!(refined.mesh.n_cells(), fine.n_cells());
+        assert_eq!(refined.mesh.n_points(), fine.n_points());
+        assert_eq!(refined.mesh.n_internal_faces(), fine.n_internal_faces());
+        assert_eq!(refined.patches, fine_patches);
+
+        let levels: Vec<f64> = (0..mesh.n_cells()).map(|c| c as f64).collect();
+        let mapped = refined.map_cell_values(&levels);
+        for (c, &parent) in mapped.iter().enumerate() {
+            let center = refined.mesh.cell_centers()[c];
+            assert_eq!(mesh.find_cell(center), Some(parent as usize));
+        }
+    }
+
+    /// Checks that `refined` is a valid mesh of the unit box whose level-0
+    /// cells are an eighth of it, and that every cell has eight anchors.
+    fn check_levels(refined: &HexRefinement) {
+        let m = &refined.mesh;
+        assert!(m.check_topology().is_ok());
+        let report = m.check_geometry();
+        assert!(report.is_ok(), "{:?}", report.errors);
+        assert!((report.total_volume - 1.0).abs() < 1e-12);
+        assert_eq!(refined.cell_level.len(), m.n_cells());
+        assert_eq!(refined.point_level.len(), m.n_points());
+        for c in 0..m.n_cells() {
+            let level = refined.cell_level[c];
+            let expected = 0.125f64.powi(level as i32 + 1);
+            assert!((m.cell_volumes()[c] - expected).abs() < 1e-12, "cell {c}");
+            let anchors = m.cell_points()[c]
+                .iter()
+                .filter(|&&p| refined.point_level[p] <= level)
+                .count();
+            assert_eq!(anchors, 8, "cell {c}");
+        }
+        assert!(check_patches(m, &refined.patches).is_ok());
+    }
+
+    #[test]
+    fn refinement_repeats_across_a_coarse_fine_interface() {
+        let (mesh, patches) = unit_box(2);
+        let first = mesh.refine_hex(&[0], &patches).unwrap();
+        check_levels(&first);
+        assert_eq!(first.cell_level[..9], [1, 1, 1, 1, 1, 1, 1, 1, 0]);
+
+        // The old neighbor has four quarters toward the fine cells and
+        // midpoints on four more faces
+        let neighbor = first.children(1).start;
+        let second = first.refine(&[neighbor]).unwrap();
+        check_levels(&second);
+        assert_eq!(second.mesh.n_cells(), 22);
+        assert_eq!(second.children(neighbor).len(), 8);
+        assert_eq!(second.cell_map[8..16], [neighbor; 8]);
+
+        // The interface between the two refined cells is four plain quarter
+        // faces, each shared by two level-1 cells
+        let m = &second.mesh;
+        let on_interface = (0..m.n_internal_faces())
+            .filter(|&f| (m.face_centers()[f].x() - 0.5).abs() < 1e-12)
+            .filter(|&f| m.face_centers()[f].y() < 0.5 && m.face_centers()[f].z() < 0.5)
+            .collect::<Vec<_>>();
+        assert_eq!(on_interface.len(), 4);
+        for &f in &on_interface {
+            assert_eq!(m.faces()[f].len(), 4);
+        }
+
+        // The coarse cell beside them is still a hexahedron
+        let x_y = mesh.find_cell(Vector::new(0.75, 0.75, 0.25)).unwrap();
+        let third = second.refine(&[second.children(first.children(x_y).start).start]);
+        check_levels(&third.unwrap());
+    }
+
+    #[test]
+    fn finer_neighbors_across_an_edge_are_kept() {
+        let (mesh, patches) = unit_box(2);
+        let find = |m: &PrimitiveMesh, x, y, z| m.find_cell(Vector::new(x, y, z)).unwrap();
+        let ring = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75)].map(|(x, y)| find(&mesh, x, y, 0.25));
+        let first = mesh.refine_hex(&ring, &patches).unwrap();
+
+        // A level-1 cell touching the last coarse cell of the layer along
+        // an edge only
+        let corner = find(&first.mesh, 0.375, 0.375, 0.125);
+        let second = first.refine(&[corner]).unwrap();
+        check_levels(&second);
+
+        // The coarse cell now has a level-2 point on an edge, beside the
+        // level-1 midpoint
+        let coarse = find(&second.mesh, 0.75, 0.75, 0.25);
+        let on_edge = second.mesh.cell_points()[coarse]
+            .iter()
+            .filter(|&&p| {
+                let q = second.mesh.points()[p];
+                (q.x() - 0.5).abs() < 1e-12 && (q.y() - 0.5).abs() < 1e-12
+            })
+            .count();
+        assert_eq!(on_edge, 4);
+        let third = second.refine(&[coarse]).unwrap();
+        check_levels(&third);
+    }
+
+    #[test]
+    fn face_neighbors_stay_within_one_level() {
+        let (mesh, patches) = unit_box(2);
+        let first = mesh.refine_hex(&[0], &patches).unwrap();
+        // The child in the middle of the box faces three coarse cells
+        let middle = first
+            .mesh
+            .find_cell(Vector::new(0.375, 0.375, 0.375))
+            .unwrap();
+        assert!(matches!(
+            first.refine(&[middle]),
+            Err(MeshError::UnbalancedRefinement { .. })
+        ));
+        // Refining it with its coarse neighbors is fine
+        let cells: Vec<usize> = (1..8)
+            .map(|c| first.children(c).start)
+            .chain([middle])
+            .collect();
+        check_levels(&first.refine(&cells).unwrap());
+    }
+
+    #[test]
+    fn non_hex_cells_are_rejected() {
+        let points = vec![
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+        ];
+        let (mesh, patches) =
+            ShapeMesh::new(points, vec![CellShape::Tet([0, 1, 2, 3])], Vec::new())
+                .build()
+                .unwrap();
+        assert!(matches!(
+            mesh.refine_hex(&[0], &patches),
+            Err(MeshError::NotHexahedron { cell: 0 })
+        ));
+    }
+}
@@ -0,0 +1,375 @@
+//! Cell renumbering for bandwidth reduction.
+//!
+//! Meshes from external generators often number cells arbitrarily, which
+//! spreads matrix coefficients far from the diagonal and hurts both cache
+//! behavior and preconditioner quality. [`PrimitiveMesh::renumber`] permutes
+//! the cells and returns the new mesh together with the maps needed to carry
+//! existing fields over.
+//!
+//! Only cells and internal faces move. Points keep their numbering, and
+//! boundary faces keep their positions, so patch face ranges stay valid.
+
+use std::collections::VecDeque;
+
+use dugong_types::tensor::Vector;
+
+use crate::compact_list::CompactList;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// Cell ordering produced by [`PrimitiveMesh::renumber`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenumberMethod {
+    /// Reverse Cuthill–McKee over [`PrimitiveMesh::cell_cells`], started
+    /// from a pseudo-peripheral cell of each connected region.
+    #[default]
+    ReverseCuthillMcKee,
+    /// Morton (Z-order) space-filling curve through the cell centers.
+    Morton,
+}
+
+/// A renumbered mesh and the maps relating it to the original.
+///
+/// Maps named `*_map` are indexed by the new number and hold the old one;
+/// `reverse_*_map` go the other way.
+pub struct Renumbering {
+    /// The renumbered mesh.
+    pub mesh: PrimitiveMesh,
+    /// `cell_map[new] == old`.
+    pub cell_map: Vec<usize>,
+    /// `reverse_cell_map[old] == new`.
+    pub reverse_cell_map: Vec<usize>,
+    /// `face_map[new] == old`. Boundary faces map onto themselves.
+    pub face_map: Vec<usize>,
+    /// `reverse_face_map[old] == new`.
+    pub reverse_face_map: Vec<usize>,
+    /// `flipped[new]` is `true` if the face was reversed to keep the owner
+    /// lower than the neighbor. Face fluxes must change sign there.
+    pub flipped: Vec<bool>,
+}
+
+impl Renumbering {
+    /// Reorders per-cell values of the original mesh to the new numbering.
+    pub fn map_cell_values<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        self.cell_map
+            .iter()
+            .map(|&old| values[old].clone())
+            .collect()
+    }
+
+    /// Reorders per-face values of the original mesh to the new numbering.
+    ///
+    /// Values are moved, not negated; fluxes on [`flipped`](Self::flipped)
+    /// faces need their sign changed by the caller.
+    pub fn map_face_values<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        self.face_map
+            .iter()
+            .map(|&old| values[old].clone())
+            .collect()
+    }
+}
+
+impl PrimitiveMesh {
+    /// Returns the matrix bandwidth, the largest `neighbor - owner` over the
+    /// internal faces (0 if there are none).
+    pub fn bandwidth(&self) -> usize {
+        self.owner()
+            .iter()
+            .zip(self.neighbor())
+            .map(|(&o, &n)| o.abs_diff(n))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Renumbers the cells with `method`.
+    ///
+    /// Internal faces are re-sorted into upper-triangular order; faces whose
+    /// owner would exceed their neighbor are reversed. Points and boundary
+    /// faces are unchanged apart from the new owner numbers.
+    pub fn renumber(&self, method: RenumberMethod) -> Renumbering {
+        let cell_map = match method {
+            RenumberMethod::ReverseCuthillMcKee => reverse_cuthill_mckee(self.cell_cells()),
+            RenumberMethod::Morton => morton_order(self.cell_centers()),
+        };
+        self.renumber_cells(cell_map)
+    }
+
+    /// Applies the cell order `cell_map` (`cell_map[new] == old`).
+    fn renumber_cells(&self, cell_map: Vec<usize>) -> Renumbering {
+        let mut reverse_cell_map = vec![0; cell_map.len()];
+        for (new, &old) in cell_map.iter().enumerate() {
+            reverse_cell_map[old] = new;
+        }
+
+        let n_internal = self.n_internal_faces();
+        let owner = self.owner();
+        let neighbor = self.neighbor();
+        // (new owner, new neighbor, old face), flipped where needed
+        let mut internal: Vec<(usize, usize, usize)> = (0..n_internal)
+            .map(|f| {
+                let o = reverse_cell_map[owner[f]];
+                let n = reverse_cell_map[neighbor[f]];
+                (o.min(n), o.max(n), f)
+            })
+            .collect();
+        internal.sort_unstable();
+
+        let n_faces = self.n_faces();
+        let mut faces = Vec::with_capacity(n_faces);
+        let mut new_owner = Vec::with_capacity(n_faces);
+        let mut new_neighbor = Vec::with_capacity(n_internal);
+        let mut face_map = Vec::with_capacity(n_faces);
+        let mut flipped = vec![false; n_faces];
+        for (new, &(o, n, old)) in internal.iter().enumerate() {
+            let mut face = self.faces()[old].to_vec();
+            if reverse_cell_map[owner[old]] != o {
+                face.reverse();
+                flipped[new] = true;
+            }
+            faces.push(face);
+            new_owner.push(o);
+            new_neighbor.push(n);
+            face_map.push(old);
+        }
+        for f in n_internal..n_faces {
+            faces.push(self.faces()[f].to_vec());
+            new_owner.push(reverse_cell_map[owner[f]]);
+            face_map.push(f);
+        }
+        let mut reverse_face_map = vec![0; n_faces];
+        for (new, &old) in face_map.iter().enumerate() {
+            reverse_face_map[old] = new;
+        }
+
+        let mesh = PrimitiveMesh::new(self.points().to_vec(), faces, new_owner, new_neighbor)
+            .expect("renumbering preserves index ranges");
+        Renumbering {
+            mesh,
+            cell_map,
+            reverse_cell_map,
+            face_map,
+            reverse_face_map,
+            flipped,
+        }
+    }
+}
+
+/// Returns the reverse Cuthill–McKee order (`order[new] == old`) of the
+/// graph `adjacency`, handling each connected component in turn.
+fn reverse_cuthill_mckee(adjacency: &CompactList) -> Vec<usize> {
+    let n = adjacency.len();
+    let degree = |c: usize| adjacency[c].len();
+    let mut order = Vec::with_capacity(n);
+    let mut visited = vec![false; n];
+
+    // Seed candidates by ascending degree so each component starts low
+    let mut seeds: Vec<usize> = (0..n).collect();
+    seeds.sort_by_key(|&c| degree(c));
+
+    for &seed in &seeds {
+        if visited[seed] {
+            continue;
+        }
+        let start = pseudo_peripheral(adjacency, seed);
+        let first = order.len();
+        visited[start] = true;
+        order.push(start);
+        let mut next = first;
+        while next < order.len() {
+            let c = order[next];
+            next += 1;
+            let mut neighbors: Vec<usize> = adjacency[c]
+                .iter()
+                .copied()
+                .filter(|&m| !visited[m])
+                .collect();
+            neighbors.sort_by_key(|&m| (degree(m), m));
+            neighbors.dedup();
+            for m in neighbors {
+                visited[m] = true;
+                order.push(m);
+            }
+        }
+    }
+
+    order.reverse();
+    order
+}
+
+/// Finds a pseudo-peripheral vertex of the component containing `start`
+/// (George–Liu): repeatedly jumps to the lowest-degree vertex of the last
+/// BFS level until the eccentricity stops growing.
+fn pseudo_peripheral(adjacency: &CompactList, start: usize) -> usize {
+    let mut root = start;
+    let (mut last_level, mut eccentricity) = bfs_last_level(adjacency, root);
+    loop {
+        let candidate = last_level
+            .iter()
+            .copied()
+            .min_by_key(|&c| (adjacency[c].len(), c))
+            .unwrap_or(root);
+        let (level, ecc) = bfs_last_level(adjacency, candidate);
+        if ecc <= eccentricity {
+            return root;
+        }
+        root = candidate;
+        last_level = level;
+        eccentricity = ecc;
+    }
+}
+
+/// Returns the vertices at maximum BFS distance from `root` and that
+/// distance.
+fn bfs_last_level(adjacency: &CompactList, root: usize) -> (Vec<usize>, usize) {
+    let mut distance = vec![usize::MAX; adjacency.len()];
+    distance[root] = 0;
+    let mut queue = VecDeque::from([root]);
+    let mut last = vec![root];
+    let mut depth = 0;
+    while let Some(c) = queue.pop_front() {
+        for &m in &adjacency[c] {
+            if distance[m] == usize::MAX {
+                distance[m] = distance[c] + 1;
+                if distance[m] > depth {
+                    depth = distance[m];
+                    last.clear();
+                }
+                last.push(m);
+                queue.push_back(m);
+            }
+        }
+    }
+    (last, depth)
+}
+
+/// Returns the cells sorted along a Morton curve through `centers`
+/// (`order[new] == old`).
+fn morton_order(centers: &[Vector]) -> Vec<usize> {
+    // 21 bits per axis fill a 63-bit key
+    const BITS: u32 = 21;
+    let scale = ((1u64 << BITS) - 1) as f64;
+
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for c in centers {
+        for (k, &x) in c.as_array().iter().enumerate() {
+            min[k] = min[k].min(x);
+            max[k] = max[k].max(x);
+        }
+    }
+    // A single global scale keeps the curve isotropic
+    let extent = (0..3).map(|k| max[k] - min[k]).fold(0.0, f64::max);
+    let quantize = |x: f64, k: usize| {
+        if extent > 0.0 {
+            ((x - min[k]) / extent * scale) as u64
+        } else {
+            0
+        }
+    };
+
+    let mut keyed: Vec<(u64, usize)> = centers
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let a = c.as_array();
+            let key = spread_bits(quantize(a[0], 0))
+                | spread_bits(quantize(a[1], 1)) << 1
+                | spread_bits(quantize(a[2], 2)) << 2;
+            (key, i)
+        })
+        .collect();
+    keyed.sort_unstable();
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Inserts two zero bits between each of the low 21 bits of `x`.
+fn spread_bits(x: u64) -> u64 {
+    let mut x = x & 0x1f_ffff;
+    x = (x | x << 32) & 0x1f_0000_0000_ffff;
+    x = (x | x << 16) & 0x1f_0000_ff00_00ff;
+    x = (x | x << 8) & 0x100f_00f0_0f00_f00f;
+    x = (x | x << 4) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x << 2) & 0x1249_2492_4924_9249;
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit cube mesh with its cells shuffled by a fixed permutation.
+    fn scrambled(nx: usize, ny: usize, nz: usize) -> (PrimitiveMesh, PrimitiveMesh) {
+        let mesh = PrimitiveMesh::unit_cube(nx, ny, nz).unwrap();
+        let n = mesh.n_cells();
+        // Multiplying by a unit modulo n permutes 0..n
+        let step = (n * 3 / 8..n).find(|&s| gcd(s, n) == 1).unwrap_or(1);
+        let cell_map = (0..n).map(|i| i * step % n).collect();
+        let scrambled = mesh.renumber_cells(cell_map).mesh;
+        (mesh, scrambled)
+    }
+
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+
+    fn assert_consistent(r: &Renumbering, original: &PrimitiveMesh) {
+        let mesh = &r.mesh;
+        assert!(mesh.check_topology().is_ok());
+        assert!(mesh.check_geometry().is_ok());
+        for (new, &old) in r.cell_map.iter().enumerate() {
+            assert_eq!(r.reverse_cell_map[old], new);
+            let d = mesh.cell_centers()[new] - original.cell_centers()[old];
+            assert!(d.mag() < 1e-12, "cell {new}");
+        }
+        for (new, &old) in r.face_map.iter().enumerate() {
+            assert_eq!(r.reverse_face_map[old], new);
+            let sign = if r.flipped[new] { -1.0 } else { 1.0 };
+            let a = mesh.face_areas()[new];
+            let b = original.face_areas()[old];
+            assert!((a - b * sign).mag() < 1e-12, "face {new}");
+        }
+    }
+
+    #[test]
+    fn rcm_reduces_bandwidth() {
+        let (_, mesh) = scrambled(8, 4, 2);
+        let before = mesh.bandwidth();
+        let r = mesh.renumber(RenumberMethod::ReverseCuthillMcKee);
+        assert_consistent(&r, &mesh);
+        assert!(
+            r.mesh.bandwidth() < before / 2,
+            "bandwidth {} -> {}",
+            before,
+            r.mesh.bandwidth()
+        );
+        // The natural x-fastest ordering has bandwidth nx * ny = 32
+        assert!(r.mesh.bandwidth() <= 12, "bandwidth {}", r.mesh.bandwidth());
+    }
+
+    #[test]
+    fn morton_orders_octants() {
+        let (_, mesh) = scrambled(4, 4, 4);
+        let r = mesh.renumber(RenumberMethod::Morton);
+        assert_consistent(&r, &mesh);
+        // Each consecutive block of 8 cells fills one 2x2x2 octant
+        for block in r.mesh.cell_centers().chunks(8) {
+            let octant = |c: &Vector| c.as_array().map(|x| (x * 2.0) as usize);
+            assert!(block.iter().all(|c| octant(c) == octant(&block[0])));
+        }
+    }
+
+    #[test]
+    fn field_maps_follow_cells() {
+        let (_, mesh) = scrambled(3, 2, 1);
+        let r = mesh.renumber(RenumberMethod::ReverseCuthillMcKee);
+        let x: Vec<f64> = mesh.cell_centers().iter().map(|c| c.x()).collect();
+        let mapped = r.map_cell_values(&x);
+        for (c, &v) in r.mesh.cell_centers().iter().zip(&mapped) {
+            assert!((c.x() - v).abs() < 1e-12);
+        }
+        let areas = r.map_face_values(mesh.face_areas());
+        assert_eq!(areas.len(), mesh.n_faces());
+        // Boundary faces do not move
+        let n_internal = mesh.n_internal_faces();
+        assert!((n_internal..mesh.n_faces()).all(|f| r.face_map[f] == f));
+    }
+}
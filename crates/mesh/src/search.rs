@@ -0,0 +1,326 @@
+//! Point location: which cell contains a given point.
+//!
+//! Cells are indexed by a bounding volume hierarchy over their axis-aligned
+//! bounding boxes, built on first use. Candidate cells are then tested
+//! exactly by splitting them into tetrahedra, each spanned by the cell
+//! center and one triangle of a face fan around the face center. This is
+//! exact for cells that are star-shaped about their center, which covers
+//! every cell that passes [`check_geometry`](PrimitiveMesh::check_geometry).
+
+use dugong_types::tensor::Vector;
+
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// Maximum number of boxes in a leaf of the tree.
+const LEAF_SIZE: usize = 8;
+
+/// Relative tolerance on tetrahedron barycentric coordinates, so points on
+/// a shared face are found in one of the two cells.
+const BARYCENTRIC_TOLERANCE: f64 = 1e-10;
+
+/// Axis-aligned box as `(min, max)` corners.
+pub(crate) type Aabb = ([f64; 3], [f64; 3]);
+
+enum Node {
+    Leaf {
+        bounds: Aabb,
+        start: usize,
+        end: usize,
+    },
+    Inner {
+        bounds: Aabb,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl Node {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Inner { bounds, .. } => bounds,
+        }
+    }
+}
+
+/// Bounding volume hierarchy over indexed boxes (cells for point location,
+/// wall faces for wall distance).
+pub(crate) struct BoxTree {
+    nodes: Vec<Node>,
+    /// Box indices, reordered so every leaf covers a contiguous range.
+    items: Vec<usize>,
+    /// Margin added to every box when testing containment.
+    margin: f64,
+}
+
+impl BoxTree {
+    /// Builds the tree over `boxes`, item `i` having box `boxes[i]`.
+    pub(crate) fn new(boxes: &[Aabb]) -> Self {
+        let mut tree = Self {
+            nodes: Vec::new(),
+            items: (0..boxes.len()).collect(),
+            margin: 0.0,
+        };
+        if !boxes.is_empty() {
+            tree.build(boxes, 0, boxes.len());
+            let (min, max) = tree.nodes[0].bounds();
+            let diagonal = (0..3).map(|i| (max[i] - min[i]).powi(2)).sum::<f64>();
+            tree.margin = 1e-9 * diagonal.sqrt();
+        }
+        tree
+    }
+
+    /// Appends the node covering `items[start..end]` and returns its index.
+    fn build(&mut self, boxes: &[Aabb], start: usize, end: usize) -> usize {
+        let bounds = self.items[start..end]
+            .iter()
+            .fold(EMPTY_BOX, |acc, &c| union(&acc, &boxes[c]));
+        let index = self.nodes.len();
+        if end - start <= LEAF_SIZE {
+            self.nodes.push(Node::Leaf { bounds, start, end });
+            return index;
+        }
+
+        // Median split along the longest extent of the box centers
+        let center = |c: usize, axis: usize| boxes[c].0[axis] + boxes[c].1[axis];
+        let centers = self.items[start..end].iter().fold(EMPTY_BOX, |acc, &c| {
+            let m = [center(c, 0), center(c, 1), center(c, 2)];
+            union(&acc, &(m, m))
+        });
+        let axis = (0..3)
+            .max_by(|&a, &b| {
+                (centers.1[a] - centers.0[a]).total_cmp(&(centers.1[b] - centers.0[b]))
+            })
+            .unwrap_or(0);
+        let mid = (start + end) / 2;
+        self.items[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            center(a, axis).total_cmp(&center(b, axis))
+        });
+
+        // Reserve the slot, then fill in the children
+        self.nodes.push(Node::Leaf { bounds, start, end });
+        let left = self.build(boxes, start, mid);
+        let right = self.build(boxes, mid, end);
+        self.nodes[index] = Node::Inner {
+            bounds,
+            left,
+            right,
+        };
+        index
+    }
+
+    /// Calls `accept` on each item whose box contains `p` until it returns
+    /// `true`, and returns that item.
+    fn find(&self, p: Vector, mut accept: impl FnMut(usize) -> bool) -> Option<usize> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let p = p.as_array();
+        let mut stack = vec![0];
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if box_distance_sqr(node.bounds(), p) > self.margin * self.margin {
+                continue;
+            }
+            match *node {
+                Node::Leaf { start, end, .. } => {
+                    if let Some(&c) = self.items[start..end].iter().find(|&&c| accept(c)) {
+                        return Some(c);
+                    }
+                }
+                Node::Inner { left, right, .. } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the item minimizing `distance_sqr`, given that it is never
+    /// less than the squared distance from `p` to the item's box.
+    pub(crate) fn nearest(&self, p: Vector, distance_sqr: impl Fn(usize) -> f64) -> Option<usize> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let p = p.as_array();
+        let mut best = (f64::INFINITY, None);
+        let mut stack = vec![0];
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if box_distance_sqr(node.bounds(), p) >= best.0 {
+                continue;
+            }
+            match *node {
+                Node::Leaf { start, end, .. } => {
+                    for &c in &self.items[start..end] {
+                        let d = distance_sqr(c);
+                        if d < best.0 {
+                            best = (d, Some(c));
+                        }
+                    }
+                }
+                Node::Inner { left, right, .. } => {
+                    // Visit the closer child first
+                    let dl = box_distance_sqr(self.nodes[left].bounds(), p);
+                    let dr = box_distance_sqr(self.nodes[right].bounds(), p);
+                    if dl <= dr {
+                        stack.push(right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+            }
+        }
+        best.1
+    }
+}
+
+pub(crate) const EMPTY_BOX: Aabb = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+
+pub(crate) fn union(a: &Aabb, b: &Aabb) -> Aabb {
+    (
+        [0, 1, 2].map(|i| a.0[i].min(b.0[i])),
+        [0, 1, 2].map(|i| a.1[i].max(b.1[i])),
+    )
+}
+
+fn box_distance_sqr((min, max): &Aabb, p: &[f64; 3]) -> f64 {
+    (0..3)
+        .map(|i| (min[i] - p[i]).max(p[i] - max[i]).max(0.0).powi(2))
+        .sum()
+}
+
+/// Returns the bounding box of every cell.
+pub(crate) fn cell_bounding_boxes(mesh: &PrimitiveMesh) -> Vec<Aabb> {
+    let points = mesh.points();
+    mesh.cell_points()
+        .iter()
+        .map(|cell| {
+            cell.iter().fold(EMPTY_BOX, |acc, &p| {
+                let p = *points[p].as_array();
+                union(&acc, &(p, p))
+            })
+        })
+        .collect()
+}
+
+/// Returns `true` if `p` lies in the tetrahedron `a b c d` (either
+/// orientation), allowing a relative tolerance on the boundary.
+fn in_tet(p: Vector, a: Vector, b: Vector, c: Vector, d: Vector) -> bool {
+    let volume = |a: Vector, b: Vector, c: Vector, d: Vector| (b - a).cross(&(c - a)) * (d - a);
+    let total = volume(a, b, c, d);
+    if total == 0.0 {
+        return false;
+    }
+    let tol = -BARYCENTRIC_TOLERANCE;
+    volume(p, b, c, d) / total >= tol
+        && volume(a, p, c, d) / total >= tol
+        && volume(a, b, p, d) / total >= tol
+        && volume(a, b, c, p) / total >= tol
+}
+
+impl PrimitiveMesh {
+    /// Returns `true` if `p` lies inside `cell` or on its boundary.
+    ///
+    /// The cell is decomposed into tetrahedra from its center to the face
+    /// fan triangles, as in [`face_centers`](Self::face_centers).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell >= n_cells()`.
+    pub fn point_in_cell(&self, p: Vector, cell: usize) -> bool {
+        let points = self.points();
+        let cc = self.cell_centers()[cell];
+        self.cell_faces()[cell].iter().any(|&f| {
+            let face = &self.faces()[f];
+            let fc = self.face_centers()[f];
+            (0..face.len()).any(|i| {
+                let a = points[face[i]];
+                let b = points[face[(i + 1) % face.len()]];
+                in_tet(p, cc, fc, a, b)
+            })
+        })
+    }
+
+    /// Returns a cell containing `p`, or `None` if `p` is outside the mesh.
+    ///
+    /// Points on a face shared by two cells are reported in one of them.
+    /// The search index is built on the first call.
+    pub fn find_cell(&self, p: Vector) -> Option<usize> {
+        self.cell_tree().find(p, |c| self.point_in_cell(p, c))
+    }
+
+    /// Returns the cell whose center is nearest to `p`, or `None` if the
+    /// mesh has no cells.
+    pub fn find_nearest_cell(&self, p: Vector) -> Option<usize> {
+        let centers = self.cell_centers();
+        self.cell_tree().nearest(p, |c| (centers[c] - p).mag_sqr())
+    }
+
+    /// Returns the cell containing `p`, falling back to
+    /// [`find_nearest_cell`](Self::find_nearest_cell) for points outside the
+    /// mesh (e.g. probes placed a round-off distance beyond a wall).
+    pub fn find_cell_or_nearest(&self, p: Vector) -> Option<usize> {
+        self.find_cell(p).or_else(|| self.find_nearest_cell(p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::{CartesianBox, CellShape, ShapeMesh};
+
+    #[test]
+    fn finds_cells_of_a_box() {
+        let (mesh, _) = CartesianBox::new(Vector::zero(), Vector::new(2.0, 1.0, 1.0), [8, 5, 3])
+            .build()
+            .unwrap();
+        for (c, &center) in mesh.cell_centers().iter().enumerate() {
+            assert_eq!(mesh.find_cell(center), Some(c));
+            assert_eq!(mesh.find_nearest_cell(center), Some(c));
+        }
+        // Cells are numbered x-fastest: (i, j, k) = (5, 1, 2)
+        let p = Vector::new(1.3, 0.3, 0.9);
+        assert_eq!(mesh.find_cell(p), Some(5 + 8 * (1 + 5 * 2)));
+
+        // On a shared face either cell will do
+        let c = mesh.find_cell(Vector::new(0.5, 0.1, 0.1)).unwrap();
+        assert!(c == 1 || c == 2);
+        // Corners of the domain are inside
+        assert!(mesh.find_cell(Vector::new(2.0, 1.0, 1.0)).is_some());
+    }
+
+    #[test]
+    fn outside_points_fall_back_to_nearest() {
+        let mesh = PrimitiveMesh::unit_cube(4, 4, 4).unwrap();
+        let p = Vector::new(1.0 + 1e-6, 0.1, 0.1);
+        assert_eq!(mesh.find_cell(p), None);
+        assert_eq!(mesh.find_cell_or_nearest(p), Some(3));
+        assert_eq!(
+            mesh.find_nearest_cell(Vector::new(-5.0, 5.0, 5.0)),
+            Some(60)
+        );
+    }
+
+    #[test]
+    fn tetrahedra_are_tested_exactly() {
+        // Two tets sharing the face x + y + z = 1
+        let points = vec![
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(1.0, 1.0, 1.0),
+        ];
+        let cells = vec![CellShape::Tet([0, 1, 2, 3]), CellShape::Tet([1, 2, 3, 4])];
+        let (mesh, _) = ShapeMesh::new(points, cells, Vec::new()).build().unwrap();
+        assert!(mesh.point_in_cell(Vector::new(0.1, 0.1, 0.1), 0));
+        assert!(!mesh.point_in_cell(Vector::new(0.1, 0.1, 0.1), 1));
+        // Inside the bounding box of cell 0 but beyond its slanted face
+        assert!(!mesh.point_in_cell(Vector::new(0.4, 0.4, 0.4), 0));
+        assert!(mesh.point_in_cell(Vector::new(0.4, 0.4, 0.4), 1));
+        assert_eq!(mesh.find_cell(Vector::new(0.9, 0.9, 0.1)), None);
+    }
+}
@@ -0,0 +1,378 @@
+//! Extraction of a sub-mesh from a set of cells.
+//!
+//! [`PrimitiveMesh::subset`] keeps the selected cells together with all
+//! faces and points they use. Internal faces cut by the selection become
+//! boundary faces of a trailing [`OLD_INTERNAL_FACES`] patch, oriented out of
+//! the kept cell. Patches keep their coupling data; a cyclic face must be
+//! kept or dropped together with its partner. The returned [`SubsetMesh`]
+//! records where every cell, face and point came from so that fields can be
+//! carried over.
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::merge::check_patches;
+use crate::patch_def::PatchDef;
+use crate::patches::check_cyclic_pairs;
+use crate::poly_mesh::PolyMesh;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// Name of the patch collecting the internal faces cut by a subset.
+pub const OLD_INTERNAL_FACES: &str = "oldInternalFaces";
+
+/// A sub-mesh and the maps relating it to the original mesh.
+///
+/// Every map is indexed by the new number and holds the old one.
+pub struct SubsetMesh {
+    /// The extracted mesh.
+    pub mesh: PrimitiveMesh,
+    /// Boundary patches of the extracted mesh. The original patches keep
+    /// their order (possibly with size zero) and are followed by
+    /// [`OLD_INTERNAL_FACES`] unless the original already had one, in which
+    /// case the cut faces are appended to it.
+    pub patches: Vec<PatchDef>,
+    /// `cell_map[new] == old`, ascending.
+    pub cell_map: Vec<usize>,
+    /// `face_map[new] == old`.
+    pub face_map: Vec<usize>,
+    /// `flipped[new]` is `true` for cut faces whose kept cell was the
+    /// neighbor, which are reversed to point out of their new owner. Face
+    /// fluxes must change sign there.
+    pub flipped: Vec<bool>,
+    /// `point_map[new] == old`, ascending.
+    pub point_map: Vec<usize>,
+}
+
+impl SubsetMesh {
+    /// Extracts per-cell values of the original mesh for the kept cells.
+    pub fn map_cell_values<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        self.cell_map
+            .iter()
+            .map(|&old| values[old].clone())
+            .collect()
+    }
+
+    /// Extracts per-face values of the original mesh for the kept faces.
+    ///
+    /// Values are moved, not negated; fluxes on [`flipped`](Self::flipped)
+    /// faces need their sign changed by the caller.
+    pub fn map_face_values<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        self.face_map
+            .iter()
+            .map(|&old| values[old].clone())
+            .collect()
+    }
+
+    /// Extracts per-point values of the original mesh for the kept points.
+    pub fn map_point_values<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        self.point_map
+            .iter()
+            .map(|&old| values[old].clone())
+            .collect()
+    }
+}
+
+impl PrimitiveMesh {
+    /// Extracts the cells listed in `cells`.
+    ///
+    /// `patches` describes the boundary of this mesh as in [`PolyMesh`].
+    /// Repeated entries in `cells` are ignored, and kept cells, faces and
+    /// points retain their relative order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a cell index is `>= n_cells()`, `patches` does not
+    /// partition the boundary, or a cyclic face would be kept without its
+    /// partner.
+    pub fn subset(&self, cells: &[usize], patches: &[PatchDef]) -> Result<SubsetMesh, MeshError> {
+        let mut keep = vec![false; self.n_cells()];
+        for &cell in cells {
+            if cell >= self.n_cells() {
+                return Err(MeshError::CellIndexOutOfRange {
+                    cell,
+                    n_cells: self.n_cells(),
+                });
+            }
+            keep[cell] = true;
+        }
+        self.subset_mask(&keep, patches)
+    }
+
+    /// Extracts the cells whose center satisfies `predicate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `patches` does not partition the boundary, or a
+    /// cyclic face would be kept without its partner.
+    pub fn subset_where(
+        &self,
+        patches: &[PatchDef],
+        predicate: impl Fn(Vector) -> bool,
+    ) -> Result<SubsetMesh, MeshError> {
+        let keep: Vec<bool> = self.cell_centers().iter().map(|&c| predicate(c)).collect();
+        self.subset_mask(&keep, patches)
+    }
+
+    fn subset_mask(&self, keep: &[bool], patches: &[PatchDef]) -> Result<SubsetMesh, MeshError> {
+        check_patches(self, patches)?;
+        check_cyclic_pairs(patches, |f| keep[self.owner()[f]] as usize)?;
+
+        let mut new_cell = vec![usize::MAX; self.n_cells()];
+        let mut cell_map = Vec::new();
+        for (c, _) in keep.iter().enumerate().filter(|(_, k)| **k) {
+            new_cell[c] = cell_map.len();
+            cell_map.push(c);
+        }
+
+        let owner = self.owner();
+        let mut face_map = Vec::new();
+        let mut flipped = Vec::new();
+        let mut new_owner = Vec::new();
+        let mut new_neighbor = Vec::new();
+        // (old face, kept cell, flipped)
+        let mut cut = Vec::new();
+        for (f, &n) in self.neighbor().iter().enumerate() {
+            let o = owner[f];
+            match (keep[o], keep[n]) {
+                (true, true) => {
+                    face_map.push(f);
+                    flipped.push(false);
+                    new_owner.push(new_cell[o]);
+                    new_neighbor.push(new_cell[n]);
+                }
+                (true, false) => cut.push((f, new_cell[o], false)),
+                (false, true) => cut.push((f, new_cell[n], true)),
+                (false, false) => {}
+            }
+        }
+
+        let existing = patches.iter().position(|p| p.name == OLD_INTERNAL_FACES);
+        let mut new_patches = Vec::with_capacity(patches.len() + 1);
+        for (i, def) in patches.iter().enumerate() {
+            let start = face_map.len();
+            for f in def.range().filter(|&f| keep[owner[f]]) {
+                face_map.push(f);
+                flipped.push(false);
+                new_owner.push(new_cell[owner[f]]);
+            }
+            if existing == Some(i) {
+                push_cut(&cut, &mut face_map, &mut flipped, &mut new_owner);
+            }
+            new_patches.push(PatchDef {
+                start,
+                size: face_map.len() - start,
+                ..def.clone()
+            });
+        }
+        if existing.is_none() {
+            let start = face_map.len();
+            push_cut(&cut, &mut face_map, &mut flipped, &mut new_owner);
+            new_patches.push(PatchDef::new(
+                OLD_INTERNAL_FACES,
+                "patch",
+                start,
+                face_map.len() - start,
+            ));
+        }
+
+        let mut faces: Vec<Vec<usize>> = face_map
+            .iter()
+            .zip(&flipped)
+            .map(|(&f, &flip)| {
+                let mut face = self.faces()[f].to_vec();
+                if flip {
+                    face.reverse();
+                }
+                face
+            })
+            .collect();
+
+        // Keep the points used by the kept faces, in their original order
+        let mut used = vec![false; self.n_points()];
+        for face in &faces {
+            for &p in face {
+                used[p] = true;
+            }
+        }
+        let mut new_point = vec![usize::MAX; self.n_points()];
+        let mut point_map = Vec::new();
+        for (p, _) in used.iter().enumerate().filter(|(_, u)| **u) {
+            new_point[p] = point_map.len();
+            point_map.push(p);
+        }
+        for face in &mut faces {
+            for p in face.iter_mut() {
+                *p = new_point[*p];
+            }
+        }
+        let points = point_map.iter().map(|&p| self.points()[p]).collect();
+
+        let mesh = PrimitiveMesh::new(points, faces, new_owner, new_neighbor)?;
+        Ok(SubsetMesh {
+            mesh,
+            patches: new_patches,
+            cell_map,
+            face_map,
+            flipped,
+            point_map,
+        })
+    }
+}
+
+impl PolyMesh {
+    /// Extracts the cells of the cell zone named `name`.
+    ///
+    /// Patch descriptions are taken from [`PolyMesh::patch_defs`]; zones are
+    /// not carried over.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if there is no such zone, or a cyclic face would be
+    /// kept without its partner.
+    pub fn subset_zone(&self, name: &str) -> Result<SubsetMesh, MeshError> {
+        let zone = self.cell_zone(name).ok_or_else(|| MeshError::UnknownZone {
+            name: name.to_string(),
+        })?;
+        self.primitive().subset(&zone.indices, &self.patch_defs())
+    }
+}
+
+fn push_cut(
+    cut: &[(usize, usize, bool)],
+    face_map: &mut Vec<usize>,
+    flipped: &mut Vec<bool>,
+    owner: &mut Vec<usize>,
+) {
+    for &(f, cell, flip) in cut {
+        face_map.push(f);
+        flipped.push(flip);
+        owner.push(cell);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::CartesianBox;
+    use crate::patch_def::PatchCoupling;
+    use crate::transform::Transform;
+    use crate::zones::Zone;
+
+    fn box_mesh(nx: usize, ny: usize) -> (PrimitiveMesh, Vec<PatchDef>) {
+        CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [nx, ny, 1])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn middle_cells_get_old_internal_faces() {
+        let (mesh, patches) = box_mesh(4, 1);
+        let sub = mesh.subset(&[2, 1, 2], &patches).unwrap();
+
+        assert_eq!(sub.cell_map, vec![1, 2]);
+        assert_eq!(sub.mesh.n_internal_faces(), 1);
+        assert!(sub.mesh.check_topology().is_ok());
+        assert!(sub.mesh.check_geometry().is_ok());
+
+        let last = sub.patches.last().unwrap();
+        assert_eq!(last.name, OLD_INTERNAL_FACES);
+        assert_eq!(last.size, 2);
+        // Cut faces sit at x = 0.25 and x = 0.75 and point away from the cells
+        let centers = sub.mesh.face_centers();
+        let areas = sub.mesh.face_areas();
+        let mut xs: Vec<_> = last
+            .range()
+            .map(|f| (centers[f].x(), areas[f].x()))
+            .collect();
+        xs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert!((xs[0].0 - 0.25).abs() < 1e-12 && xs[0].1 < 0.0);
+        assert!((xs[1].0 - 0.75).abs() < 1e-12 && xs[1].1 > 0.0);
+        assert_eq!(
+            last.range().filter(|&f| sub.flipped[f]).count(),
+            1,
+            "only the face owned by the dropped cell is flipped"
+        );
+
+        // xMin and xMax lose all their faces but stay in place
+        assert_eq!(sub.patches[0].size, 0);
+        assert_eq!(sub.patches[1].size, 0);
+        assert!(PolyMesh::from_patch_defs(sub.mesh, &sub.patches).is_ok());
+    }
+
+    #[test]
+    fn predicate_subset_maps_fields() {
+        let (mesh, patches) = box_mesh(4, 2);
+        let sub = mesh.subset_where(&patches, |c| c.x() < 0.5).unwrap();
+
+        assert_eq!(sub.mesh.n_cells(), 4);
+        assert_eq!(sub.mesh.n_points(), 18);
+        let volumes = sub.map_cell_values(mesh.cell_volumes());
+        assert_eq!(volumes, sub.mesh.cell_volumes());
+        let points = sub.map_point_values(mesh.points());
+        assert_eq!(points, sub.mesh.points());
+        let face_centers = sub.map_face_values(mesh.face_centers());
+        for (a, b) in face_centers.iter().zip(sub.mesh.face_centers()) {
+            assert!((*a - *b).mag() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn zone_subset_and_errors() {
+        let (mesh, patches) = box_mesh(3, 1);
+        let mut poly = PolyMesh::from_patch_defs(mesh, &patches).unwrap();
+        poly.add_cell_zone(Zone::new("solid", vec![0])).unwrap();
+
+        let sub = poly.subset_zone("solid").unwrap();
+        assert_eq!(sub.mesh.n_cells(), 1);
+        assert_eq!(sub.patches.last().unwrap().size, 1);
+
+        // Subsetting again extends the existing cut patch
+        let again = sub.mesh.subset(&[0], &sub.patches).unwrap();
+        assert_eq!(again.patches.len(), sub.patches.len());
+
+        assert!(matches!(
+            poly.subset_zone("fluid"),
+            Err(MeshError::UnknownZone { .. })
+        ));
+        assert!(matches!(
+            poly.primitive().subset(&[3], &patches),
+            Err(MeshError::CellIndexOutOfRange { cell: 3, .. })
+        ));
+    }
+
+    /// Couples the `xMin` and `xMax` patches of a unit box periodically.
+    fn periodic_in_x(patches: &mut [PatchDef]) {
+        for (p, neighbor_patch, dx) in [(0, "xMax", -1.0), (1, "xMin", 1.0)] {
+            patches[p].patch_type = "cyclic".to_string();
+            patches[p].coupling = Some(PatchCoupling::Cyclic {
+                neighbor_patch: neighbor_patch.to_string(),
+                transform: Transform::Translation {
+                    separation: Vector::new(dx, 0.0, 0.0),
+                },
+            });
+        }
+    }
+
+    #[test]
+    fn cyclic_pairs_are_kept_whole() {
+        let (mesh, mut patches) = box_mesh(4, 2);
+        periodic_in_x(&mut patches);
+        let mut poly = PolyMesh::from_patch_defs(mesh, &patches).unwrap();
+        poly.add_cell_zone(Zone::new("bottom", vec![0, 1, 2, 3]))
+            .unwrap();
+        poly.add_cell_zone(Zone::new("left", vec![0, 4])).unwrap();
+
+        let sub = poly.subset_zone("bottom").unwrap();
+        assert_eq!(sub.patches[0].size, 1);
+        assert_eq!(sub.patches[0].coupling, patches[0].coupling);
+        let sub_poly = PolyMesh::from_patch_defs(sub.mesh, &sub.patches).unwrap();
+        let x_min = sub_poly.patches()[0].as_coupled().unwrap();
+        // Cell 3 seen through the periodic boundary
+        assert!((x_min.neighbor_cell_centers()[0] - Vector::new(-0.125, 0.25, 0.5)).mag() < 1e-12);
+
+        assert!(matches!(
+            poly.subset_zone("left"),
+            Err(MeshError::SplitCyclicPair { ref patch, .. }) if patch == "xMin"
+        ));
+    }
+}
@@ -0,0 +1,212 @@
+//! Extraction of the boundary surface of a mesh.
+//!
+//! [`PrimitiveMesh::boundary_surface`] copies the boundary faces into a
+//! standalone polygonal surface with its own compact point numbering, for
+//! export (STL, OBJ) and for feature detection. Faces keep their mesh
+//! orientation, so normals point out of the domain.
+
+use dugong_types::tensor::Vector;
+
+use crate::compact_list::CompactList;
+use crate::error::MeshError;
+use crate::geometry;
+use crate::merge::check_patches;
+use crate::patch_def::PatchDef;
+use crate::poly_mesh::PolyMesh;
+use crate::primitive_mesh::PrimitiveMesh;
+
+/// The boundary faces of a mesh as a polygonal surface.
+///
+/// Every per-face vector is indexed by the surface face number, which
+/// follows the mesh face order.
+pub struct BoundarySurface {
+    /// Surface point coordinates.
+    pub points: Vec<Vector>,
+    /// Polygons, as indices into `points`, oriented out of the domain.
+    pub faces: CompactList,
+    /// Name of each patch, indexed by the values of `patch_ids`.
+    pub patch_names: Vec<String>,
+    /// Patch index of each face.
+    pub patch_ids: Vec<usize>,
+    /// Mesh cell owning each face.
+    pub owner: Vec<usize>,
+    /// `face_map[surface] == mesh face`.
+    pub face_map: Vec<usize>,
+    /// `point_map[surface] == mesh point`, ascending.
+    pub point_map: Vec<usize>,
+}
+
+impl BoundarySurface {
+    /// Returns the number of faces.
+    pub fn n_faces(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Returns the area vector of each face, as in
+    /// [`PrimitiveMesh::face_areas`].
+    pub fn face_areas(&self) -> Vec<Vector> {
+        self.faces
+            .iter()
+            .map(|face| geometry::compute_face_geometry(&self.points, face).1)
+            .collect()
+    }
+
+    /// Returns the feature edges of the surface as pairs of surface point
+    /// indices, lowest first, in ascending order.
+    ///
+    /// An edge is a feature if the normals of its two faces differ by more
+    /// than `angle` degrees, if the faces belong to different patches, or if
+    /// it does not have exactly two faces (an open or non-manifold edge).
+    pub fn feature_edges(&self, angle: f64) -> Vec<[usize; 2]> {
+        let normals: Vec<Vector> = self
+            .face_areas()
+            .into_iter()
+            .map(|a| {
+                let mag = a.mag();
+                if mag > 0.0 { a / mag } else { a }
+            })
+            .collect();
+        let min_cos = angle.to_radians().cos();
+
+        let edges = geometry::compute_edges(&self.faces);
+        let face_edges = geometry::compute_face_edges(&self.faces, &edges);
+        let edge_faces = geometry::compute_point_lists(&face_edges, edges.len());
+        edges
+            .iter()
+            .zip(&edge_faces)
+            .filter(|&(_, faces)| match *faces {
+                [a, b] => {
+                    self.patch_ids[a] != self.patch_ids[b] || normals[a] * normals[b] < min_cos
+                }
+                _ => true,
+            })
+            .map(|(&edge, _)| edge)
+            .collect()
+    }
+}
+
+impl PrimitiveMesh {
+    /// Extracts the boundary faces as a [`BoundarySurface`].
+    ///
+    /// `patches` describes the boundary of this mesh as in
+    /// [`PolyMesh`]; the surface keeps one patch id per entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `patches` does not partition the boundary faces.
+    pub fn boundary_surface(&self, patches: &[PatchDef]) -> Result<BoundarySurface, MeshError> {
+        check_patches(self, patches)?;
+        let boundary = self.n_internal_faces()..self.n_faces();
+
+        let mut point_map: Vec<usize> = boundary
+            .clone()
+            .flat_map(|f| self.faces()[f].iter().copied())
+            .collect();
+        point_map.sort_unstable();
+        point_map.dedup();
+        let mut reverse_point_map = vec![usize::MAX; self.n_points()];
+        for (new, &old) in point_map.iter().enumerate() {
+            reverse_point_map[old] = new;
+        }
+
+        let faces = boundary
+            .clone()
+            .map(|f| {
+                self.faces()[f]
+                    .iter()
+                    .map(|&p| reverse_point_map[p])
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let patch_ids = patches
+            .iter()
+            .enumerate()
+            .flat_map(|(i, def)| std::iter::repeat_n(i, def.size))
+            .collect();
+
+        Ok(BoundarySurface {
+            points: point_map.iter().map(|&p| self.points()[p]).collect(),
+            faces,
+            patch_names: patches.iter().map(|def| def.name.clone()).collect(),
+            patch_ids,
+            owner: self.owner()[boundary.clone()].to_vec(),
+            face_map: boundary.collect(),
+            point_map,
+        })
+    }
+}
+
+impl PolyMesh {
+    /// Extracts the boundary faces as a [`BoundarySurface`], with patches
+    /// taken from [`PolyMesh::patch_defs`].
+    pub fn boundary_surface(&self) -> BoundarySurface {
+        self.primitive()
+            .boundary_surface(&self.patch_defs())
+            .expect("patches of a PolyMesh partition its boundary")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::CartesianBox;
+
+    fn cube() -> (PrimitiveMesh, Vec<PatchDef>) {
+        CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [2, 2, 2])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn extracts_the_boundary_of_a_box() {
+        let (mesh, patches) = cube();
+        let surface = mesh.boundary_surface(&patches).unwrap();
+
+        assert_eq!(surface.n_faces(), 24);
+        // All points but the middle one
+        assert_eq!(surface.points.len(), 26);
+        assert!(!surface.point_map.contains(&13));
+        assert_eq!(surface.patch_names[1], "xMax");
+        for (i, &f) in surface.face_map.iter().enumerate() {
+            assert_eq!(surface.owner[i], mesh.owner()[f]);
+            assert!(patches[surface.patch_ids[i]].range().contains(&f));
+            let points: Vec<usize> = surface.faces[i]
+                .iter()
+                .map(|&p| surface.point_map[p])
+                .collect();
+            assert_eq!(points, mesh.faces()[f]);
+        }
+
+        // Closed surface
+        let areas = surface.face_areas();
+        let total = areas.iter().fold(Vector::zero(), |sum, &a| sum + a);
+        assert!(total.mag() < 1e-12);
+        let area: f64 = areas.iter().map(|a| a.mag()).sum();
+        assert!((area - 6.0).abs() < 1e-12);
+
+        assert!(
+            mesh.boundary_surface(&patches[1..]).is_err(),
+            "patches must cover the boundary"
+        );
+    }
+
+    #[test]
+    fn detects_feature_edges_by_angle_and_patch() {
+        let (mesh, patches) = cube();
+        let surface = mesh.boundary_surface(&patches).unwrap();
+        // Two segments along each of the 12 cube edges
+        let features = surface.feature_edges(45.0);
+        assert_eq!(features.len(), 24);
+        for &[a, b] in &features {
+            let d = surface.points[b] - surface.points[a];
+            assert!((d.mag() - 0.5).abs() < 1e-12);
+        }
+
+        // With a single patch only the angle decides
+        let n = mesh.n_faces() - mesh.n_internal_faces();
+        let single = [PatchDef::new("walls", "wall", mesh.n_internal_faces(), n)];
+        let surface = mesh.boundary_surface(&single).unwrap();
+        assert_eq!(surface.feature_edges(45.0).len(), 24);
+        assert!(surface.feature_edges(95.0).is_empty());
+    }
+}
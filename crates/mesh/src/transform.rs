@@ -0,0 +1,179 @@
+use dugong_types::tensor::{Tensor, Vector};
+
+/// Geometric transformation between the two sides of a coupled patch.
+///
+/// A transform maps positions and vectors expressed on the *neighbor* side
+/// of a coupling into the frame of the patch that owns the transform.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Transform {
+    /// The two sides coincide (e.g. processor boundaries).
+    #[default]
+    Identity,
+    /// Translational periodicity: `p ↦ p + separation`.
+    Translation {
+        /// Offset added to neighbor-side positions.
+        separation: Vector,
+    },
+    /// Rotational periodicity about `origin`: `p ↦ origin + R · (p - origin)`.
+    Rotation {
+        /// Orthogonal rotation tensor `R`.
+        rotation: Tensor,
+        /// A point on the rotation axis.
+        origin: Vector,
+    },
+}
+
+impl Transform {
+    /// Returns the right-handed rotation by `angle` radians about the axis
+    /// through `origin` along `axis`.
+    ///
+    /// `axis` need not be normalized but must be non-zero.
+    pub fn rotation(axis: Vector, angle: f64, origin: Vector) -> Transform {
+        Transform::Rotation {
+            rotation: rotation_tensor(axis, angle),
+            origin,
+        }
+    }
+
+    /// Returns the unit axis and the angle in radians of a
+    /// [`Transform::Rotation`], with the angle in `[0, π]`; `None` for other
+    /// transforms. A rotation by zero returns the z axis.
+    pub fn axis_angle(&self) -> Option<(Vector, f64)> {
+        let Transform::Rotation { rotation: r, .. } = self else {
+            return None;
+        };
+        let angle = ((r.trace() - 1.0) / 2.0).clamp(-1.0, 1.0).acos();
+        // Twice the axis times sin(angle)
+        let skew = Vector::new(r.zy() - r.yz(), r.xz() - r.zx(), r.yx() - r.xy());
+        let axis = if skew.mag() > 1e-9 {
+            skew / skew.mag()
+        } else if angle < std::f64::consts::FRAC_PI_2 {
+            Vector::new(0.0, 0.0, 1.0)
+        } else {
+            // Half turn: row i of (R + I) / 2 is k_i times the axis k
+            let m = r.as_array();
+            let i = (0..3)
+                .max_by(|&a, &b| m[4 * a].total_cmp(&m[4 * b]))
+                .unwrap();
+            let mut row = [m[3 * i], m[3 * i + 1], m[3 * i + 2]];
+            row[i] += 1.0;
+            let k = Vector::new(row[0], row[1], row[2]);
+            k / k.mag()
+        };
+        Some((axis, angle))
+    }
+
+    /// Returns `true` for [`Transform::Identity`].
+    pub fn is_identity(&self) -> bool {
+        matches!(self, Transform::Identity)
+    }
+
+    /// Maps a neighbor-side position into this side's frame.
+    pub fn transform_position(&self, p: Vector) -> Vector {
+        match self {
+            Transform::Identity => p,
+            Transform::Translation { separation } => p + *separation,
+            Transform::Rotation { rotation, origin } => *origin + *rotation * (p - *origin),
+        }
+    }
+
+    /// Maps a neighbor-side direction vector into this side's frame.
+    ///
+    /// Translations leave vectors unchanged.
+    pub fn transform_vector(&self, v: Vector) -> Vector {
+        match self {
+            Transform::Identity | Transform::Translation { .. } => v,
+            Transform::Rotation { rotation, .. } => *rotation * v,
+        }
+    }
+
+    /// Returns the inverse transform, mapping this side into the neighbor's
+    /// frame.
+    ///
+    /// The rotation tensor is assumed to be orthogonal, so its inverse is its
+    /// transpose.
+    pub fn inverse(&self) -> Transform {
+        match self {
+            Transform::Identity => Transform::Identity,
+            Transform::Translation { separation } => Transform::Translation {
+                separation: -*separation,
+            },
+            Transform::Rotation { rotation, origin } => Transform::Rotation {
+                rotation: rotation.transpose(),
+                origin: *origin,
+            },
+        }
+    }
+}
+
+/// Returns the tensor of the right-handed rotation by `angle` radians about
+/// `axis` (Rodrigues' formula).
+pub(crate) fn rotation_tensor(axis: Vector, angle: f64) -> Tensor {
+    let k = axis / axis.mag();
+    let (sin, cos) = angle.sin_cos();
+    let cross = Tensor::new(0.0, -k.z(), k.y(), k.z(), 0.0, -k.x(), -k.y(), k.x(), 0.0);
+    Tensor::identity() * cos + cross * sin + k.outer(&k) * (1.0 - cos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translation_moves_positions_but_not_vectors() {
+        let t = Transform::Translation {
+            separation: Vector::new(1.0, 0.0, 0.0),
+        };
+        let p = Vector::new(0.5, 2.0, 0.0);
+        assert_eq!(t.transform_position(p), Vector::new(1.5, 2.0, 0.0));
+        assert_eq!(t.transform_vector(p), p);
+        assert_eq!(t.inverse().transform_position(t.transform_position(p)), p);
+    }
+
+    #[test]
+    fn rotation_about_origin_and_inverse() {
+        // 90 degrees about the z axis through (1, 0, 0)
+        let t = Transform::Rotation {
+            rotation: Tensor::new(0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0),
+            origin: Vector::new(1.0, 0.0, 0.0),
+        };
+        let p = Vector::new(2.0, 0.0, 3.0);
+        assert!((t.transform_position(p) - Vector::new(1.0, 1.0, 3.0)).mag() < 1e-15);
+        assert!(
+            (t.transform_vector(Vector::new(1.0, 0.0, 0.0)) - Vector::new(0.0, 1.0, 0.0)).mag()
+                < 1e-15
+        );
+        let back = t.inverse().transform_position(t.transform_position(p));
+        assert!((back - p).mag() < 1e-15);
+        assert!(Transform::default().is_identity());
+    }
+
+    #[test]
+    fn rotation_from_axis_and_angle() {
+        // The same quarter turn, from an unnormalized axis
+        let p = Vector::new(2.0, 0.0, 3.0);
+        let axis_angle = Transform::rotation(
+            Vector::new(0.0, 0.0, 2.0),
+            std::f64::consts::FRAC_PI_2,
+            Vector::new(1.0, 0.0, 0.0),
+        );
+        assert!((axis_angle.transform_position(p) - Vector::new(1.0, 1.0, 3.0)).mag() < 1e-15);
+    }
+
+    #[test]
+    fn axis_angle_recovers_the_rotation() {
+        let origin = Vector::new(1.0, 2.0, 3.0);
+        let axis = Vector::new(1.0, -2.0, 2.0) / 3.0;
+        for angle in [0.3, 2.0, std::f64::consts::PI] {
+            let t = Transform::rotation(axis, angle, origin);
+            let (a, theta) = t.axis_angle().unwrap();
+            assert!((theta - angle).abs() < 1e-12);
+            let rebuilt = Transform::rotation(a, theta, origin);
+            let p = Vector::new(0.5, -1.0, 4.0);
+            assert!((rebuilt.transform_position(p) - t.transform_position(p)).mag() < 1e-12);
+        }
+        let still = Transform::rotation(axis, 0.0, origin).axis_angle().unwrap();
+        assert_eq!(still.1, 0.0);
+        assert_eq!(Transform::Identity.axis_angle(), None);
+    }
+}
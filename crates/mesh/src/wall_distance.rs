@@ -0,0 +1,439 @@
+//! Distance from cell centers to the nearest wall.
+//!
+//! Turbulence models such as k-ω SST need the wall distance `y` in every
+//! cell. [`PrimitiveMesh::wall_distance`] computes it for an arbitrary set
+//! of boundary faces, and [`PolyMesh::wall_distance`] for the faces of all
+//! `wall` patches.
+
+use dugong_types::tensor::Vector;
+
+use crate::error::MeshError;
+use crate::patches::WallPolyPatch;
+use crate::poly_mesh::PolyMesh;
+use crate::primitive_mesh::PrimitiveMesh;
+use crate::search::{Aabb, BoxTree, EMPTY_BOX, union};
+
+/// Algorithm used by [`PrimitiveMesh::wall_distance`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WallDistanceMethod {
+    /// Exact distance from each cell center to the nearest point of a wall
+    /// face, searched through a bounding volume hierarchy over the faces.
+    #[default]
+    Exact,
+    /// Approximation from the solution of `∇²φ = -1` with `φ = 0` on the
+    /// walls (Spalding's method): `y = -|∇φ| + √(|∇φ|² + 2φ)`. It is exact
+    /// between parallel plates, cheaper on large meshes, and smooth, but
+    /// overestimates the distance near convex corners.
+    ///
+    /// The equation has no solution in a region of cells not connected to
+    /// any wall face, so a mesh with such a region falls back to
+    /// [`Exact`](Self::Exact).
+    Poisson,
+}
+
+/// Relative residual reduction at which the Poisson solve stops.
+const POISSON_TOLERANCE: f64 = 1e-10;
+
+impl PrimitiveMesh {
+    /// Returns the distance from each cell center to the nearest of
+    /// `wall_faces`, aligned with [`cell_centers`](Self::cell_centers).
+    ///
+    /// A face listed more than once counts as a single wall face.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `wall_faces` is empty or contains a face that is not
+    /// a boundary face.
+    pub fn wall_distance(
+        &self,
+        wall_faces: &[usize],
+        method: WallDistanceMethod,
+    ) -> Result<Vec<f64>, MeshError> {
+        if wall_faces.is_empty() {
+            return Err(MeshError::NoWallFaces);
+        }
+        if let Some(&face) = wall_faces
+            .iter()
+            .find(|&&f| f < self.n_internal_faces() || f >= self.n_faces())
+        {
+            return Err(MeshError::NotBoundaryFace { face });
+        }
+        // Each wall face must enter the Poisson matrix once
+        let mut wall_faces = wall_faces.to_vec();
+        wall_faces.sort_unstable();
+        wall_faces.dedup();
+        Ok(match method {
+            WallDistanceMethod::Exact => self.exact_wall_distance(&wall_faces),
+            WallDistanceMethod::Poisson if self.all_cells_reach_a_wall(&wall_faces) => {
+                self.poisson_wall_distance(&wall_faces)
+            }
+            WallDistanceMethod::Poisson => self.exact_wall_distance(&wall_faces),
+        })
+    }
+
+    fn exact_wall_distance(&self, wall_faces: &[usize]) -> Vec<f64> {
+        let points = self.points();
+        let boxes: Vec<Aabb> = wall_faces
+            .iter()
+            .map(|&f| {
+                self.faces()[f].iter().fold(EMPTY_BOX, |acc, &p| {
+                    let p = *points[p].as_array();
+                    union(&acc, &(p, p))
+                })
+            })
+            .collect();
+        let tree = BoxTree::new(&boxes);
+
+        let face_distance_sqr = |p: Vector, f: usize| {
+            let face = &self.faces()[f];
+            let fc = self.face_centers()[f];
+            (0..face.len())
+                .map(|i| {
+                    let a = points[face[i]];
+                    let b = points[face[(i + 1) % face.len()]];
+                    (closest_point_on_triangle(p, fc, a, b) - p).mag_sqr()
+                })
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        self.cell_centers()
+            .iter()
+            .map(|&c| {
+                tree.nearest(c, |i| face_distance_sqr(c, wall_faces[i]))
+                    .map_or(f64::INFINITY, |i| {
+                        face_distance_sqr(c, wall_faces[i]).sqrt()
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns `true` if every cell is connected to a cell owning one of
+    /// `wall_faces` through internal faces. Otherwise the Poisson matrix is
+    /// singular, and a cell without neighbors or wall faces has a zero
+    /// diagonal.
+    fn all_cells_reach_a_wall(&self, wall_faces: &[usize]) -> bool {
+        let mut reached = vec![false; self.n_cells()];
+        let mut stack: Vec<usize> = wall_faces.iter().map(|&f| self.owner()[f]).collect();
+        while let Some(c) = stack.pop() {
+            if !std::mem::replace(&mut reached[c], true) {
+                stack.extend(self.cell_cells()[c].iter().filter(|&&n| !reached[n]));
+            }
+        }
+        reached.into_iter().all(|r| r)
+    }
+
+    fn poisson_wall_distance(&self, wall_faces: &[usize]) -> Vec<f64> {
+        let n_cells = self.n_cells();
+        let centers = self.cell_centers();
+        let volumes = self.cell_volumes();
+        let face_centers = self.face_centers();
+        let areas = self.face_areas();
+        let owner = self.owner();
+        let neighbor = self.neighbor();
+
+        // Orthogonal-part diffusion coefficient |S|² / (S · d)
+        let coefficient = |s: Vector, d: Vector| (s * s) / (s * d).max(1e-300);
+        let face_coeffs: Vec<f64> = (0..self.n_internal_faces())
+            .map(|f| coefficient(areas[f], centers[neighbor[f]] - centers[owner[f]]))
+            .collect();
+        let mut diag = vec![0.0; n_cells];
+        for (f, &a) in face_coeffs.iter().enumerate() {
+            diag[owner[f]] += a;
+            diag[neighbor[f]] += a;
+        }
+        for &f in wall_faces {
+            diag[owner[f]] += coefficient(areas[f], face_centers[f] - centers[owner[f]]);
+        }
+
+        // A φ = V, with A symmetric positive definite
+        let apply = |x: &[f64], y: &mut [f64]| {
+            for (yi, (d, xi)) in y.iter_mut().zip(diag.iter().zip(x)) {
+                *yi = d * xi;
+            }
+            for (f, &a) in face_coeffs.iter().enumerate() {
+                let (o, n) = (owner[f], neighbor[f]);
+                y[o] -= a * x[n];
+                y[n] -= a * x[o];
+            }
+        };
+        let phi = conjugate_gradient(apply, &diag, volumes);
+
+        // Gauss gradient with φ = 0 on walls and zero gradient elsewhere
+        let mut is_wall = vec![false; self.n_faces() - self.n_internal_faces()];
+        for &f in wall_faces {
+            is_wall[f - self.n_internal_faces()] = true;
+        }
+        let mut grad = vec![Vector::zero(); n_cells];
+        for f in 0..self.n_internal_faces() {
+            let (o, n) = (owner[f], neighbor[f]);
+            let s = areas[f];
+            let w = (s * (centers[n] - face_centers[f])) / (s * (centers[n] - centers[o]));
+            let phi_f = w * phi[o] + (1.0 - w) * phi[n];
+            grad[o] += s * phi_f;
+            grad[n] -= s * phi_f;
+        }
+        for f in self.n_internal_faces()..self.n_faces() {
+            if !is_wall[f - self.n_internal_faces()] {
+                grad[owner[f]] += areas[f] * phi[owner[f]];
+            }
+        }
+
+        grad.iter()
+            .zip(&phi)
+            .zip(volumes)
+            .map(|((&g, &p), &v)| {
+                let g = (g / v).mag();
+                (g * g + 2.0 * p.max(0.0)).sqrt() - g
+            })
+            .collect()
+    }
+}
+
+impl PolyMesh {
+    /// Returns the distance from each cell center to the nearest face of a
+    /// `wall` patch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the mesh has no wall faces.
+    pub fn wall_distance(&self, method: WallDistanceMethod) -> Result<Vec<f64>, MeshError> {
+        let wall_faces: Vec<usize> = self
+            .patches()
+            .iter()
+            .filter(|p| p.patch_type() == WallPolyPatch::TYPE_NAME)
+            .flat_map(|p| p.range())
+            .collect();
+        self.primitive().wall_distance(&wall_faces, method)
+    }
+}
+
+/// Solves `A x = b` for symmetric positive definite `A`, given as the
+/// product `apply(x, y)` computing `y = A x`, with Jacobi preconditioning
+/// by `diag`.
+fn conjugate_gradient(apply: impl Fn(&[f64], &mut [f64]), diag: &[f64], b: &[f64]) -> Vec<f64> {
+    let n = b.len();
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+    let mut x = vec![0.0; n];
+    let mut r = b.to_vec();
+    let mut z: Vec<f64> = r.iter().zip(diag).map(|(r, d)| r / d).collect();
+    let mut p = z.clone();
+    let mut q = vec![0.0; n];
+    let mut rz = dot(&r, &z);
+    let target = POISSON_TOLERANCE * dot(b, b).sqrt();
+
+    for _ in 0..n.max(1) * 10 {
+        if dot(&r, &r).sqrt() <= target {
+            break;
+        }
+        apply(&p, &mut q);
+        let alpha = rz / dot(&p, &q);
+        for i in 0..n {
+            x[i] += alpha * p[i];
+            r[i] -= alpha * q[i];
+            z[i] = r[i] / diag[i];
+        }
+        let rz_new = dot(&r, &z);
+        let beta = rz_new / rz;
+        rz = rz_new;
+        for i in 0..n {
+            p[i] = z[i] + beta * p[i];
+        }
+    }
+    x
+}
+
+/// Returns the point of triangle `a b c` closest to `p`.
+fn closest_point_on_triangle(p: Vector, a: Vector, b: Vector, c: Vector) -> Vector {
+    // Voronoi region tests (Ericson, Real-Time Collision Detection, 5.1.5)
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab * ap;
+    let d2 = ac * ap;
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let d3 = ab * bp;
+    let d4 = ac * bp;
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let d5 = ab * cp;
+    let d6 = ac * cp;
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = va + vb + vc;
+    if denom == 0.0 {
+        // Degenerate triangle: fall back to the nearest vertex
+        return [a, b, c]
+            .into_iter()
+            .min_by(|x, y| (*x - p).mag_sqr().total_cmp(&(*y - p).mag_sqr()))
+            .unwrap_or(a);
+    }
+    a + ab * (vb / denom) + ac * (vc / denom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::CartesianBox;
+    use crate::patch_def::PatchDef;
+
+    /// Channel `[0, 2] × [0, 1] × [0, 1]` with walls at y = 0 and y = 1.
+    fn channel(ny: usize) -> PolyMesh {
+        let (mesh, patches) =
+            CartesianBox::new(Vector::zero(), Vector::new(2.0, 1.0, 1.0), [4, ny, 1])
+                .build()
+                .unwrap();
+        let patches: Vec<PatchDef> = patches
+            .into_iter()
+            .map(|mut p| {
+                if p.name.starts_with('y') {
+                    p.patch_type = "wall".to_string();
+                }
+                p
+            })
+            .collect();
+        PolyMesh::from_patch_defs(mesh, &patches).unwrap()
+    }
+
+    #[test]
+    fn exact_distance_in_a_channel() {
+        let mesh = channel(10);
+        let y = mesh.wall_distance(WallDistanceMethod::Exact).unwrap();
+        for (c, &d) in mesh.cell_centers().iter().zip(&y) {
+            assert!((d - c.y().min(1.0 - c.y())).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn exact_distance_to_box_walls() {
+        let (mesh, patches) =
+            CartesianBox::new(Vector::zero(), Vector::new(1.0, 1.0, 1.0), [5, 5, 5])
+                .build()
+                .unwrap();
+        let walls: Vec<usize> = patches.iter().flat_map(|p| p.range()).collect();
+        let y = mesh
+            .wall_distance(&walls, WallDistanceMethod::Exact)
+            .unwrap();
+        for (c, &d) in mesh.cell_centers().iter().zip(&y) {
+            let expected = c
+                .as_array()
+                .iter()
+                .map(|&x| x.min(1.0 - x))
+                .fold(f64::INFINITY, f64::min);
+            assert!((d - expected).abs() < 1e-12);
+        }
+
+        // Closest points at a corner and on an edge of a triangle
+        let (a, b, c) = (
+            Vector::zero(),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+        );
+        let corner = closest_point_on_triangle(Vector::new(2.0, -1.0, 3.0), a, b, c);
+        assert!((corner - b).mag() < 1e-12);
+        let edge = closest_point_on_triangle(Vector::new(0.5, -1.0, 1.0), a, b, c);
+        assert!((edge - Vector::new(0.5, 0.0, 0.0)).mag() < 1e-12);
+    }
+
+    #[test]
+    fn poisson_distance_approximates_channel() {
+        let mesh = channel(20);
+        let y = mesh.wall_distance(WallDistanceMethod::Poisson).unwrap();
+        for (c, &d) in mesh.cell_centers().iter().zip(&y) {
+            let exact = c.y().min(1.0 - c.y());
+            assert!((d - exact).abs() < 0.01, "y = {}, d = {d}", c.y());
+        }
+
+        // Repeated wall faces do not change the result
+        let walls: Vec<usize> = mesh
+            .patches()
+            .iter()
+            .filter(|p| p.patch_type() == WallPolyPatch::TYPE_NAME)
+            .flat_map(|p| p.start()..p.start() + p.size())
+            .collect();
+        let repeated: Vec<usize> = walls.iter().chain(&walls[..5]).copied().collect();
+        let primitive = mesh.primitive();
+        assert_eq!(
+            primitive
+                .wall_distance(&repeated, WallDistanceMethod::Poisson)
+                .unwrap(),
+            primitive
+                .wall_distance(&walls, WallDistanceMethod::Poisson)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn poisson_falls_back_to_exact_without_a_wall_in_a_region() {
+        // Two cells with walls all around, and a lone cell far away with
+        // none, whose Poisson equation has no solution
+        let (walled, walled_patches) =
+            CartesianBox::new(Vector::zero(), Vector::new(2.0, 1.0, 1.0), [2, 1, 1])
+                .build()
+                .unwrap();
+        let (lone, lone_patches) = CartesianBox::new(
+            Vector::new(4.0, 0.0, 0.0),
+            Vector::new(5.0, 1.0, 1.0),
+            [1, 1, 1],
+        )
+        .build()
+        .unwrap();
+        let lone_patches: Vec<PatchDef> = lone_patches
+            .into_iter()
+            .map(|p| PatchDef {
+                name: format!("lone_{}", p.name),
+                ..p
+            })
+            .collect();
+        let (mesh, patches) = walled
+            .merge(&walled_patches, &lone, &lone_patches, 1e-9)
+            .unwrap();
+        let walls: Vec<usize> = patches
+            .iter()
+            .filter(|p| !p.name.starts_with("lone"))
+            .flat_map(|p| p.range())
+            .collect();
+
+        let poisson = mesh
+            .wall_distance(&walls, WallDistanceMethod::Poisson)
+            .unwrap();
+        assert!(poisson.iter().all(|d| d.is_finite()));
+        assert_eq!(
+            poisson,
+            mesh.wall_distance(&walls, WallDistanceMethod::Exact)
+                .unwrap()
+        );
+        // The lone cell is 2.5 from the nearest wall at x = 2
+        assert!((poisson[2] - 2.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rejects_missing_or_internal_wall_faces() {
+        let mesh = PrimitiveMesh::unit_cube(2, 2, 2).unwrap();
+        assert!(matches!(
+            mesh.wall_distance(&[], WallDistanceMethod::Exact),
+            Err(MeshError::NoWallFaces)
+        ));
+        assert!(matches!(
+            mesh.wall_distance(&[0], WallDistanceMethod::Poisson),
+            Err(MeshError::NotBoundaryFace { face: 0 })
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error::MeshError;
+
+/// A named subset of cells or points (OpenFOAM `cellZone` / `pointZone`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Zone {
+    /// Zone name.
+    pub name: String,
+    /// Cell or point indices in the zone.
+    pub indices: Vec<usize>,
+}
+
+impl Zone {
+    /// Creates a zone from its name and member indices.
+    pub fn new(name: impl Into<String>, indices: Vec<usize>) -> Self {
+        Self {
+            name: name.into(),
+            indices,
+        }
+    }
+
+    /// Returns the number of members.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns `true` if the zone has no members.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+/// A named subset of faces with orientation flags (OpenFOAM `faceZone`).
+///
+/// `flip_map[i]` is `true` if face `indices[i]` must be reversed to point in
+/// the zone's orientation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FaceZone {
+    /// Zone name.
+    pub name: String,
+    /// Face indices in the zone.
+    pub indices: Vec<usize>,
+    /// Orientation flag of each face, parallel to `indices`.
+    pub flip_map: Vec<bool>,
+}
+
+impl FaceZone {
+    /// Creates a face zone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `flip_map` and `indices` differ in length.
+    pub fn new(
+        name: impl Into<String>,
+        indices: Vec<usize>,
+        flip_map: Vec<bool>,
+    ) -> Result<Self, MeshError> {
+        let name = name.into();
+        if flip_map.len() != indices.len() {
+            return Err(MeshError::FlipMapLengthMismatch {
+                zone: name,
+                n_indices: indices.len(),
+                n_flips: flip_map.len(),
+            });
+        }
+        Ok(Self {
+            name,
+            indices,
+            flip_map,
+        })
+    }
+
+    /// Creates a face zone in which no face is flipped.
+    pub fn unflipped(name: impl Into<String>, indices: Vec<usize>) -> Self {
+        let flip_map = vec![false; indices.len()];
+        Self {
+            name: name.into(),
+            indices,
+            flip_map,
+        }
+    }
+
+    /// Returns the number of faces.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns `true` if the zone has no faces.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_accessors() {
+        let zone = Zone::new("porous", vec![3, 1, 4]);
+        assert_eq!(zone.name, "porous");
+        assert_eq!(zone.len(), 3);
+        assert_eq!(zone.indices[2], 4);
+        assert!(Zone::new("none", vec![]).is_empty());
+    }
+
+    #[test]
+    fn face_zone_checks_flip_map_length() {
+        let zone = FaceZone::new("baffle", vec![0, 2], vec![false, true]).unwrap();
+        assert_eq!(zone.flip_map, vec![false, true]);
+        assert_eq!(FaceZone::unflipped("b", vec![5]).flip_map, vec![false]);
+        let bad = FaceZone::new("baffle", vec![0, 2], vec![true]);
+        assert!(matches!(
+            bad,
+            Err(MeshError::FlipMapLengthMismatch {
+                n_indices: 2,
+                n_flips: 1,
+                ..
+            })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::tensor::*;
 
@@ -71,8 +72,8 @@ mod tests {
 
     #[test]
     fn test_spherical_tensor_value() {
-        let s = SphericalTensor::new(3.14);
-        assert_eq!(s.value(), 3.14);
+        let s = SphericalTensor::new(2.5);
+        assert_eq!(s.value(), 2.5);
     }
 
     #[test]